@@ -0,0 +1,66 @@
+//! Build metadata for `--version --verbose`
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn main() {
+    // Target triple is only visible to build scripts; forward it to rustc
+    let target = std::env::var("TARGET").unwrap_or_default();
+    println!("cargo:rustc-env=CCS_BUILD_TARGET={target}");
+    // Commit id: supplied by the packager (e.g. CI), else read from git
+    println!("cargo:rerun-if-env-changed=CCS_GIT_COMMIT");
+    println!("cargo:rerun-if-changed=build.rs");
+    let commit = std::env::var("CCS_GIT_COMMIT")
+        .ok()
+        .filter(|c| !c.is_empty());
+    if let Some(commit) = commit.or_else(commit_from_own_checkout) {
+        println!("cargo:rustc-env=CCS_GIT_COMMIT={commit}");
+    }
+}
+
+/// Short HEAD commit of this project's own checkout
+///
+/// `None` when the build directory sits inside some other repository (e.g.
+/// a `cargo install` under a dotfiles `$HOME`), so its commit isn't embedded.
+fn commit_from_own_checkout() -> Option<String> {
+    let manifest_dir = PathBuf::from(std::env::var_os("CARGO_MANIFEST_DIR")?);
+    // crates/claude-code-statusline-cli -> workspace root
+    let workspace_root = manifest_dir.parent()?.parent()?.canonicalize().ok()?;
+    let toplevel = git(&manifest_dir, &["rev-parse", "--show-toplevel"])?;
+    if Path::new(&toplevel).canonicalize().ok()? != workspace_root {
+        return None;
+    }
+    // Rebuild when HEAD moves: on checkout, on commits to the branch, and
+    // when the branch ref is packed (`git pack-refs`, `git gc`)
+    let mut watched = vec!["HEAD".to_string(), "packed-refs".to_string()];
+    watched.extend(git(&manifest_dir, &["symbolic-ref", "-q", "HEAD"]));
+    for name in watched {
+        let Some(path) = git(&manifest_dir, &["rev-parse", "--git-path", &name]) else {
+            continue;
+        };
+        // Relative paths are relative to the directory git ran in; a missing
+        // file would rerun on every build, so watch where it would appear
+        let path = manifest_dir.join(path);
+        let path = if path.exists() {
+            path
+        } else {
+            path.parent()?.to_path_buf()
+        };
+        println!("cargo:rerun-if-changed={}", path.display());
+    }
+    git(&manifest_dir, &["rev-parse", "--short", "HEAD"])
+}
+
+/// Trimmed stdout of a successful `git` invocation in `dir`
+fn git(dir: &Path, args: &[&str]) -> Option<String> {
+    let out = Command::new("git")
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    let text = String::from_utf8(out.stdout).ok()?.trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
@@ -1,15 +1,135 @@
 use anyhow::Result;
-use clap::{Parser, Subcommand};
-use std::io::{self, Read};
+use clap::{Parser, Subcommand, ValueEnum};
+use std::io::{self, IsTerminal, Read};
 
 /// Command line interface arguments structure (placeholder for future subcommands)
 #[derive(Parser)]
 #[command(name = env!("CARGO_PKG_NAME"))]
 #[command(version = env!("CARGO_PKG_VERSION"))]
 #[command(about = env!("CARGO_PKG_DESCRIPTION"))]
+#[command(disable_version_flag = true)]
 struct Cli {
     #[command(subcommand)]
     command: Option<Command>,
+
+    /// Print version (add --verbose for build metadata)
+    #[arg(short = 'V', long)]
+    version: bool,
+
+    /// With --version: include features, commit and target
+    #[arg(long, requires = "version")]
+    verbose: bool,
+
+    /// Exit with a nonzero status when the config fails to load or validate
+    #[arg(long)]
+    strict: bool,
+
+    /// Read one JSON object per line and print one status line per input
+    #[arg(long)]
+    batch: bool,
+
+    /// Skip all git modules for this run, as if they were disabled in config
+    #[arg(long)]
+    no_git: bool,
+
+    /// Write the status line to this file (atomically) instead of stdout
+    #[arg(long, value_name = "PATH", conflicts_with = "batch")]
+    output: Option<std::path::PathBuf>,
+
+    /// End the status line with a newline (same as `trailing_newline = true`)
+    #[arg(long)]
+    newline: bool,
+
+    /// Print the parsed input as pretty JSON to stderr before rendering
+    #[arg(long, conflicts_with = "batch")]
+    dump_input: bool,
+
+    /// Print the render time in milliseconds instead of the status line
+    /// (`--measure=verbose` adds one line per module); fails instead of
+    /// printing a fallback line when there is nothing to measure
+    #[arg(
+        long,
+        value_enum,
+        value_name = "DETAIL",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "total",
+        conflicts_with_all = ["batch", "output"]
+    )]
+    measure: Option<Measure>,
+
+    /// Only emit errors on stderr (no warnings, even with `debug = true`)
+    #[arg(long, global = true)]
+    quiet: bool,
+
+    /// Override `command_timeout` for this run (milliseconds, 50..=600000);
+    /// takes precedence over `CCS_TIMEOUT`
+    #[arg(long, global = true, value_name = "MS", value_parser = parse_timeout)]
+    timeout: Option<u64>,
+
+    /// Format of diagnostics written to stderr
+    #[arg(long, value_enum, default_value_t = LogFormat::Text)]
+    log_format: LogFormat,
+}
+
+/// Detail printed by `--measure`
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Measure {
+    /// Total render time only
+    Total,
+    /// `total` plus a `<module> <ms>` line per module
+    Verbose,
+}
+
+impl Measure {
+    /// Print `measured` to stdout, durations in milliseconds
+    fn print(self, measured: &claude_code_statusline_core::RenderMeasurement) {
+        let ms = |d: std::time::Duration| format!("{:.3}", d.as_secs_f64() * 1000.0);
+        match self {
+            Measure::Total => println!("{}", ms(measured.total)),
+            Measure::Verbose => {
+                println!("total {}", ms(measured.total));
+                for (name, elapsed) in &measured.modules {
+                    println!("{name} {}", ms(*elapsed));
+                }
+            }
+        }
+    }
+}
+
+/// Diagnostic output format for stderr
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum LogFormat {
+    /// Human-readable lines (default)
+    Text,
+    /// One JSON object per line with `level`, `message`, and context fields
+    Json,
+}
+
+impl LogFormat {
+    /// Install the tracing subscriber for this format at `level`
+    fn init_tracing(self, level: &str) {
+        let builder = tracing_subscriber::fmt()
+            .with_env_filter(level)
+            .with_writer(std::io::stderr);
+        let _ = match self {
+            LogFormat::Text => builder.with_target(false).try_init(),
+            LogFormat::Json => builder
+                .json()
+                .flatten_event(true)
+                .with_current_span(false)
+                .with_span_list(false)
+                .try_init(),
+        };
+    }
+
+    /// Report an error; text mode also prints a plain `msg: err` line
+    fn report_error(self, msg: &str, err: &dyn std::fmt::Display) {
+        tracing::error!(error = %err, "{msg}");
+        if self == LogFormat::Text {
+            eprintln!("{msg}: {err}");
+        }
+    }
 }
 
 #[derive(Subcommand)]
@@ -25,6 +145,21 @@ enum Command {
         /// Validate current config
         #[arg(long)]
         validate: bool,
+        /// Print JSON Schema for the config file
+        #[arg(long)]
+        schema: bool,
+        /// Validate, render a sample input, and exit nonzero on errors or warnings
+        #[arg(long)]
+        check: bool,
+        /// Print a built-in preset (TOML)
+        #[arg(long, value_name = "NAME")]
+        preset: Option<String>,
+        /// Print the effective config after presets and overrides (TOML)
+        #[arg(long)]
+        effective: bool,
+        /// Write the default config to a writable config location
+        #[arg(long)]
+        init: bool,
     },
     /// Inspect module registry
     Modules {
@@ -35,12 +170,427 @@ enum Command {
         #[arg(long)]
         enabled: bool,
     },
+    /// Render every registered module against sample data
+    #[cfg(feature = "demo")]
+    Demo,
+}
+
+/// Version line for `--version`; `verbose` appends `key: value` build metadata
+fn version_string(verbose: bool) -> String {
+    let mut out = format!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+    if verbose {
+        let features = claude_code_statusline_core::enabled_features();
+        let features = if features.is_empty() {
+            "none".to_string()
+        } else {
+            features.join(",")
+        };
+        out.push_str(&format!("\nfeatures: {features}"));
+        out.push_str(&format!(
+            "\ncommit: {}",
+            option_env!("CCS_GIT_COMMIT").unwrap_or("unknown")
+        ));
+        out.push_str(&format!("\ntarget: {}", env!("CCS_BUILD_TARGET")));
+    }
+    out
+}
+
+/// Built-in input used by `config --check` to exercise rendering (and by
+/// `--no-git` and `modules --enabled` to instantiate modules)
+const CHECK_SAMPLE_INPUT: &str = r#"{
+    "hook_event_name": "Status",
+    "session_id": "check-session",
+    "cwd": ".",
+    "model": { "id": "claude-opus-4-1", "display_name": "Opus" },
+    "version": "1.0.0"
+}"#;
+
+/// Shown on stderr when the binary is started without piped input
+#[cfg(feature = "demo")]
+const STDIN_TTY_HINT: &str = "claude-code-statusline reads Claude Code's status JSON from stdin, e.g.\n  \
+     echo '{\"model\":{\"display_name\":\"Opus\"},\"cwd\":\".\"}' | claude-code-statusline\n\
+     Try `claude-code-statusline demo` to preview every module.";
+#[cfg(not(feature = "demo"))]
+const STDIN_TTY_HINT: &str = "claude-code-statusline reads Claude Code's status JSON from stdin, e.g.\n  \
+     echo '{\"model\":{\"display_name\":\"Opus\"},\"cwd\":\".\"}' | claude-code-statusline";
+
+/// Where and how the final status line (or a fallback line) is written
+struct LineSink<'a> {
+    /// `--output` file; stdout when unset
+    path: Option<&'a std::path::Path>,
+    /// Append `\n` (`--newline` or `trailing_newline`)
+    newline: bool,
+}
+
+impl LineSink<'_> {
+    /// Write `line` to the output file, or stdout when unset
+    ///
+    /// The file is replaced atomically (temp file + rename) so readers never
+    /// see a partial line. If that fails the line goes to stdout instead,
+    /// with a warning on stderr.
+    fn emit(&self, line: &str) -> Result<()> {
+        let line = if self.newline {
+            format!("{line}\n")
+        } else {
+            line.to_string()
+        };
+        if let Some(path) = self.path {
+            match write_atomically(path, &line) {
+                Ok(()) => return Ok(()),
+                Err(e) => eprintln!(
+                    "Failed to write {}: {e}; printing to stdout",
+                    path.display()
+                ),
+            }
+        }
+        print!("{line}");
+        io::Write::flush(&mut io::stdout())?;
+        Ok(())
+    }
+}
+
+/// Replace `path` with `contents` via a temp file in the same directory
+fn write_atomically(path: &std::path::Path, contents: &str) -> io::Result<()> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => std::path::Path::new("."),
+    };
+    let mut tmp = tempfile::NamedTempFile::new_in(dir)?;
+    io::Write::write_all(&mut tmp, contents.as_bytes())?;
+    tmp.persist(path).map_err(|e| e.error)?;
+    Ok(())
+}
+
+/// Whether `stdin` is an interactive terminal, where reading would block
+/// until the user types EOF instead of receiving piped JSON
+fn stdin_is_interactive(stdin: &impl IsTerminal) -> bool {
+    stdin.is_terminal()
+}
+
+/// For interactive stdin, write the usage hint to `hint` and `fallback` to
+/// `sink` instead of blocking on a read
+///
+/// Returns whether it did, i.e. whether the caller should stop.
+fn explain_interactive_stdin(
+    interactive: bool,
+    hint: &mut impl io::Write,
+    sink: &LineSink,
+    fallback: &str,
+) -> Result<bool> {
+    if !interactive {
+        return Ok(false);
+    }
+    writeln!(hint, "{STDIN_TTY_HINT}")?;
+    sink.emit(fallback)?;
+    Ok(true)
+}
+
+/// Mark every module that reads git state (and its variants) as disabled
+/// for `--no-git`
+///
+/// Git modules are the registered ones whose
+/// [`requires`](claude_code_statusline_core::modules::Module::requires)
+/// declares `git`.
+fn disable_git_modules(config: &mut claude_code_statusline_core::Config) -> Result<()> {
+    use claude_code_statusline_core::{Context, modules::Registry};
+
+    let registry = Registry::with_defaults();
+    // Requirements don't depend on the input; any context will do
+    let input = claude_code_statusline_core::parse_claude_input(CHECK_SAMPLE_INPUT)?;
+    let context = Context::new(input, config.clone());
+    let is_git = |name: &str| {
+        registry
+            .create(name, &context)
+            .is_some_and(|module| module.requires().git)
+    };
+
+    let mut table = toml::Table::try_from(&*config)?;
+    for name in registry.list().into_iter().filter(|name| is_git(name)) {
+        if let Some(toml::Value::Table(section)) = table.get_mut(name) {
+            section.insert("disabled".into(), toml::Value::Boolean(true));
+        }
+    }
+    let mut disabled: claude_code_statusline_core::Config = toml::Value::Table(table).try_into()?;
+    disabled.variants = std::mem::take(&mut config.variants);
+    for (name, overrides) in disabled.variants.iter_mut() {
+        if is_git(claude_code_statusline_core::parser::split_variant(name).0) {
+            overrides.insert("disabled".into(), toml::Value::Boolean(true));
+        }
+    }
+    *config = disabled;
+    Ok(())
+}
+
+/// Registered modules referenced by `cfg.format` (variants included) whose
+/// config, with variant overrides applied, isn't disabled
+fn enabled_modules(cfg: &claude_code_statusline_core::Config) -> Result<Vec<String>> {
+    use claude_code_statusline_core::{Context, modules::Registry, parser};
+
+    let registry = Registry::with_defaults();
+    let input = parser::parse_claude_input(CHECK_SAMPLE_INPUT)?;
+    let mut enabled = Vec::new();
+    for name in parser::extract_modules_from_format(&cfg.format) {
+        let (module, variant) = parser::split_variant(&name);
+        if !registry.list().contains(&module) {
+            continue;
+        }
+        let module_cfg = match variant {
+            Some(variant) => cfg.with_variant(module, variant)?,
+            None => cfg.clone(),
+        };
+        let context = Context::new(input.clone(), module_cfg);
+        if registry
+            .config(module, &context)
+            .is_none_or(|c| !c.disabled())
+        {
+            enabled.push(name);
+        }
+    }
+    Ok(enabled)
+}
+
+/// Strict config check: returns the process exit code (0 = clean, 1 = problems)
+fn run_check() -> i32 {
+    let cfg = match claude_code_statusline_core::Config::load() {
+        Ok(cfg) => cfg,
+        Err(e) => {
+            eprintln!("Config error: {e}");
+            println!("INVALID");
+            return 1;
+        }
+    };
+    if let Err(e) = cfg.validate() {
+        eprintln!("Config validation error: {e}");
+        println!("INVALID");
+        return 1;
+    }
+    let warnings = cfg.collect_warnings();
+    for w in &warnings {
+        eprintln!("Config warning: {w}");
+    }
+
+    let input = match claude_code_statusline_core::parse_claude_input(CHECK_SAMPLE_INPUT) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("Failed to parse sample input: {e}");
+            return 1;
+        }
+    };
+    match claude_code_statusline_core::Engine::new(cfg).render(&input) {
+        Ok(out) => println!("{out}"),
+        Err(e) => {
+            eprintln!("Render error: {e}");
+            println!("INVALID");
+            return 1;
+        }
+    }
+
+    if warnings.is_empty() {
+        println!("OK");
+        0
+    } else {
+        println!("INVALID");
+        1
+    }
+}
+
+/// `--timeout` value: milliseconds within the bounds [`Config::validate`]
+/// enforces for `command_timeout`
+///
+/// [`Config::validate`]: claude_code_statusline_core::Config::validate
+fn parse_timeout(value: &str) -> std::result::Result<u64, String> {
+    let ms: u64 = value
+        .parse()
+        .map_err(|_| format!("not a number of milliseconds: '{value}'"))?;
+    claude_code_statusline_core::Config {
+        command_timeout: ms,
+        ..Default::default()
+    }
+    .validate()
+    .map_err(|e| match e {
+        claude_code_statusline_core::CoreError::InvalidConfig(msg) => msg,
+        e => e.to_string(),
+    })?;
+    Ok(ms)
+}
+
+/// `config --effective`: the loaded config with every layer applied, as TOML
+///
+/// Header comments name the source file and any environment or `--timeout`
+/// override.
+fn run_effective(timeout: Option<u64>) -> Result<()> {
+    let mut cfg = claude_code_statusline_core::Config::load()?;
+    match claude_code_statusline_core::loaded_config_path() {
+        Some(path) => println!("# Effective config loaded from {}", path.display()),
+        None => println!("# Effective config (no config file found, using defaults)"),
+    }
+    let timeout_env = claude_code_statusline_core::config::TIMEOUT_ENV;
+    if let Ok(value) = std::env::var(timeout_env) {
+        match cfg.apply_timeout_override(&value) {
+            Ok(()) => println!("# command_timeout overridden by {timeout_env}"),
+            Err(e) => eprintln!("Ignoring {timeout_env}: {e}"),
+        }
+    }
+    if let Some(ms) = timeout {
+        cfg.command_timeout = ms;
+        println!("# command_timeout overridden by --timeout");
+    }
+    println!();
+    print!("{}", cfg.to_effective_toml()?);
+    Ok(())
+}
+
+/// `config --init`: write the default config, falling back to another
+/// config directory when the primary one is not writable
+fn run_init() -> Result<()> {
+    if let Some(existing) = claude_code_statusline_core::loaded_config_path() {
+        anyhow::bail!("config already exists at {}", existing.display());
+    }
+    let primary = claude_code_statusline_core::config_path();
+    let Some(path) = claude_code_statusline_core::writable_config_path() else {
+        anyhow::bail!("no writable config location (tried {})", primary.display());
+    };
+    if path != primary {
+        eprintln!(
+            "{} is not writable; using {}",
+            primary.display(),
+            path.display()
+        );
+    }
+    let toml = toml::to_string_pretty(&claude_code_statusline_core::Config::default())?;
+    std::fs::write(&path, toml)?;
+    println!("{}", path.display());
+    Ok(())
+}
+
+/// Create a throwaway git repository with a commit, a modified and an
+/// untracked file so the git modules have something to show
+#[cfg(feature = "demo")]
+fn create_demo_repo(root: &std::path::Path) -> Result<()> {
+    let repo = git2::Repository::init(root)?;
+    let sig = git2::Signature::now("Demo", "demo@example.com")?;
+    std::fs::write(root.join("README.md"), "demo\n")?;
+    let mut index = repo.index()?;
+    index.add_path(std::path::Path::new("README.md"))?;
+    index.write()?;
+    let tree = repo.find_tree(index.write_tree()?)?;
+    let head = repo.commit(Some("HEAD"), &sig, &sig, "initial", &tree, &[])?;
+    repo.branch("main", &repo.find_commit(head)?, true)?;
+    repo.set_head("refs/heads/main")?;
+    std::fs::write(root.join("README.md"), "demo (edited)\n")?;
+    std::fs::write(root.join("notes.txt"), "untracked\n")?;
+    Ok(())
+}
+
+/// Print `module_name: <rendered>` for every registered module
+///
+/// Each module is rendered by the engine on its own (`format = "${name}"`),
+/// so styles and the final reset match a real status line.
+#[cfg(feature = "demo")]
+fn run_demo() -> Result<()> {
+    let cfg = claude_code_statusline_core::Config::load()
+        .map_err(|e| anyhow::Error::new(e).context("Config error"))?;
+    let tmp = tempfile::tempdir()?;
+    let root = tmp.path().join("demo-repo");
+    std::fs::create_dir_all(&root)?;
+    create_demo_repo(&root)?;
+
+    let cwd = root.display().to_string();
+    let json = format!(
+        r#"{{
+        "hook_event_name": "Status",
+        "session_id": "5f2e9a41-7c1d-4e0b-9a3f-demo",
+        "cwd": {cwd:?},
+        "model": {{ "id": "claude-opus-4-1", "display_name": "Opus" }},
+        "workspace": {{ "current_dir": {cwd:?}, "project_dir": {cwd:?} }},
+        "version": "1.0.80",
+        "output_style": {{ "name": "default" }},
+        "exit_status": 1
+    }}"#
+    );
+    let input = claude_code_statusline_core::parse_claude_input(&json)?;
+
+    let reg = claude_code_statusline_core::modules::Registry::with_defaults();
+    for name in reg.list() {
+        let mut module_cfg = cfg.clone();
+        module_cfg.format = format!("${{{name}}}");
+        module_cfg.empty_placeholder.clear();
+        let out = claude_code_statusline_core::Engine::new(module_cfg).render(&input)?;
+        println!("{name}: {out}");
+    }
+    Ok(())
+}
+
+/// `--batch`: render each stdin line as its own input with one shared engine
+///
+/// Blank lines are skipped; a line that is oversized, fails to parse or
+/// fails to render prints its fallback message so output stays one line
+/// per input.
+fn run_batch(config: claude_code_statusline_core::Config, log_format: LogFormat) -> Result<()> {
+    use std::io::{BufRead, Write};
+
+    let max_input = config.max_input_bytes;
+    let messages = config.messages.clone();
+    let engine = claude_code_statusline_core::Engine::new(config)
+        .with_plain_stderr(log_format == LogFormat::Text);
+    let mut stdout = io::stdout().lock();
+    let mut stdin = io::stdin().lock();
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        // At most one byte past the size limit per line; the rest of an
+        // oversized line is skipped so the next input stays aligned
+        let read = (&mut stdin)
+            .take(max_input as u64 + 1)
+            .read_until(b'\n', &mut buf)?;
+        if read == 0 {
+            break;
+        }
+        let line = buf.strip_suffix(b"\n").unwrap_or(&buf);
+        if line.len() > max_input {
+            stdin.skip_until(b'\n')?;
+        }
+        if line.trim_ascii().is_empty() {
+            continue;
+        }
+        let out = match parse_batch_line(line, max_input) {
+            Ok(input) => engine.render(&input).unwrap_or_else(|e| {
+                log_format.report_error("Render error", &e);
+                messages.for_error(&e).to_string()
+            }),
+            Err(e) => {
+                log_format.report_error("Failed to parse JSON", &e);
+                messages.for_error(&e).to_string()
+            }
+        };
+        writeln!(stdout, "{out}")?;
+    }
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Parse one `--batch` line; invalid UTF-8 is reported as invalid JSON
+fn parse_batch_line(
+    line: &[u8],
+    max_input: usize,
+) -> std::result::Result<
+    claude_code_statusline_core::types::claude::ClaudeInput,
+    claude_code_statusline_core::CoreError,
+> {
+    if line.len() > max_input {
+        return Err(claude_code_statusline_core::CoreError::InputTooLarge { max: max_input });
+    }
+    Ok(serde_json::from_slice(line)?)
 }
 
 /// Run the claude-code-statusline CLI: read stdin JSON, render status line, write stdout.
 pub fn run() -> Result<()> {
-    let _cli = Cli::parse();
-    if let Some(cmd) = &_cli.command {
+    let cli = Cli::parse();
+    if cli.version {
+        println!("{}", version_string(cli.verbose));
+        return Ok(());
+    }
+    if let Some(cmd) = &cli.command {
         // Minimal subscriber for subcommands
         let _ = tracing_subscriber::fmt()
             .with_env_filter("error")
@@ -52,6 +602,11 @@ pub fn run() -> Result<()> {
                 path,
                 default,
                 validate,
+                schema,
+                check,
+                preset,
+                effective,
+                init,
             } => {
                 if *path {
                     let path = claude_code_statusline_core::config_path();
@@ -65,6 +620,35 @@ pub fn run() -> Result<()> {
                     println!("{toml}");
                     return Ok(());
                 }
+                if let Some(name) = preset {
+                    let Some(toml) = claude_code_statusline_core::presets::preset_toml(name) else {
+                        anyhow::bail!(
+                            "unknown preset '{name}' (available: {})",
+                            claude_code_statusline_core::presets::names().join(", ")
+                        );
+                    };
+                    print!("{toml}");
+                    return Ok(());
+                }
+                if *schema {
+                    println!("{}", claude_code_statusline_core::Config::json_schema());
+                    return Ok(());
+                }
+                if *effective {
+                    return run_effective(cli.timeout);
+                }
+                if *init {
+                    return run_init();
+                }
+                if *check {
+                    let code = run_check();
+                    io::Write::flush(&mut io::stdout())?;
+                    // `main` reports the error and exits nonzero
+                    if code != 0 {
+                        anyhow::bail!("config check failed");
+                    }
+                    return Ok(());
+                }
                 if *validate {
                     match claude_code_statusline_core::Config::load() {
                         Ok(cfg) => match cfg.validate() {
@@ -84,7 +668,9 @@ pub fn run() -> Result<()> {
                     return Ok(());
                 }
                 // If no flags, show help
-                println!("Use --path | --default | --validate");
+                println!(
+                    "Use --path | --default | --effective | --init | --validate | --check | --schema | --preset <NAME>"
+                );
                 return Ok(());
             }
             Command::Modules { list, enabled } => {
@@ -97,118 +683,219 @@ pub fn run() -> Result<()> {
                 }
                 if *enabled {
                     let cfg = claude_code_statusline_core::Config::load().unwrap_or_default();
-                    let names = claude_code_statusline_core::parser::extract_modules_from_format(
-                        &cfg.format,
-                    );
-                    let reg = claude_code_statusline_core::modules::Registry::with_defaults();
-                    for name in names {
-                        if name == "character" {
-                            continue;
-                        }
-                        // Only consider registered modules
-                        if !reg.list().contains(&name.as_str()) {
-                            continue;
-                        }
-                        let is_enabled = match name.as_str() {
-                            "directory" => !cfg.directory.disabled,
-                            "claude_model" => !cfg.claude_model.disabled,
-                            "git_branch" => !cfg.git_branch.disabled,
-                            "git_status" => !cfg.git_status.disabled,
-                            _ => true,
-                        };
-                        if is_enabled {
-                            println!("{name}");
-                        }
+                    for name in enabled_modules(&cfg)? {
+                        println!("{name}");
                     }
                     return Ok(());
                 }
                 println!("Use --list | --enabled");
                 return Ok(());
             }
+            #[cfg(feature = "demo")]
+            Command::Demo => return run_demo(),
         }
     }
 
+    let log_format = cli.log_format;
+    // Keep stderr machine-readable: no plain `[DEBUG]` lines in JSON mode
+    let plain_stderr = log_format == LogFormat::Text;
+
     // Load configuration with graceful error handling
-    let config = match claude_code_statusline_core::Config::load() {
+    let mut config = match claude_code_statusline_core::Config::load() {
         Ok(cfg) => cfg,
+        Err(e) if cli.strict => {
+            return Err(anyhow::Error::new(e).context("Config error"));
+        }
         Err(e) => {
             // Initialize minimal subscriber to show errors (stderr)
-            let _ = tracing_subscriber::fmt()
-                .with_env_filter("error")
-                .with_writer(std::io::stderr)
-                .try_init();
-            tracing::error!(error = %e, "Config error");
-            eprintln!("Config error: {e}");
-            let msg = claude_code_statusline_core::messages::MSG_FAILED_INVALID_CONFIG;
-            print!("{msg}");
-            io::Write::flush(&mut io::stdout())?;
+            log_format.init_tracing("error");
+            log_format.report_error("Config error", &e);
+            // No config to read `trailing_newline` from: only the flag applies
+            let sink = LineSink {
+                path: cli.output.as_deref(),
+                newline: cli.newline,
+            };
+            sink.emit(e.user_message())?;
             return Ok(());
         }
     };
 
-    // Initialize tracing subscriber based on config.debug
+    if cli.no_git {
+        disable_git_modules(&mut config)?;
+    }
+
+    // Initialize tracing subscriber based on config.debug; JSON output also
+    // carries warnings (module timeouts, config warnings) for tooling.
+    // `--quiet` keeps errors only.
     {
-        let level = if config.debug { "debug" } else { "error" };
-        let _ = tracing_subscriber::fmt()
-            .with_env_filter(level)
-            .with_target(false)
-            .with_writer(std::io::stderr)
-            .try_init();
+        let level = match (cli.quiet, config.debug, log_format) {
+            (true, _, _) => "error",
+            (false, true, _) => "debug",
+            (false, false, LogFormat::Json) => "warn",
+            (false, false, LogFormat::Text) => "error",
+        };
+        log_format.init_tracing(level);
     }
 
     // Initialize debug logger
-    let logger = claude_code_statusline_core::debug::DebugLogger::new(config.debug);
+    let logger = claude_code_statusline_core::debug::DebugLogger::new(config.debug)
+        .with_plain_stderr(plain_stderr);
     logger.log_execution_start();
+
+    // CCS_TIMEOUT overrides command_timeout for quick experiments
+    let timeout_env = claude_code_statusline_core::config::TIMEOUT_ENV;
+    if let Ok(value) = std::env::var(timeout_env) {
+        match config.apply_timeout_override(&value) {
+            Ok(()) => logger.log_stderr(&format!(
+                "command_timeout overridden by {timeout_env}: {}ms",
+                config.command_timeout
+            )),
+            Err(e) => tracing::warn!("Ignoring {timeout_env}: {e}"),
+        }
+    }
+    if let Some(ms) = cli.timeout {
+        config.command_timeout = ms;
+        logger.log_stderr(&format!("command_timeout overridden by --timeout: {ms}ms"));
+    }
     logger.log_config(config.debug, config.command_timeout);
 
+    let sink = LineSink {
+        path: cli.output.as_deref(),
+        newline: cli.newline || config.trailing_newline,
+    };
+
     // Config validation and non-fatal warnings
     if let Err(e) = config.validate() {
-        tracing::error!(error = %e, "Config validation error");
-        eprintln!("Config validation error: {e}");
-        print!("Failed to build status line due to invalid config");
-        io::Write::flush(&mut io::stdout())?;
+        if cli.strict || cli.measure.is_some() {
+            return Err(anyhow::Error::new(e).context("Config validation error"));
+        }
+        log_format.report_error("Config validation error", &e);
+        sink.emit(config.messages.for_error(&e))?;
+        return Ok(());
+    }
+    if !cli.quiet {
+        for w in config.collect_warnings() {
+            tracing::warn!("{w}");
+        }
+    }
+
+    // Run directly in a terminal: explain the expected input instead of blocking
+    if explain_interactive_stdin(
+        stdin_is_interactive(&io::stdin()),
+        &mut io::stderr(),
+        &sink,
+        config.messages.empty_input(),
+    )? {
         return Ok(());
     }
-    for w in config.collect_warnings() {
-        tracing::warn!("{w}");
+
+    if cli.batch {
+        return run_batch(config, log_format);
     }
 
-    // Read JSON input from stdin
-    let mut buffer = String::new();
-    if io::stdin().read_to_string(&mut buffer).is_err() || buffer.trim().is_empty() {
-        let msg = claude_code_statusline_core::messages::MSG_FAILED_EMPTY_INPUT;
-        print!("{msg}");
-        io::Write::flush(&mut io::stdout())?;
+    // Read JSON input from stdin, at most one byte past the size limit so
+    // oversized input is rejected without buffering all of it
+    let max_input = config.max_input_bytes;
+    let mut bytes = Vec::new();
+    let read_ok = io::stdin()
+        .take(max_input as u64 + 1)
+        .read_to_end(&mut bytes)
+        .is_ok();
+    if read_ok && bytes.len() > max_input {
+        let e = claude_code_statusline_core::CoreError::InputTooLarge { max: max_input };
+        if cli.measure.is_some() {
+            return Err(anyhow::Error::new(e).context("Failed to parse JSON"));
+        }
+        log_format.report_error("Failed to parse JSON", &e);
+        sink.emit(config.messages.for_error(&e))?;
         return Ok(());
     }
+    let buffer = match String::from_utf8(bytes) {
+        Ok(buffer) if read_ok && !buffer.trim().is_empty() => buffer,
+        _ if cli.measure.is_some() => anyhow::bail!("no input to measure"),
+        _ => {
+            sink.emit(config.messages.empty_input())?;
+            return Ok(());
+        }
+    };
     logger.log_input(&buffer);
 
     // Parse JSON input
     let input = match claude_code_statusline_core::parse_claude_input(&buffer) {
         Ok(i) => i,
+        Err(e) if cli.measure.is_some() => {
+            return Err(anyhow::Error::new(e).context("Failed to parse JSON"));
+        }
         Err(e) => {
-            tracing::error!(error = %e, "Failed to parse JSON");
-            eprintln!("Failed to parse JSON: {e}");
-            let msg = claude_code_statusline_core::messages::MSG_FAILED_INVALID_JSON;
-            print!("{msg}");
-            io::Write::flush(&mut io::stdout())?;
+            log_format.report_error("Failed to parse JSON", &e);
+            sink.emit(config.messages.for_error(&e))?;
             return Ok(());
         }
     };
     logger.log_success(&input.model.display_name, &input.cwd);
+    if cli.dump_input {
+        match serde_json::to_string_pretty(&input) {
+            Ok(json) => eprintln!("{json}"),
+            Err(e) => tracing::warn!("Failed to dump input: {e}"),
+        }
+    }
 
     // Render via engine
-    let engine = claude_code_statusline_core::Engine::new(config);
+    let messages = config.messages.clone();
+    let engine = claude_code_statusline_core::Engine::new(config).with_plain_stderr(plain_stderr);
+    if let Some(measure) = cli.measure {
+        let measured = engine
+            .render_measured(&input)
+            .map_err(|e| anyhow::Error::new(e).context("Render error"))?;
+        measure.print(&measured);
+        return Ok(());
+    }
     match engine.render(&input) {
         Ok(out) => {
-            print!("{out}");
-            io::Write::flush(&mut io::stdout())?;
+            logger.log_prompt(&out);
+            sink.emit(&out)?;
         }
         Err(e) => {
-            tracing::error!(error = %e, "Render error");
-            eprintln!("Render error: {e}");
+            log_format.report_error("Render error", &e);
+            sink.emit(messages.for_error(&e))?;
         }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redirected_stdin_is_not_interactive() {
+        let file = tempfile::tempfile().unwrap();
+        assert!(!stdin_is_interactive(&file));
+    }
+
+    #[rstest::rstest]
+    #[case::interactive(true)]
+    #[case::piped(false)]
+    fn interactive_stdin_gets_hint_and_fallback(#[case] interactive: bool) {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("line");
+        let sink = LineSink {
+            path: Some(&out),
+            newline: false,
+        };
+        let mut hint = Vec::new();
+        let stopped = explain_interactive_stdin(interactive, &mut hint, &sink, "no input").unwrap();
+        assert_eq!(stopped, interactive);
+        if interactive {
+            assert_eq!(
+                String::from_utf8(hint).unwrap(),
+                format!("{STDIN_TTY_HINT}\n")
+            );
+            assert_eq!(std::fs::read_to_string(&out).unwrap(), "no input");
+        } else {
+            assert!(hint.is_empty());
+            assert!(!out.exists());
+        }
+    }
+}
@@ -26,6 +26,18 @@ fn config_default_prints_valid_toml() {
     assert!(s.contains("command_timeout"));
 }
 
+#[test]
+fn config_schema_prints_json_schema() {
+    let mut cmd = ccs_cmd();
+    cmd.arg("config").arg("--schema");
+    let out = cmd.assert().success().get_output().stdout.clone();
+    let s = String::from_utf8(out).unwrap();
+    assert!(s.trim_start().starts_with('{'));
+    assert!(s.contains("\"$schema\""));
+    assert!(s.contains("\"DirectoryConfig\""));
+    assert!(s.contains("\"command_timeout\""));
+}
+
 #[test]
 fn config_validate_ok_and_invalid() {
     let tmp = tempfile::tempdir().unwrap();
@@ -56,6 +68,42 @@ format = "$directory $claude_model"
         .stderr(predicates::str::contains("Config validation error"));
 }
 
+#[test]
+fn config_check_exits_nonzero_on_warnings_or_errors() {
+    let tmp = tempfile::tempdir().unwrap();
+    let home = tmp.path();
+    let cfg_dir = config_dir_for_home(home);
+    fs::create_dir_all(&cfg_dir).unwrap();
+    let cfg_file = cfg_dir.join("claude-code-statusline.toml");
+
+    // clean config renders the sample input and passes
+    fs::write(&cfg_file, "format = \"$claude_model\"\n").unwrap();
+    let mut ok = ccs_cmd_with_home(home);
+    ok.arg("config").arg("--check");
+    ok.assert()
+        .success()
+        .stdout(predicates::str::contains("Opus"))
+        .stdout(predicates::str::contains("OK"));
+
+    // unknown token only produces a warning, which --check treats as failure
+    fs::write(&cfg_file, "format = \"$claude_model $nope\"\n").unwrap();
+    let mut warn = ccs_cmd_with_home(home);
+    warn.arg("config").arg("--check");
+    warn.assert()
+        .code(1)
+        .stdout(predicates::str::contains("INVALID"))
+        .stderr(predicates::str::contains("Config warning"))
+        .stderr(predicates::str::contains("config check failed"));
+
+    // validation errors fail as well
+    fs::write(&cfg_file, "command_timeout = 10\n").unwrap();
+    let mut bad = ccs_cmd_with_home(home);
+    bad.arg("config").arg("--check");
+    bad.assert()
+        .code(1)
+        .stderr(predicates::str::contains("Config validation error"));
+}
+
 #[test]
 fn modules_list_and_enabled() {
     let tmp = tempfile::tempdir().unwrap();
@@ -82,3 +130,249 @@ fn modules_list_and_enabled() {
     assert!(s2.contains("directory"));
     assert!(s2.contains("claude_model"));
 }
+
+#[test]
+fn modules_enabled_applies_disabled_flags_and_variants() {
+    let tmp = tempfile::tempdir().unwrap();
+    let home = tmp.path();
+    let cfg_dir = config_dir_for_home(home);
+    fs::create_dir_all(&cfg_dir).unwrap();
+    fs::write(
+        cfg_dir.join("claude-code-statusline.toml"),
+        r#"format = "$directory ${directory#short} ${directory#off} $claude_session $localip $nope"
+
+[directory.off]
+disabled = true
+
+[session]
+disabled = true
+
+[localip]
+disabled = false
+"#,
+    )
+    .unwrap();
+
+    let mut cmd = ccs_cmd_with_home(home);
+    cmd.arg("modules").arg("--enabled");
+    let out = cmd.assert().success().get_output().stdout.clone();
+    let names: Vec<String> = String::from_utf8(out)
+        .unwrap()
+        .lines()
+        .map(str::to_string)
+        .collect();
+    assert_eq!(names, ["directory", "directory#short", "localip"]);
+}
+
+#[cfg(feature = "demo")]
+#[test]
+fn demo_renders_every_registered_module() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cmd = ccs_cmd_with_home(tmp.path());
+    cmd.arg("demo");
+    let out = cmd.assert().success().get_output().stdout.clone();
+    let plain = String::from_utf8(strip_ansi_escapes::strip(out)).unwrap();
+    let lines: Vec<&str> = plain.lines().collect();
+
+    for name in [
+        "directory",
+        "claude_model",
+        "cc_version",
+        "status",
+        "git_branch",
+        "git_status",
+    ] {
+        assert!(
+            lines.iter().any(|l| l.starts_with(&format!("{name}: "))),
+            "missing {name} in:\n{plain}"
+        );
+    }
+    assert!(lines.contains(&"claude_model: Opus"), "{plain}");
+    assert!(
+        lines
+            .iter()
+            .any(|l| l.starts_with("git_branch: ") && l.ends_with("main")),
+        "{plain}"
+    );
+    assert!(lines.iter().any(|l| l.starts_with("directory: demo-repo")));
+}
+
+#[cfg(feature = "demo")]
+#[test]
+fn demo_reports_config_errors() {
+    let tmp = tempfile::tempdir().unwrap();
+    let home = tmp.path();
+    let cfg_dir = config_dir_for_home(home);
+    fs::create_dir_all(&cfg_dir).unwrap();
+    fs::write(cfg_dir.join("claude-code-statusline.toml"), "not = = toml").unwrap();
+
+    let mut cmd = ccs_cmd_with_home(home);
+    cmd.arg("demo");
+    cmd.assert()
+        .failure()
+        .stdout(predicates::str::is_empty())
+        .stderr(predicates::str::contains("Config error"));
+}
+
+#[test]
+fn version_plain_output_is_stable() {
+    for flag in ["--version", "-V"] {
+        let mut cmd = ccs_cmd();
+        cmd.arg(flag);
+        cmd.assert().success().stdout(format!(
+            "claude-code-statusline-cli {}\n",
+            env!("CARGO_PKG_VERSION")
+        ));
+    }
+}
+
+#[test]
+fn version_verbose_includes_build_metadata() {
+    let mut cmd = ccs_cmd();
+    cmd.args(["--version", "--verbose"]);
+    let out = cmd.assert().success().get_output().stdout.clone();
+    let s = String::from_utf8(out).unwrap();
+    let lines: Vec<&str> = s.lines().collect();
+    assert_eq!(
+        lines[0],
+        format!("claude-code-statusline-cli {}", env!("CARGO_PKG_VERSION"))
+    );
+    let features = lines.iter().find_map(|l| l.strip_prefix("features: "));
+    assert!(
+        features.is_some_and(|f| f.split(',').any(|x| x == "git")),
+        "{s}"
+    );
+    let commit = lines.iter().find_map(|l| l.strip_prefix("commit: "));
+    // Built from a git checkout: the commit comes from `git rev-parse`
+    let head = std::process::Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .map(|out| String::from_utf8(out.stdout).unwrap().trim().to_string());
+    match head {
+        Some(head) if std::env::var("CCS_GIT_COMMIT").is_err() => {
+            assert_eq!(commit, Some(head.as_str()), "{s}")
+        }
+        _ => assert!(commit.is_some(), "{s}"),
+    }
+    let target = lines.iter().find_map(|l| l.strip_prefix("target: "));
+    assert!(target.is_some_and(|t| !t.is_empty()), "{s}");
+}
+
+#[test]
+fn config_preset_prints_toml_or_fails_for_unknown_name() {
+    let mut cmd = ccs_cmd();
+    cmd.args(["config", "--preset", "minimal"]);
+    let out = cmd.assert().success().get_output().stdout.clone();
+    let s = String::from_utf8(out).unwrap();
+    let table: toml::Table = toml::from_str(&s).unwrap();
+    assert!(table.contains_key("format"));
+
+    let mut bad = ccs_cmd();
+    bad.args(["config", "--preset", "nope"]);
+    bad.assert().failure().stderr(predicates::str::contains(
+        "available: default, minimal, powerline",
+    ));
+}
+
+#[test]
+fn preset_from_config_file_is_applied() {
+    let tmp = tempfile::tempdir().unwrap();
+    let home = tmp.path();
+    let cfg_dir = config_dir_for_home(home);
+    fs::create_dir_all(&cfg_dir).unwrap();
+    fs::write(
+        cfg_dir.join("claude-code-statusline.toml"),
+        "preset = \"minimal\"\n[claude_model]\nformat = \"<$model>\"\n",
+    )
+    .unwrap();
+
+    let mut cmd = ccs_cmd_with_home(home);
+    cmd.write_stdin(test_support::cli::input_json_with_cwd("/var/preset/app"));
+    let out = cmd.assert().success().get_output().stdout.clone();
+    let plain = String::from_utf8(strip_ansi_escapes::strip(out)).unwrap();
+    // Preset truncates to one directory component; user format wins for the model
+    assert_eq!(plain, "app <Opus>");
+}
+
+#[test]
+fn config_effective_prints_layered_config_with_source() {
+    let tmp = tempfile::tempdir().unwrap();
+    let home = tmp.path();
+    let cfg_dir = config_dir_for_home(home);
+    fs::create_dir_all(&cfg_dir).unwrap();
+    let cfg_file = cfg_dir.join("claude-code-statusline.toml");
+    fs::write(
+        &cfg_file,
+        r#"preset = "minimal"
+[directory]
+style = "bold blue"
+[directory.short]
+truncation_length = 2
+"#,
+    )
+    .unwrap();
+
+    let mut cmd = ccs_cmd_with_home(home);
+    cmd.env("CCS_TIMEOUT", "750")
+        .args(["config", "--effective"]);
+    let out = cmd.assert().success().get_output().stdout.clone();
+    let s = String::from_utf8(out).unwrap();
+    assert!(
+        s.starts_with(&format!(
+            "# Effective config loaded from {}\n# command_timeout overridden by CCS_TIMEOUT\n",
+            cfg_file.display()
+        )),
+        "{s}"
+    );
+
+    let table: toml::Table = toml::from_str(&s).unwrap();
+    let directory = table["directory"].as_table().unwrap();
+    // preset value, user override, and the variant table all survive
+    assert_eq!(directory["truncation_length"].as_integer(), Some(1));
+    assert_eq!(directory["style"].as_str(), Some("bold blue"));
+    assert_eq!(
+        directory["short"]["truncation_length"].as_integer(),
+        Some(2)
+    );
+    assert_eq!(table["command_timeout"].as_integer(), Some(750));
+    assert_eq!(
+        table["claude_model"]["format"].as_str(),
+        Some("[$model]($style)")
+    );
+}
+
+#[test]
+fn config_init_falls_back_when_primary_dir_is_not_writable() {
+    let tmp = tempfile::tempdir().unwrap();
+    let home = tmp.path();
+    // A regular file where the config directory should be: it can never be
+    // created or written, regardless of the user running the test
+    let blocked = home.join("blocked");
+    fs::write(&blocked, "").unwrap();
+
+    let mut cmd = ccs_cmd();
+    cmd.env("HOME", home)
+        .env("XDG_CONFIG_HOME", &blocked)
+        .args(["config", "--init"]);
+    let expected = home.join(".config").join("claude-code-statusline.toml");
+    cmd.assert()
+        .success()
+        .stdout(format!("{}\n", expected.display()))
+        .stderr(predicates::str::contains("is not writable"));
+    let written: toml::Table = toml::from_str(&fs::read_to_string(&expected).unwrap()).unwrap();
+    assert!(written.contains_key("format"));
+
+    // A second run refuses to overwrite the file it just wrote
+    let mut again = ccs_cmd();
+    again
+        .env("HOME", home)
+        .env("XDG_CONFIG_HOME", &blocked)
+        .args(["config", "--init"]);
+    again
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("config already exists"));
+}
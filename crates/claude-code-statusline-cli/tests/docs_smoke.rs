@@ -18,7 +18,7 @@ fn valid_input_json() -> String {
 
 #[test]
 fn docs_format_example_runs_without_error() {
-    // Prepare a temp HOME with docs example format including $claude_session (not implemented)
+    // Prepare a temp HOME with docs example format including $claude_session
     let tmp = tempfile::tempdir().unwrap();
     let home = tmp.path();
     let cfg_dir = config_dir_for_home(home);
@@ -30,8 +30,8 @@ fn docs_format_example_runs_without_error() {
         format = "[🌿 $branch]($style)"
         style = "bold green"
 
-        [claude_session]
-        format = "[🔗 $short_id]($style)"
+        [session]
+        format = "[🔗 $session_id]($style)"
         style = "italic yellow"
 
         [claude_model]
@@ -46,6 +46,7 @@ fn docs_format_example_runs_without_error() {
     cmd.assert()
         .success()
         .stdout(predicate::str::contains("Opus"))
+        .stdout(predicate::str::contains("🔗 s-123"))
         .stdout(predicate::str::is_empty().not())
         .stdout(predicate::str::contains("Failed").not());
 }
@@ -53,3 +53,235 @@ fn invalid_toml_config_produces_concise_stdout_and_stderr_details() {
         ))
         .stderr(predicate::str::contains("Config error"));
 }
+
+#[rstest::rstest]
+#[case::invalid_json("not json", "[x] bad input")]
+#[case::empty_input("", "[x] no input")]
+fn configured_fallback_messages_replace_defaults(#[case] stdin: &str, #[case] expected: &str) {
+    let tmp = tempfile::tempdir().unwrap();
+    let home = tmp.path();
+    let cfg_dir = config_dir_for_home(home);
+    fs::create_dir_all(&cfg_dir).unwrap();
+    fs::write(
+        cfg_dir.join("claude-code-statusline.toml"),
+        r#"[messages]
+fallback_invalid_json = "[x] bad input"
+fallback_empty = "[x] no input"
+"#,
+    )
+    .unwrap();
+
+    let mut cmd = ccs_cmd_with_home(home);
+    cmd.write_stdin(stdin);
+    cmd.assert().success().stdout(predicate::eq(expected));
+}
+
+#[test]
+fn configured_invalid_config_message_applies_to_validation_errors() {
+    let tmp = tempfile::tempdir().unwrap();
+    let home = tmp.path();
+    let cfg_dir = config_dir_for_home(home);
+    fs::create_dir_all(&cfg_dir).unwrap();
+    fs::write(
+        cfg_dir.join("claude-code-statusline.toml"),
+        "command_timeout = 10\n[messages]\nfallback_invalid_config = \"[x] config\"\n",
+    )
+    .unwrap();
+
+    let mut cmd = ccs_cmd_with_home(home);
+    cmd.write_stdin(valid_input_json());
+    cmd.assert().success().stdout(predicate::eq("[x] config"));
+}
+
+#[rstest::rstest]
+#[case::single(&[] as &[&str], "[x] config\n")]
+#[case::batch(&["--batch"], "[x] config\n[x] config\n")]
+fn render_errors_emit_the_fallback_message(#[case] args: &[&str], #[case] expected: &str) {
+    let tmp = tempfile::tempdir().unwrap();
+    let home = tmp.path().join("home");
+    let cfg_dir = config_dir_for_home(&home);
+    fs::create_dir_all(&cfg_dir).unwrap();
+    fs::write(
+        cfg_dir.join("claude-code-statusline.toml"),
+        "project_config = true\n[messages]\nfallback_invalid_config = \"[x] config\"\n",
+    )
+    .unwrap();
+    // An unparsable project config fails the render, not the config load
+    let repo = tmp.path().join("repo");
+    fs::create_dir_all(repo.join(".git")).unwrap();
+    fs::write(repo.join(".claude-statusline.toml"), "this is not = toml").unwrap();
+    let input = valid_input_json()
+        .replace("/tmp", repo.to_str().unwrap())
+        .replace('\n', "");
+
+    let stdin = if args.is_empty() {
+        input
+    } else {
+        format!("{input}\n{input}\n")
+    };
+
+    let mut cmd = ccs_cmd_with_home(&home);
+    cmd.args(args).arg("--newline").write_stdin(stdin);
+    cmd.assert()
+        .success()
+        .stdout(predicate::eq(expected))
+        .stderr(predicate::str::contains("Render error"));
+}
+
+#[rstest::rstest]
+#[case("this is not = toml", "Config error")]
+#[case("command_timeout = 10\n", "Config validation error")]
+fn strict_mode_exits_nonzero_on_config_failure(#[case] contents: &str, #[case] stderr: &str) {
+    let tmp = tempfile::tempdir().unwrap();
+    let home = tmp.path();
+    let cfg_dir = config_dir_for_home(home);
+    fs::create_dir_all(&cfg_dir).unwrap();
+    fs::write(cfg_dir.join("claude-code-statusline.toml"), contents).unwrap();
+
+    // Default mode stays graceful
+    let mut graceful = ccs_cmd_with_home(home);
+    graceful.write_stdin(valid_input_json());
+    graceful.assert().success();
+
+    let mut strict = ccs_cmd_with_home(home);
+    strict.arg("--strict").write_stdin(valid_input_json());
+    strict
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains(stderr));
+}
+
+#[test]
+fn json_log_format_emits_single_line_json_on_stderr() {
+    let tmp = tempfile::tempdir().unwrap();
+    let home = tmp.path();
+    let cfg_dir = config_dir_for_home(home);
+    fs::create_dir_all(&cfg_dir).unwrap();
+    fs::write(
+        cfg_dir.join("claude-code-statusline.toml"),
+        "format = \"$claude_model $nope\"\n",
+    )
+    .unwrap();
+
+    let mut cmd = ccs_cmd_with_home(home);
+    cmd.args(["--log-format", "json"])
+        .write_stdin("this is not json");
+    let out = cmd.assert().success().get_output().clone();
+    let stderr = String::from_utf8(out.stderr).unwrap();
+
+    let lines: Vec<&str> = stderr.lines().filter(|l| !l.trim().is_empty()).collect();
+    assert!(!lines.is_empty());
+    for line in &lines {
+        assert!(line.starts_with('{') && line.ends_with('}'), "{line}");
+        assert!(line.contains("\"level\":"), "{line}");
+        assert!(line.contains("\"message\":"), "{line}");
+    }
+    // the unknown-token warning and the parse error are both reported
+    assert!(
+        lines
+            .iter()
+            .any(|l| l.contains("\"WARN\"") && l.contains("nope"))
+    );
+    assert!(
+        lines
+            .iter()
+            .any(|l| l.contains("\"ERROR\"") && l.contains("Failed to parse JSON"))
+    );
+}
+
+#[test]
+fn json_log_format_stays_structured_in_debug_mode() {
+    let tmp = tempfile::tempdir().unwrap();
+    let home = tmp.path();
+    let cfg_dir = config_dir_for_home(home);
+    fs::create_dir_all(&cfg_dir).unwrap();
+    fs::write(
+        cfg_dir.join("claude-code-statusline.toml"),
+        "format = \"$claude_model\"\ndebug = true\n",
+    )
+    .unwrap();
+
+    let mut cmd = ccs_cmd_with_home(home);
+    cmd.args(["--log-format", "json"])
+        .write_stdin(valid_input_json());
+    let out = cmd.assert().success().get_output().clone();
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    // No plain-text `[DEBUG]` lines leak into JSON mode
+    assert!(
+        stderr
+            .lines()
+            .all(|l| l.trim().is_empty() || l.starts_with('{'))
+    );
+}
+
+#[rstest::rstest]
+#[case::default(false, true)]
+#[case::quiet(true, false)]
+fn quiet_suppresses_config_warnings(#[case] quiet: bool, #[case] expect_warning: bool) {
+    let tmp = tempfile::tempdir().unwrap();
+    let home = tmp.path();
+    let cfg_dir = config_dir_for_home(home);
+    fs::create_dir_all(&cfg_dir).unwrap();
+    fs::write(
+        cfg_dir.join("claude-code-statusline.toml"),
+        "debug = true\n[directory]\nstyle = \"bold blink cyan\"\n",
+    )
+    .unwrap();
+
+    let mut cmd = ccs_cmd_with_home(home);
+    if quiet {
+        cmd.arg("--quiet");
+    }
+    cmd.write_stdin(valid_input_json());
+    let out = cmd.assert().success().get_output().stderr.clone();
+    let stderr = String::from_utf8(out).unwrap();
+    assert_eq!(
+        stderr.contains("Unknown style token"),
+        expect_warning,
+        "{stderr}"
+    );
+}
+
+#[rstest::rstest]
+#[case::within_limit(4096, "Opus")]
+#[case::over_limit(64, "Failed to build status line due to invalid json")]
+fn oversized_input_gets_invalid_json_fallback(#[case] max: usize, #[case] expected: &str) {
+    let tmp = tempfile::tempdir().unwrap();
+    let home = tmp.path();
+    let cfg_dir = config_dir_for_home(home);
+    fs::create_dir_all(&cfg_dir).unwrap();
+    fs::write(
+        cfg_dir.join("claude-code-statusline.toml"),
+        format!("max_input_bytes = {max}\nformat = \"$claude_model\"\n"),
+    )
+    .unwrap();
+
+    let input = valid_input_json();
+    assert!(input.len() > 64 && input.len() < 4096);
+    let mut cmd = ccs_cmd_with_home(home);
+    cmd.write_stdin(input);
+    let assert = cmd
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(expected));
+    if max == 64 {
+        assert.stderr(predicate::str::contains("input larger than 64 bytes"));
+    }
+}
+
+#[test]
+fn oversized_input_under_default_limit_is_rejected() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cmd = ccs_cmd_with_home(tmp.path());
+    // Valid JSON padded with whitespace past the 4 MiB default
+    let mut input = valid_input_json();
+    input.push_str(&" ".repeat(claude_code_statusline_core::parser::DEFAULT_MAX_INPUT_BYTES));
+    cmd.write_stdin(input);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Failed to build status line due to invalid json",
+        ))
+        .stderr(predicate::str::contains("input larger than"));
+}
@@ -0,0 +1,7 @@
+//! Golden-file rendering tests: see `tests/golden/<case>/`
+use test_support::golden::assert_golden_dir;
+
+#[test]
+fn golden_fixtures_render_as_expected() {
+    assert_golden_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden"));
+}
@@ -5,6 +5,35 @@ use test_support::cli::{
     ccs_cmd_with_home, config_dir_for_home, input_json_with_cwd, write_basic_config,
 };
 
+/// Init a repo under `home/repo` with an initial commit and HEAD on main
+fn init_repo_on_main(home: &std::path::Path) -> std::path::PathBuf {
+    use git2::{Repository, Signature};
+    use std::io::Write as _;
+    use std::path::Path;
+
+    let repo_dir = home.join("repo");
+    fs::create_dir_all(&repo_dir).unwrap();
+    let repo = Repository::init(&repo_dir).unwrap();
+    let sig = Signature::now("Tester", "tester@example.com").unwrap();
+    let fp = repo_dir.join("README.md");
+    let mut f = std::fs::File::create(&fp).unwrap();
+    writeln!(f, "init").unwrap();
+    f.sync_all().unwrap();
+    let mut idx = repo.index().unwrap();
+    idx.add_path(Path::new("README.md")).unwrap();
+    let tree_id = idx.write_tree().unwrap();
+    let tree = repo.find_tree(tree_id).unwrap();
+    let commit = repo
+        .commit(Some("HEAD"), &sig, &sig, "initial", &tree, &[])
+        .unwrap();
+    let commit0 = repo.find_commit(commit).unwrap();
+    if repo.find_branch("main", git2::BranchType::Local).is_err() {
+        let _ = repo.branch("main", &commit0, true).unwrap();
+    }
+    let _ = repo.set_head("refs/heads/main");
+    repo_dir
+}
+
 #[rstest]
 #[case(false)]
 #[case(true)]
@@ -13,34 +42,9 @@ fn smoke_one_line_output(#[case] with_git_repo: bool) {
     let home = tmp.path();
     write_basic_config(home, None);
 
-    use git2::{Repository, Signature};
-    use std::io::Write as _;
-    use std::path::Path;
-
     // Prepare cwd depending on case
     let cwd = if with_git_repo {
-        // Init a repo with an initial commit and set HEAD to main
-        let repo_dir = home.join("repo");
-        fs::create_dir_all(&repo_dir).unwrap();
-        let repo = Repository::init(&repo_dir).unwrap();
-        let sig = Signature::now("Tester", "tester@example.com").unwrap();
-        let fp = repo_dir.join("README.md");
-        let mut f = std::fs::File::create(&fp).unwrap();
-        writeln!(f, "init").unwrap();
-        f.sync_all().unwrap();
-        let mut idx = repo.index().unwrap();
-        idx.add_path(Path::new("README.md")).unwrap();
-        let tree_id = idx.write_tree().unwrap();
-        let tree = repo.find_tree(tree_id).unwrap();
-        let commit = repo
-            .commit(Some("HEAD"), &sig, &sig, "initial", &tree, &[])
-            .unwrap();
-        let commit0 = repo.find_commit(commit).unwrap();
-        if repo.find_branch("main", git2::BranchType::Local).is_err() {
-            let _ = repo.branch("main", &commit0, true).unwrap();
-        }
-        let _ = repo.set_head("refs/heads/main");
-        repo_dir
+        init_repo_on_main(home)
     } else {
         let d = home.join("nogit");
         fs::create_dir_all(&d).unwrap();
@@ -63,3 +67,270 @@ fn smoke_one_line_output(#[case] with_git_repo: bool) {
         assert.stdout(predicate::str::contains("🌿").not());
     }
 }
+
+#[test]
+fn no_git_flag_skips_git_modules() {
+    let tmp = tempfile::tempdir().unwrap();
+    let home = tmp.path();
+    let cfg_dir = config_dir_for_home(home);
+    fs::create_dir_all(&cfg_dir).unwrap();
+    fs::write(
+        cfg_dir.join("claude-code-statusline.toml"),
+        "format = \"$directory${git_branch}${git_status}${git_branch#short} $claude_model\"\n\
+         [git_branch]\nformat = \" $branch\"\n[git_branch.short]\nformat = \" ($branch)\"\n\
+         [git_status]\nformat = \" $all_status\"\n",
+    )
+    .unwrap();
+    let repo_dir = init_repo_on_main(home);
+    fs::write(repo_dir.join("untracked.txt"), "new\n").unwrap();
+
+    let render = |args: &[&str]| {
+        let mut cmd = ccs_cmd_with_home(home);
+        cmd.args(args)
+            .write_stdin(input_json_with_cwd(repo_dir.to_str().unwrap()));
+        let out = cmd.assert().success().get_output().stdout.clone();
+        String::from_utf8(strip_ansi_escapes::strip(out)).unwrap()
+    };
+    let with_git = render(&[]);
+    assert!(
+        with_git.starts_with("repo main ") && with_git.contains('?') && with_git.contains("(main)"),
+        "{with_git}"
+    );
+    assert_eq!(render(&["--no-git"]), "repo Opus");
+}
+
+#[test]
+fn batch_renders_one_line_per_input() {
+    let tmp = tempfile::tempdir().unwrap();
+    let home = tmp.path();
+    let cfg_dir = config_dir_for_home(home);
+    fs::create_dir_all(&cfg_dir).unwrap();
+    fs::write(
+        cfg_dir.join("claude-code-statusline.toml"),
+        "format = \"$directory $claude_model\"\n",
+    )
+    .unwrap();
+
+    // Each input is compacted onto a single line
+    let line = |cwd: &str| input_json_with_cwd(cwd).replace('\n', "");
+    let stdin = format!(
+        "{}\nnot json\n\n{}\n",
+        line("/var/batch/one"),
+        line("/var/batch/two")
+    );
+
+    let mut cmd = ccs_cmd_with_home(home);
+    cmd.arg("--batch").write_stdin(stdin);
+    let out = cmd.assert().success().get_output().stdout.clone();
+    let plain = String::from_utf8(strip_ansi_escapes::strip(out)).unwrap();
+    let lines: Vec<&str> = plain.lines().collect();
+    assert_eq!(
+        lines,
+        [
+            "/var/batch/one Opus",
+            "Failed to build status line due to invalid json",
+            "/var/batch/two Opus",
+        ]
+    );
+}
+
+#[test]
+fn batch_reports_undecodable_and_oversized_lines_in_place() {
+    let tmp = tempfile::tempdir().unwrap();
+    let home = tmp.path();
+    let cfg_dir = config_dir_for_home(home);
+    fs::create_dir_all(&cfg_dir).unwrap();
+    fs::write(
+        cfg_dir.join("claude-code-statusline.toml"),
+        "format = \"$directory $claude_model\"\nmax_input_bytes = 512\n",
+    )
+    .unwrap();
+
+    let line = |cwd: &str| input_json_with_cwd(cwd).replace('\n', "").into_bytes();
+    let mut stdin = line("/var/batch/one");
+    stdin.extend_from_slice(b"\n\xff\xfe\n");
+    stdin.extend_from_slice(&[b'x'; 2048]);
+    stdin.push(b'\n');
+    stdin.extend_from_slice(&line("/var/batch/two"));
+    stdin.push(b'\n');
+
+    let mut cmd = ccs_cmd_with_home(home);
+    cmd.arg("--batch").write_stdin(stdin);
+    let out = cmd.assert().success().get_output().stdout.clone();
+    let plain = String::from_utf8(strip_ansi_escapes::strip(out)).unwrap();
+    let lines: Vec<&str> = plain.lines().collect();
+    assert_eq!(
+        lines,
+        [
+            "/var/batch/one Opus",
+            "Failed to build status line due to invalid json",
+            "Failed to build status line due to invalid json",
+            "/var/batch/two Opus",
+        ]
+    );
+}
+
+#[test]
+fn measure_prints_render_time_instead_of_the_line() {
+    let tmp = tempfile::tempdir().unwrap();
+    let home = tmp.path();
+    let cfg_dir = config_dir_for_home(home);
+    fs::create_dir_all(&cfg_dir).unwrap();
+    fs::write(
+        cfg_dir.join("claude-code-statusline.toml"),
+        "format = \"$directory $claude_model\"\n",
+    )
+    .unwrap();
+    let run = |flag: &str| {
+        let mut cmd = ccs_cmd_with_home(home);
+        cmd.arg(flag)
+            .write_stdin(input_json_with_cwd("/var/measure/app"));
+        let out = cmd.assert().success().get_output().stdout.clone();
+        String::from_utf8(out).unwrap()
+    };
+
+    let total = run("--measure");
+    assert!(
+        total.trim().parse::<f64>().is_ok_and(|ms| ms >= 0.0),
+        "{total:?}"
+    );
+
+    let verbose = run("--measure=verbose");
+    let rows: Vec<(&str, f64)> = verbose
+        .lines()
+        .map(|line| {
+            let (name, ms) = line.split_once(' ').unwrap();
+            (name, ms.parse().unwrap())
+        })
+        .collect();
+    let names: Vec<&str> = rows.iter().map(|(name, _)| *name).collect();
+    assert_eq!(names, ["total", "directory", "claude_model"], "{verbose}");
+}
+
+#[rstest]
+#[case::invalid_json("not json", "Failed to parse JSON")]
+#[case::empty_input("", "no input to measure")]
+#[case::oversized(
+    "{\"padding\": \"more than thirty-two bytes\"}",
+    "Failed to parse JSON"
+)]
+fn measure_fails_without_printing_a_fallback(#[case] stdin: &str, #[case] stderr: &str) {
+    let tmp = tempfile::tempdir().unwrap();
+    let home = tmp.path();
+    let cfg_dir = config_dir_for_home(home);
+    fs::create_dir_all(&cfg_dir).unwrap();
+    fs::write(
+        cfg_dir.join("claude-code-statusline.toml"),
+        "max_input_bytes = 32\n",
+    )
+    .unwrap();
+
+    let mut cmd = ccs_cmd_with_home(home);
+    cmd.arg("--measure").write_stdin(stdin);
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::contains(stderr));
+}
+
+#[test]
+fn dump_input_prints_parsed_input_to_stderr_and_still_renders() {
+    let tmp = tempfile::tempdir().unwrap();
+    let home = tmp.path();
+    let cfg_dir = config_dir_for_home(home);
+    fs::create_dir_all(&cfg_dir).unwrap();
+    fs::write(
+        cfg_dir.join("claude-code-statusline.toml"),
+        "format = \"$directory $claude_model\"\n",
+    )
+    .unwrap();
+    let mut cmd = ccs_cmd_with_home(home);
+    cmd.arg("--dump-input")
+        .write_stdin(input_json_with_cwd("/var/dump/app"));
+    let out = cmd.assert().success().get_output().clone();
+
+    let plain = String::from_utf8(strip_ansi_escapes::strip(&out.stdout)).unwrap();
+    assert_eq!(plain, "/var/dump/app Opus");
+    let dumped: serde_json::Value = serde_json::from_slice(&out.stderr).unwrap();
+    assert_eq!(dumped["session_id"], "s-123");
+    assert_eq!(dumped["cwd"], "/var/dump/app");
+    assert_eq!(dumped["model"]["id"], "claude-opus");
+    assert_eq!(dumped["model"]["display_name"], "Opus");
+    assert_eq!(dumped["workspace"]["project_dir"], "/var/dump/app");
+    assert_eq!(dumped["version"], "1.0.0");
+    // Null optional fields are normalized away
+    assert!(dumped.get("output_style").is_none(), "{dumped}");
+}
+
+#[test]
+fn output_flag_writes_line_to_file_or_falls_back_to_stdout() {
+    let tmp = tempfile::tempdir().unwrap();
+    let home = tmp.path();
+    let cfg_dir = config_dir_for_home(home);
+    fs::create_dir_all(&cfg_dir).unwrap();
+    fs::write(
+        cfg_dir.join("claude-code-statusline.toml"),
+        "format = \"$directory $claude_model\"\n",
+    )
+    .unwrap();
+
+    let target = home.join("status.txt");
+    fs::write(&target, "stale").unwrap();
+    let mut cmd = ccs_cmd_with_home(home);
+    cmd.arg("--output")
+        .arg(&target)
+        .write_stdin(input_json_with_cwd("/var/out/app"));
+    cmd.assert().success().stdout("");
+    let written = fs::read(&target).unwrap();
+    assert_eq!(
+        String::from_utf8(strip_ansi_escapes::strip(written)).unwrap(),
+        "/var/out/app Opus"
+    );
+
+    // Unwritable target: the line still reaches stdout, with a warning
+    let missing = home.join("no-such-dir").join("status.txt");
+    let mut cmd = ccs_cmd_with_home(home);
+    cmd.arg("--output")
+        .arg(&missing)
+        .write_stdin(input_json_with_cwd("/var/out/app"));
+    let out = cmd
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Failed to write"))
+        .get_output()
+        .stdout
+        .clone();
+    assert_eq!(
+        String::from_utf8(strip_ansi_escapes::strip(out)).unwrap(),
+        "/var/out/app Opus"
+    );
+}
+
+#[rstest]
+#[case::default(false, &[], false)]
+#[case::config(true, &[], true)]
+#[case::flag(false, &["--newline"], true)]
+fn trailing_newline_applies_to_rendered_and_fallback_lines(
+    #[case] in_config: bool,
+    #[case] args: &[&str],
+    #[case] expected: bool,
+) {
+    let tmp = tempfile::tempdir().unwrap();
+    let home = tmp.path();
+    let cfg_dir = config_dir_for_home(home);
+    fs::create_dir_all(&cfg_dir).unwrap();
+    fs::write(
+        cfg_dir.join("claude-code-statusline.toml"),
+        format!("format = \"$claude_model\"\ntrailing_newline = {in_config}\n"),
+    )
+    .unwrap();
+
+    for stdin in [input_json_with_cwd("/var/nl/app"), String::new()] {
+        let mut cmd = ccs_cmd_with_home(home);
+        cmd.args(args).write_stdin(stdin);
+        let out = cmd.assert().success().get_output().stdout.clone();
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out.ends_with('\n'), expected, "{out:?}");
+        assert_eq!(out.matches('\n').count(), usize::from(expected), "{out:?}");
+    }
+}
@@ -1,6 +1,8 @@
 use predicates::prelude::*;
 use std::fs;
-use test_support::cli::{ccs_cmd, input_json_with_cwd, write_basic_config};
+use test_support::cli::{
+    ccs_cmd, ccs_cmd_with_home, config_dir_for_home, input_json_with_cwd, write_basic_config,
+};
 
 #[test]
 fn one_line_output_with_small_timeout() {
@@ -21,3 +23,89 @@ fn one_line_output_with_small_timeout() {
         .success()
         .stdout(predicate::str::is_match("^[^\n]*$").unwrap());
 }
+
+#[test]
+fn ccs_timeout_env_overrides_command_timeout() {
+    let tmp = tempfile::tempdir().unwrap();
+    let home = tmp.path();
+    let cfg_dir = config_dir_for_home(home);
+    fs::create_dir_all(&cfg_dir).unwrap();
+    // Out of range on its own; only a valid CCS_TIMEOUT makes it render
+    fs::write(
+        cfg_dir.join("claude-code-statusline.toml"),
+        "command_timeout = 10\ndebug = true\nformat = \"$claude_model\"\n",
+    )
+    .unwrap();
+    let input = input_json_with_cwd(home.to_str().unwrap());
+
+    // Set: the override is applied and logged
+    let mut set = ccs_cmd_with_home(home);
+    set.env("CCS_TIMEOUT", "1000").write_stdin(input.clone());
+    set.assert()
+        .success()
+        .stdout(predicate::str::contains("Opus"))
+        .stderr(predicate::str::contains(
+            "command_timeout overridden by CCS_TIMEOUT: 1000ms",
+        ));
+
+    // Unset: the config value stands and fails validation
+    let mut unset = ccs_cmd_with_home(home);
+    unset.env_remove("CCS_TIMEOUT").write_stdin(input.clone());
+    unset
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Opus").not())
+        .stderr(predicate::str::contains("command_timeout out of range"));
+
+    // Invalid: ignored with a warning
+    let mut invalid = ccs_cmd_with_home(home);
+    invalid.env("CCS_TIMEOUT", "5").write_stdin(input);
+    invalid
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Ignoring CCS_TIMEOUT"))
+        .stderr(predicate::str::contains("command_timeout out of range"));
+}
+
+#[test]
+fn timeout_flag_overrides_config_and_env() {
+    let tmp = tempfile::tempdir().unwrap();
+    let home = tmp.path();
+    let cfg_dir = config_dir_for_home(home);
+    fs::create_dir_all(&cfg_dir).unwrap();
+    // The directory module sleeps in its `when` predicate, far past the flag
+    // but well within the configured and environment timeouts
+    fs::write(
+        cfg_dir.join("claude-code-statusline.toml"),
+        "command_timeout = 10000\nformat = \"$claude_model ${directory}\"\n\n[directory]\nwhen = \"sleep 3\"\n",
+    )
+    .unwrap();
+    let input = input_json_with_cwd(home.to_str().unwrap());
+
+    let start = std::time::Instant::now();
+    let mut cmd = ccs_cmd_with_home(home);
+    cmd.env("CCS_TIMEOUT", "10000")
+        .args(["--timeout", "100"])
+        .write_stdin(input);
+    let out = cmd.assert().success().get_output().stdout.clone();
+    let plain = String::from_utf8(strip_ansi_escapes::strip(out)).unwrap();
+    assert_eq!(plain.trim_end(), "Opus");
+    assert!(start.elapsed() < std::time::Duration::from_secs(2));
+}
+
+#[test]
+fn timeout_flag_out_of_range_is_an_error() {
+    for value in ["10", "600001", "soon"] {
+        let mut cmd = ccs_cmd();
+        cmd.args(["--timeout", value]).write_stdin("{}");
+        cmd.assert()
+            .failure()
+            .stdout(predicate::str::is_empty())
+            .stderr(predicate::str::contains("--timeout"));
+    }
+    let mut cmd = ccs_cmd();
+    cmd.args(["--timeout", "10"]);
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "command_timeout out of range (50..=600000): 10",
+    ));
+}
@@ -1,6 +1,40 @@
+//! Render latency benchmarks
+//!
+//! Bench ids carry the active feature set so runs across combinations can be
+//! compared side by side, e.g.:
+//!
+//! ```sh
+//! cargo bench -p claude-code-statusline-core
+//! cargo bench -p claude-code-statusline-core --features git
+//! cargo bench -p claude-code-statusline-core --features git,parallel
+//! ```
+
 use claude_code_statusline_core::{Config, Engine, parse_claude_input};
 use criterion::{Criterion, criterion_group, criterion_main};
 
+/// Suffix describing the enabled features (e.g. `git+parallel`)
+fn feature_label() -> &'static str {
+    match (cfg!(feature = "git"), cfg!(feature = "parallel")) {
+        (true, true) => "git+parallel",
+        (true, false) => "git",
+        (false, true) => "parallel",
+        (false, false) => "default",
+    }
+}
+
+fn input_for(cwd: &str) -> claude_code_statusline_core::types::claude::ClaudeInput {
+    let json = format!(
+        r#"{{
+        "session_id": "bench",
+        "cwd": {cwd:?},
+        "model": {{"id": "claude-opus", "display_name": "Opus"}},
+        "workspace": {{"current_dir": {cwd:?}, "project_dir": {cwd:?}}},
+        "version": "1.0.80"
+    }}"#
+    );
+    parse_claude_input(&json).unwrap()
+}
+
 fn bench_engine_render(c: &mut Criterion) {
     let cfg = Config::default();
     let engine = Engine::new(cfg);
@@ -18,5 +52,88 @@ fn bench_engine_render(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, bench_engine_render);
+/// Representative multi-module line: full render vs. module rendering only
+fn bench_representative_line(c: &mut Criterion) {
+    let config = Config {
+        format: "[$directory](bold cyan) $git_branch $git_status $claude_model $cc_version"
+            .to_string(),
+        ..Default::default()
+    };
+    let engine = Engine::new(config);
+    let cwd = env!("CARGO_MANIFEST_DIR");
+    let input = input_for(cwd);
+
+    let mut group = c.benchmark_group(format!("representative/{}", feature_label()));
+    group.bench_function("render", |b| {
+        b.iter(|| {
+            let _ = engine.render(&input).unwrap();
+        })
+    });
+    group.bench_function("render_modules", |b| {
+        b.iter(|| {
+            let _ = engine.render_modules(&input);
+        })
+    });
+    group.finish();
+}
+
+/// Stress `git_status` on a synthetic repository with many dirty files
+#[cfg(feature = "git")]
+fn bench_git_status_synthetic_repo(c: &mut Criterion) {
+    use std::fs;
+    use std::path::Path;
+
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let repo = git2::Repository::init(root).unwrap();
+    let sig = git2::Signature::now("Bench", "bench@example.com").unwrap();
+
+    // 500 tracked files committed, then a mix of modified and untracked files
+    let mut index = repo.index().unwrap();
+    for i in 0..500 {
+        let rel = format!("src/dir{}/file{i}.txt", i % 20);
+        let path = root.join(&rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, format!("line {i}\n")).unwrap();
+        index.add_path(Path::new(&rel)).unwrap();
+    }
+    index.write().unwrap();
+    let tree_id = index.write_tree().unwrap();
+    let tree = repo.find_tree(tree_id).unwrap();
+    repo.commit(Some("HEAD"), &sig, &sig, "initial", &tree, &[])
+        .unwrap();
+    for i in (0..500).step_by(5) {
+        let path = root.join(format!("src/dir{}/file{i}.txt", i % 20));
+        fs::write(path, format!("changed {i}\n")).unwrap();
+    }
+    for i in 0..100 {
+        fs::write(root.join(format!("untracked{i}.txt")), "new\n").unwrap();
+    }
+
+    let config = Config {
+        format: "$git_status".to_string(),
+        ..Default::default()
+    };
+    let engine = Engine::new(config);
+    let input = input_for(root.to_str().unwrap());
+
+    let mut group = c.benchmark_group(format!("git_status_synthetic/{}", feature_label()));
+    group.sample_size(20);
+    group.bench_function("render_modules", |b| {
+        b.iter(|| {
+            let _ = engine.render_modules(&input);
+        })
+    });
+    group.finish();
+}
+
+#[cfg(not(feature = "git"))]
+criterion_group!(benches, bench_engine_render, bench_representative_line);
+#[cfg(feature = "git")]
+criterion_group!(
+    benches,
+    bench_engine_render,
+    bench_representative_line,
+    bench_git_status_synthetic_repo
+);
 criterion_main!(benches);
@@ -23,6 +23,10 @@
 //! [claude_model]
 //! symbol = "<"
 //! style = "bold yellow"
+//!
+//! # `$directory#short` renders `directory` with these keys overridden
+//! [directory.short]
+//! truncation_length = 1
 //! ```
 
 use crate::error::CoreError;
@@ -30,6 +34,38 @@ pub use crate::types::config::Config;
 use std::fs;
 use std::path::PathBuf;
 
+/// Environment variable overriding `command_timeout` (milliseconds)
+///
+/// Applied by the CLI through [`Config::apply_timeout_override`].
+pub const TIMEOUT_ENV: &str = "CCS_TIMEOUT";
+
+/// Project-local configuration file merged over the user config when
+/// [`Config::project_config`] is enabled
+pub const PROJECT_CONFIG_FILE: &str = ".claude-statusline.toml";
+
+/// Top-level keys a [`PROJECT_CONFIG_FILE`] may set: presentation settings
+/// and the built-in module sections
+const PROJECT_CONFIG_KEYS: &[&str] = &[
+    "format",
+    "empty_placeholder",
+    "symbol_mode",
+    "accent_from_model",
+    "accents",
+    "directory",
+    "claude_model",
+    "git_branch",
+    "git_status",
+    "cc_version",
+    "session",
+    "localip",
+    "status",
+    "context_usage",
+];
+
+/// Module keys a [`PROJECT_CONFIG_FILE`] may not set, in a module section or
+/// any of its variants: `when` runs shell commands, timeouts are the user's
+const PROJECT_DENIED_MODULE_KEYS: &[&str] = &["when", "timeout_ms", "timeout_symbol"];
+
 impl Config {
     /// Loads configuration from the default location
     ///
@@ -52,48 +88,197 @@ impl Config {
     /// println!("Format: {}", config.format);
     /// ```
     pub fn load() -> Result<Self, CoreError> {
-        // Candidate 1: XDG-style (~/.config/claude-code-statusline.toml)
-        let xdg_candidate =
-            dirs::home_dir().map(|h| h.join(".config").join("claude-code-statusline.toml"));
-
-        if let Some(ref xdg) = xdg_candidate {
-            if xdg.exists() {
-                let contents = fs::read_to_string(xdg).map_err(|e| CoreError::ConfigRead {
-                    path: xdg.display().to_string(),
-                    source: e,
-                })?;
-                let cfg: Config =
-                    toml::from_str(&contents).map_err(|e| CoreError::ConfigParse {
-                        path: xdg.display().to_string(),
-                        source: e,
-                    })?;
-                return Ok(cfg);
+        let Some(path) = loaded_config_path() else {
+            // Default when no config file is present
+            return Ok(Config::default());
+        };
+        let contents = fs::read_to_string(&path).map_err(|e| CoreError::ConfigRead {
+            path: path.display().to_string(),
+            source: e,
+        })?;
+        Config::from_toml_str(&contents).map_err(|e| CoreError::ConfigParse {
+            path: path.display().to_string(),
+            source: e,
+        })
+    }
+
+    /// Parses configuration TOML, applying the `preset` it names (if any)
+    ///
+    /// The preset is merged under `contents`, so explicitly set keys win.
+    /// Unknown preset names are left for [`Config::validate`] to report.
+    /// Module variant tables (e.g. `[directory.short]`) are collected into
+    /// [`Config::variants`] and must deserialize on top of their module.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use claude_code_statusline_core::Config;
+    ///
+    /// let cfg = Config::from_toml_str("preset = \"minimal\"").unwrap();
+    /// assert_eq!(cfg.directory.truncation_length, 1);
+    /// ```
+    pub fn from_toml_str(contents: &str) -> Result<Self, toml::de::Error> {
+        Config::from_table(toml::from_str(contents)?)
+    }
+
+    /// Copy of this config with a project file's TOML merged over it
+    ///
+    /// Keys set in `contents` win; tables are merged key by key, so a project
+    /// file only needs the settings it changes. Used for
+    /// [`PROJECT_CONFIG_FILE`] when [`Config::project_config`] is enabled.
+    ///
+    /// A project file is untrusted: only presentation settings and module
+    /// sections may be set, and never a module's `when` (which runs shell
+    /// commands) or timeouts. Any other key is an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use claude_code_statusline_core::Config;
+    ///
+    /// let user = Config::from_toml_str("[directory]\nstyle = \"blue\"").unwrap();
+    /// let cfg = user.with_project_overrides("[directory]\ntruncation_length = 1").unwrap();
+    /// assert_eq!(cfg.directory.style, "blue");
+    /// assert_eq!(cfg.directory.truncation_length, 1);
+    /// ```
+    pub fn with_project_overrides(&self, contents: &str) -> Result<Config, toml::de::Error> {
+        use serde::de::Error as _;
+        let project: toml::Table = toml::from_str(contents)?;
+        check_project_keys(&project).map_err(toml::de::Error::custom)?;
+        let mut table = self.to_table().map_err(toml::de::Error::custom)?;
+        crate::presets::merge_tables(&mut table, project);
+        let mut cfg = Config::from_table(table)?;
+        // Only the user config decides whether project files are read
+        cfg.project_config = self.project_config;
+        Ok(cfg)
+    }
+
+    fn from_table(user: toml::Table) -> Result<Self, toml::de::Error> {
+        let preset = user
+            .get("preset")
+            .and_then(toml::Value::as_str)
+            .and_then(crate::presets::preset_toml);
+        let mut merged = match preset {
+            Some(preset) => {
+                let mut base: toml::Table = toml::from_str(preset)?;
+                crate::presets::merge_tables(&mut base, user);
+                base
+            }
+            None => user,
+        };
+        let variants = take_variants(&mut merged);
+        let mut cfg: Config = toml::Value::Table(merged).try_into()?;
+        cfg.variants = variants;
+        for key in cfg.variants.keys() {
+            if let (module, Some(variant)) = crate::parser::split_variant(key) {
+                cfg.with_variant(module, variant)?;
             }
         }
+        Ok(cfg)
+    }
 
-        // Candidate 2: Platform config dir (e.g., macOS ~/Library/Application Support)
-        let primary = get_config_path();
-        if primary.exists() {
-            let contents = fs::read_to_string(&primary).map_err(|e| CoreError::ConfigRead {
-                path: primary.display().to_string(),
-                source: e,
-            })?;
-            let cfg: Config = toml::from_str(&contents).map_err(|e| CoreError::ConfigParse {
-                path: primary.display().to_string(),
-                source: e,
-            })?;
-            return Ok(cfg);
+    /// Configuration for rendering `$module#variant`
+    ///
+    /// Returns a copy with the `[module.variant]` table merged over the
+    /// `[module]` section. Without such a table the copy is unchanged; extra
+    /// (third-party) modules read the variant from their own section.
+    pub fn with_variant(&self, module: &str, variant: &str) -> Result<Config, toml::de::Error> {
+        use serde::de::Error as _;
+        let overrides = self
+            .variants
+            .get(&format!("{module}#{variant}"))
+            .or_else(|| self.extra_module_table(module)?.get(variant)?.as_table())
+            .cloned()
+            .unwrap_or_default();
+        let mut table = toml::Table::try_from(self).map_err(toml::de::Error::custom)?;
+        match table.get_mut(module) {
+            Some(toml::Value::Table(section)) => crate::presets::merge_tables(section, overrides),
+            _ => {
+                table.insert(module.to_string(), toml::Value::Table(overrides));
+            }
         }
+        let mut cfg: Config = toml::Value::Table(table).try_into()?;
+        cfg.variants = self.variants.clone();
+        Ok(cfg)
+    }
+
+    /// Pretty TOML of every resolved setting, variant tables included
+    ///
+    /// Unlike serializing the struct directly, `[module.variant]` tables
+    /// are written back under their module so the output loads as-is.
+    pub fn to_effective_toml(&self) -> Result<String, toml::ser::Error> {
+        toml::to_string_pretty(&self.to_table()?)
+    }
 
-        // Default when no config file is present
-        Ok(Config::default())
+    /// Every resolved setting as a TOML table, variant tables included
+    fn to_table(&self) -> Result<toml::Table, toml::ser::Error> {
+        use serde::ser::Error as _;
+        let mut table = toml::Table::try_from(self)?;
+        let mut variants: Vec<_> = self.variants.iter().collect();
+        variants.sort_by(|a, b| a.0.cmp(b.0));
+        for (key, overrides) in variants {
+            let (module, Some(variant)) = crate::parser::split_variant(key) else {
+                continue;
+            };
+            match table
+                .entry(module)
+                .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+            {
+                toml::Value::Table(section) => {
+                    section.insert(variant.to_string(), toml::Value::Table(overrides.clone()));
+                }
+                _ => return Err(toml::ser::Error::custom(format!("{module} is not a table"))),
+            }
+        }
+        Ok(table)
+    }
+}
+
+/// Remove variant tables from the typed module sections of `table`
+///
+/// A variant is a sub-table whose key is not a field of the section, e.g.
+/// `short` in `[directory.short]`. Returned keys are `module#variant`.
+fn take_variants(table: &mut toml::Table) -> std::collections::HashMap<String, toml::Table> {
+    let mut variants = std::collections::HashMap::new();
+    let Ok(defaults) = toml::Table::try_from(Config::default()) else {
+        return variants;
+    };
+    for (module, default) in &defaults {
+        let (Some(fields), Some(toml::Value::Table(section))) =
+            (default.as_table(), table.get_mut(module))
+        else {
+            continue;
+        };
+        let names: Vec<String> = section
+            .iter()
+            .filter(|(key, value)| value.is_table() && !fields.contains_key(*key))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for name in names {
+            if let Some(toml::Value::Table(overrides)) = section.remove(&name) {
+                variants.insert(format!("{module}#{name}"), overrides);
+            }
+        }
     }
+    variants
+}
+
+/// `claude-code-statusline.toml` under `$XDG_CONFIG_HOME`, when that is set
+/// to an absolute path (relative values are ignored, as the XDG spec says)
+///
+/// Checked on every platform so macOS and Windows users can opt into the
+/// XDG layout; `dirs::config_dir()` only honors it on Linux.
+fn xdg_config_path() -> Option<PathBuf> {
+    let base = PathBuf::from(std::env::var_os("XDG_CONFIG_HOME")?);
+    base.is_absolute()
+        .then(|| base.join("claude-code-statusline.toml"))
 }
 
 /// Determines the path to the configuration file
 ///
-/// Constructs the path to `claude-code-statusline.toml` within the user's
-/// configuration directory. Uses `dirs::config_dir()` for cross-platform
+/// Constructs the path to `claude-code-statusline.toml` within
+/// `$XDG_CONFIG_HOME` when set, otherwise the user's configuration
+/// directory. Uses `dirs::config_dir()` for cross-platform
 /// compatibility and falls back to the literal
 /// `~/.config/claude-code-statusline.toml` if no config directory can be
 /// determined.
@@ -102,6 +287,9 @@ impl Config {
 ///
 /// A `PathBuf` pointing to the expected configuration file location
 fn get_config_path() -> PathBuf {
+    if let Some(path) = xdg_config_path() {
+        return path;
+    }
     // Prefer platform config dir for display/tooling
     if let Some(base) = dirs::config_dir() {
         return base.join("claude-code-statusline.toml");
@@ -124,6 +312,48 @@ fn get_config_path() -> PathBuf {
     PathBuf::from("~/.config/claude-code-statusline.toml")
 }
 
+/// Configuration file [`Config::load`] reads, if any exists
+///
+/// `$XDG_CONFIG_HOME/claude-code-statusline.toml` wins, then
+/// `~/.config/claude-code-statusline.toml`, then the platform config
+/// directory; `None` means the defaults are in effect.
+pub fn loaded_config_path() -> Option<PathBuf> {
+    let home = dirs::home_dir().map(|h| h.join(".config").join("claude-code-statusline.toml"));
+    [xdg_config_path(), home, Some(get_config_path())]
+        .into_iter()
+        .flatten()
+        .find(|p| p.exists())
+}
+
+/// Where a new configuration file can be written
+///
+/// Tries [`config_path`] first, then the platform config directory and
+/// finally `~/.config`, returning the first location whose directory can be
+/// created and written to. `None` when every candidate is read-only.
+pub fn writable_config_path() -> Option<PathBuf> {
+    let mut candidates = vec![get_config_path()];
+    if let Some(base) = dirs::config_dir() {
+        candidates.push(base.join("claude-code-statusline.toml"));
+    }
+    if let Some(home) = dirs::home_dir() {
+        candidates.push(home.join(".config").join("claude-code-statusline.toml"));
+    }
+    candidates
+        .into_iter()
+        .find(|path| path.parent().is_some_and(dir_is_writable))
+}
+
+/// Whether `dir` exists (or can be created) and accepts new files
+fn dir_is_writable(dir: &std::path::Path) -> bool {
+    if fs::create_dir_all(dir).is_err() {
+        return false;
+    }
+    let probe = dir.join(".claude-code-statusline-write-test");
+    let ok = fs::File::create(&probe).is_ok();
+    let _ = fs::remove_file(&probe);
+    ok
+}
+
 /// Public accessor for the resolved configuration file path
 ///
 /// Exposes a stable path resolution for consumers (e.g., CLI) so that all
@@ -156,17 +386,40 @@ impl<'a> ConfigProvider<'a> {
     }
 }
 
+/// Reject keys a project file may not set (see [`PROJECT_CONFIG_KEYS`])
+fn check_project_keys(project: &toml::Table) -> Result<(), String> {
+    fn check_module(path: &str, table: &toml::Table) -> Result<(), String> {
+        for (key, value) in table {
+            let path = format!("{path}.{key}");
+            if PROJECT_DENIED_MODULE_KEYS.contains(&key.as_str()) {
+                return Err(format!("'{path}' can't be set in {PROJECT_CONFIG_FILE}"));
+            }
+            // Variant tables (`[git_status.short]`)
+            if let toml::Value::Table(variant) = value {
+                check_module(&path, variant)?;
+            }
+        }
+        Ok(())
+    }
+    for (key, value) in project {
+        if !PROJECT_CONFIG_KEYS.contains(&key.as_str()) {
+            return Err(format!("'{key}' can't be set in {PROJECT_CONFIG_FILE}"));
+        }
+        if let toml::Value::Table(module) = value {
+            if key != "accents" {
+                check_module(key, module)?;
+            }
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::types::config::Config as Cfg;
 
-    use std::sync::{Mutex, OnceLock};
-
-    fn env_lock() -> &'static Mutex<()> {
-        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
-        LOCK.get_or_init(|| Mutex::new(()))
-    }
+    use crate::test_env::env_lock;
 
     #[test]
     fn test_default_config() {
@@ -194,7 +447,7 @@ mod tests {
     #[test]
     fn test_load_missing_config_returns_default() {
         // Serialize env mutation to avoid races across tests
-        let _guard = env_lock().lock().unwrap();
+        let _guard = env_lock();
         let tmp = tempfile::tempdir().unwrap();
         // Capture the original HOME (if any) so we can restore it later
         let orig_home = std::env::var_os("HOME");
@@ -270,10 +523,49 @@ mod tests {
         assert!(result.is_err());
     }
 
+    /// Run `f` with `XDG_CONFIG_HOME` set to `value`, restoring it afterwards
+    fn with_xdg_config_home<T>(value: &std::path::Path, f: impl FnOnce() -> T) -> T {
+        let _guard = env_lock();
+        let orig = std::env::var_os("XDG_CONFIG_HOME");
+        // SAFETY: env mutation is serialized by `env_lock`
+        unsafe { std::env::set_var("XDG_CONFIG_HOME", value) };
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(f));
+        match orig {
+            Some(v) => unsafe { std::env::set_var("XDG_CONFIG_HOME", v) },
+            None => unsafe { std::env::remove_var("XDG_CONFIG_HOME") },
+        }
+        result.unwrap_or_else(|e| std::panic::resume_unwind(e))
+    }
+
+    #[test]
+    fn xdg_config_home_is_resolved_and_loaded_first() {
+        let tmp = tempfile::tempdir().unwrap();
+        let expected = tmp.path().join("claude-code-statusline.toml");
+        std::fs::write(&expected, "format = \"$claude_model\"\n").unwrap();
+
+        let (path, loaded, config) = with_xdg_config_home(tmp.path(), || {
+            (config_path(), loaded_config_path(), Config::load().unwrap())
+        });
+        assert_eq!(path, expected);
+        assert_eq!(loaded, Some(expected));
+        assert_eq!(config.format, "$claude_model");
+    }
+
+    #[test]
+    fn relative_xdg_config_home_is_ignored() {
+        let path = with_xdg_config_home(std::path::Path::new("relative/dir"), config_path);
+        assert!(!path.starts_with("relative"), "{}", path.display());
+    }
+
     #[test]
     fn test_config_path_with_config_dir() {
         // This test checks the path construction logic via dirs::config_dir
+        let _guard = env_lock();
         let path = get_config_path();
+        if let Some(xdg) = xdg_config_path() {
+            assert_eq!(path, xdg);
+            return;
+        }
 
         if let Some(cfg_dir) = dirs::config_dir() {
             let expected = cfg_dir.join("claude-code-statusline.toml");
@@ -306,6 +598,78 @@ mod tests {
         );
     }
 
+    #[test]
+    fn extra_modules_survive_toml_round_trip() {
+        let toml_str = r#"
+            format = "$directory $my_custom"
+            theme = "dark"
+            retries = 3
+
+            [directory]
+            style = "bold blue"
+
+            [my_custom]
+            key = "value"
+            answer = 42
+            list = ["a", "b"]
+
+            [my_custom.nested]
+            enabled = true
+
+            [another]
+            ratio = 0.5
+        "#;
+        let cfg: Cfg = toml::from_str(toml_str).unwrap();
+        let serialized = toml::to_string_pretty(&cfg).unwrap();
+        let reparsed: Cfg = toml::from_str(&serialized).unwrap();
+
+        assert_eq!(reparsed.extra_modules, cfg.extra_modules);
+        assert_eq!(reparsed.extra_modules.len(), 4);
+        assert_eq!(reparsed.format, "$directory $my_custom");
+        assert_eq!(reparsed.directory.style, "bold blue");
+        let nested = reparsed.extra_module_table("my_custom").unwrap()["nested"]
+            .as_table()
+            .unwrap();
+        assert_eq!(nested["enabled"].as_bool(), Some(true));
+        // A second pass is stable
+        assert_eq!(toml::to_string_pretty(&reparsed).unwrap(), serialized);
+    }
+
+    #[test]
+    fn variant_tables_are_collected_and_merged() {
+        let cfg = Config::from_toml_str(
+            r#"
+            [directory]
+            style = "bold blue"
+            truncation_length = 4
+
+            [directory.substitutions]
+            "/var/work" = "W"
+
+            [directory.short]
+            truncation_length = 1
+        "#,
+        )
+        .unwrap();
+        assert_eq!(cfg.variants.keys().collect::<Vec<_>>(), ["directory#short"]);
+        // Map-valued fields are not mistaken for variants
+        assert_eq!(cfg.directory.substitutions.len(), 1);
+        assert_eq!(cfg.directory.truncation_length, 4);
+
+        let short = cfg.with_variant("directory", "short").unwrap();
+        assert_eq!(short.directory.truncation_length, 1);
+        assert_eq!(short.directory.style, "bold blue");
+        // Unknown variants leave the module unchanged
+        let same = cfg.with_variant("directory", "nope").unwrap();
+        assert_eq!(same.directory.truncation_length, 4);
+    }
+
+    #[test]
+    fn invalid_variant_table_is_a_parse_error() {
+        let err = Config::from_toml_str("[directory.short]\ntruncation_length = \"one\"\n");
+        assert!(err.is_err());
+    }
+
     #[test]
     fn test_claude_model_default_symbol_is_empty() {
         // New desired default behavior for issue #27
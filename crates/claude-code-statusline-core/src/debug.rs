@@ -7,6 +7,15 @@
 use std::fs::OpenOptions;
 use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// A message held by a deferred logger until it is replayed
+enum Deferred {
+    /// A `log_stderr` message
+    Debug(String),
+    /// A `warn_module` warning
+    Warning { module: String, message: String },
+}
 
 /// Debug logger for development and troubleshooting
 ///
@@ -22,6 +31,11 @@ use std::path::{Path, PathBuf};
 pub struct DebugLogger {
     enabled: bool,
     log_file: PathBuf,
+    /// Whether `log_stderr` echoes plain `[DEBUG]` lines (off for JSON log output)
+    plain_stderr: bool,
+    /// When set, `log_stderr` and `warn_module` messages are held here until
+    /// replayed
+    deferred: Option<Mutex<Vec<Deferred>>>,
 }
 
 impl DebugLogger {
@@ -50,7 +64,57 @@ impl DebugLogger {
         // Use cross-platform temp directory
         let log_file = std::env::temp_dir().join("claude-code-statusline.log");
 
-        Self { enabled, log_file }
+        Self {
+            enabled,
+            log_file,
+            plain_stderr: true,
+            deferred: None,
+        }
+    }
+
+    /// Enable or disable plain-text `[DEBUG]` lines on stderr
+    ///
+    /// Structured log consumers (e.g. `--log-format json`) turn this off so
+    /// that stderr only carries the tracing subscriber's output.
+    pub fn with_plain_stderr(mut self, enabled: bool) -> Self {
+        self.plain_stderr = enabled;
+        self
+    }
+
+    /// Create a logger with the same settings whose `log_stderr` and
+    /// `warn_module` messages are buffered until [`DebugLogger::replay_into`]
+    /// is called
+    ///
+    /// Used to keep log order stable when work runs concurrently.
+    pub fn deferred(&self) -> Self {
+        Self {
+            enabled: self.enabled,
+            log_file: self.log_file.clone(),
+            plain_stderr: self.plain_stderr,
+            deferred: Some(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Emit buffered messages through `target`, in order
+    pub fn replay_into(self, target: &DebugLogger) {
+        if let Some(buf) = self.deferred {
+            for entry in buf.into_inner().unwrap_or_else(|e| e.into_inner()) {
+                match entry {
+                    Deferred::Debug(message) => target.log_stderr(&message),
+                    Deferred::Warning { module, message } => target.warn_module(&module, &message),
+                }
+            }
+        }
+    }
+
+    fn defer(&self, entry: Deferred) -> Option<Deferred> {
+        match &self.deferred {
+            Some(buf) => {
+                buf.lock().unwrap_or_else(|e| e.into_inner()).push(entry);
+                None
+            }
+            None => Some(entry),
+        }
     }
 
     /// Log a message if debug mode is enabled
@@ -77,10 +141,26 @@ impl DebugLogger {
 
     /// Log to stderr if debug mode is enabled
     pub fn log_stderr(&self, message: &str) {
-        // Emit via tracing; subscriber decides output
-        tracing::debug!(target: "claude-code-statusline", "{message}");
-        if self.enabled {
-            eprintln!("[DEBUG] {message}");
+        if self.defer(Deferred::Debug(message.to_string())).is_some() {
+            // Emit via tracing; subscriber decides output
+            tracing::debug!(target: "claude-code-statusline", "{message}");
+            if self.enabled && self.plain_stderr {
+                eprintln!("[DEBUG] {message}");
+            }
+        }
+    }
+
+    /// Warn about a module (timeout, failure) at tracing's warn level
+    ///
+    /// Deferred loggers hold the warning with their other messages, so
+    /// warnings replay in the same order as the module logs.
+    pub fn warn_module(&self, module: &str, message: &str) {
+        let entry = Deferred::Warning {
+            module: module.to_string(),
+            message: message.to_string(),
+        };
+        if self.defer(entry).is_some() {
+            tracing::warn!(target: "claude-code-statusline", module, "{message}");
         }
     }
 
@@ -112,9 +192,13 @@ impl DebugLogger {
         self.log(&format!("SUCCESS: Model={model}, CWD={cwd}"));
     }
 
-    /// Log generated prompt
+    /// Log generated prompt, followed by its text with ANSI escapes removed
     pub fn log_prompt(&self, prompt: &str) {
         self.log(&format!("Generated: {prompt}"));
+        self.log(&format!(
+            "Generated (plain): {}",
+            crate::style::strip_ansi(prompt)
+        ));
     }
 
     /// Log error
@@ -129,3 +213,63 @@ impl DebugLogger {
         self.enabled
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deferred_logger_buffers_until_replayed() {
+        let target = DebugLogger::new(false);
+        let buffered = target.deferred();
+        buffered.log_stderr("first");
+        buffered.log_stderr("second");
+        buffered.warn_module("git_status", "third");
+        let held: Vec<String> = buffered
+            .deferred
+            .as_ref()
+            .map(|b| {
+                b.lock()
+                    .unwrap()
+                    .iter()
+                    .map(|entry| match entry {
+                        Deferred::Debug(message) => message.clone(),
+                        Deferred::Warning { module, message } => format!("{module}: {message}"),
+                    })
+                    .collect()
+            })
+            .unwrap();
+        assert_eq!(held, ["first", "second", "git_status: third"]);
+        buffered.replay_into(&target);
+    }
+
+    #[test]
+    fn plain_stderr_is_a_per_logger_setting() {
+        let text = DebugLogger::new(true);
+        let json = DebugLogger::new(true).with_plain_stderr(false);
+        assert!(text.plain_stderr);
+        assert!(!json.plain_stderr);
+        assert!(!json.deferred().plain_stderr);
+    }
+
+    #[test]
+    fn prompt_is_also_logged_without_escapes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let logger = DebugLogger {
+            enabled: true,
+            log_file: tmp.path().join("debug.log"),
+            plain_stderr: true,
+            deferred: None,
+        };
+        logger.log_prompt("\x1b[1;34m~/app\x1b[0m \x1b[33mOpus\x1b[0m");
+        let log = std::fs::read_to_string(&logger.log_file).unwrap();
+        let lines: Vec<&str> = log.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "Generated: \x1b[1;34m~/app\x1b[0m \x1b[33mOpus\x1b[0m",
+                "Generated (plain): ~/app Opus",
+            ]
+        );
+    }
+}
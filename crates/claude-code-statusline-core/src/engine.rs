@@ -8,62 +8,64 @@
 use crate::Config;
 use crate::debug::DebugLogger;
 use crate::error::CoreError;
-use crate::modules::render_module_with_timeout;
+use crate::modules::{ModuleRequirements, Registry, render_module_with_registry};
 use crate::parser::extract_modules_from_format;
 use crate::types::claude::ClaudeInput;
 use crate::types::context::Context;
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// Rendering engine that produces a status line from input and config.
 pub struct Engine {
     config: Config,
+    registry: Arc<Registry>,
+    /// Whether debug logs echo plain `[DEBUG]` lines on stderr
+    plain_stderr: bool,
 }
 
 impl Engine {
     /// Construct a new engine with the given configuration.
     pub fn new(config: Config) -> Self {
-        Self { config }
+        Self::new_with_registry(config, Registry::with_defaults())
+    }
+
+    /// Construct an engine that resolves `$name` tokens from `registry`.
+    ///
+    /// Start from [`Registry::with_defaults`] to keep the built-in modules
+    /// and add custom ones via [`Registry::register_factory`].
+    pub fn new_with_registry(config: Config, registry: Registry) -> Self {
+        Self {
+            config,
+            registry: Arc::new(registry),
+            plain_stderr: true,
+        }
+    }
+
+    /// Enable or disable plain-text `[DEBUG]` lines on stderr
+    ///
+    /// See [`DebugLogger::with_plain_stderr`].
+    pub fn with_plain_stderr(mut self, enabled: bool) -> Self {
+        self.plain_stderr = enabled;
+        self
     }
 
     /// Render a status line string from the provided Claude input.
     pub fn render(&self, input: &ClaudeInput) -> Result<String, CoreError> {
-        let logger = DebugLogger::new(self.config.debug);
-        let context = Context::new(input.clone(), self.config.clone());
+        self.render_measured(input).map(|measured| measured.line)
+    }
 
-        let format = &context.config.format;
-        let module_names = extract_modules_from_format(format);
-
-        // Render modules (optionally in parallel when feature enabled)
-        let module_outputs: HashMap<String, String> = {
-            #[cfg(feature = "parallel")]
-            {
-                use rayon::prelude::*;
-                module_names
-                    .par_iter()
-                    .filter_map(|name| {
-                        if name == "character" {
-                            return None;
-                        }
-                        render_module_with_timeout(name, &context, &logger)
-                            .map(|out| (name.clone(), out))
-                    })
-                    .collect()
-            }
+    /// Render like [`Engine::render`], also reporting how long the whole
+    /// render and each referenced module took
+    pub fn render_measured(&self, input: &ClaudeInput) -> Result<RenderMeasurement, CoreError> {
+        let start = Instant::now();
+        let context = self.context(input)?;
+        let logger = DebugLogger::new(context.config.debug).with_plain_stderr(self.plain_stderr);
 
-            #[cfg(not(feature = "parallel"))]
-            {
-                let mut map = HashMap::new();
-                for name in &module_names {
-                    if name == "character" {
-                        continue;
-                    }
-                    if let Some(out) = render_module_with_timeout(name, &context, &logger) {
-                        map.insert(name.clone(), out);
-                    }
-                }
-                map
-            }
-        };
+        let format = &context.config.format;
+        let runs = self.module_runs(&context, &logger);
+        let modules = runs.iter().map(|r| (r.name.clone(), r.elapsed)).collect();
+        let module_outputs = outputs(runs);
 
         // Replace tokens anywhere and apply top-level bracket styles like
         // [text](fg:.. bg:..), matching Starship-style presets.
@@ -71,18 +73,767 @@ impl Engine {
         for (k, v) in &module_outputs {
             tokens.insert(k.as_str(), v.clone());
         }
-        let mut rendered = crate::style::render_with_style_template(format, &tokens, "");
+        let accent = accent_style(&context);
+        let format = drop_empty_groups(format, &tokens);
+        let depth = context.color_depth();
+        let mut rendered =
+            crate::style::render_with_style_template_depth(&format, &tokens, accent, depth);
+        // Nothing visible (all modules hidden or empty): show the placeholder
+        let placeholder = &context.config.empty_placeholder;
+        if !placeholder.is_empty() && !crate::style::has_visible_text(&rendered) {
+            rendered = crate::style::render_with_style_template_depth(
+                placeholder,
+                &HashMap::new(),
+                accent,
+                depth,
+            );
+        }
         // Ensure a final reset to avoid leaking styles into hosts that
         // don't strictly track nested resets.
         rendered.push_str("\x1b[0m");
-        Ok(rendered)
+        Ok(RenderMeasurement {
+            line: rendered,
+            total: start.elapsed(),
+            modules,
+        })
+    }
+
+    /// Render only the modules referenced by the format, without assembling
+    /// the final line.
+    ///
+    /// Returns `(name, output)` pairs in format order, skipping modules that
+    /// render nothing. Useful for measuring module cost separately from
+    /// token substitution and styling.
+    pub fn render_modules(&self, input: &ClaudeInput) -> Vec<(String, String)> {
+        let context = self
+            .context(input)
+            .unwrap_or_else(|_| Context::new(input.clone(), self.config.clone()));
+        let logger = DebugLogger::new(context.config.debug).with_plain_stderr(self.plain_stderr);
+        self.module_outputs(&context, &logger)
+    }
+
+    /// Context for one render, with the project config applied
+    ///
+    /// When `project_config` is enabled, the nearest
+    /// [`PROJECT_CONFIG_FILE`](crate::config::PROJECT_CONFIG_FILE) between
+    /// the current directory and the repository root is merged over the
+    /// engine's config, and the result validated. Outside a repository no
+    /// project file is read.
+    fn context(&self, input: &ClaudeInput) -> Result<Context, CoreError> {
+        let context = Context::new(input.clone(), self.config.clone());
+        if !self.config.project_config {
+            return Ok(context);
+        }
+        let Some(path) = find_in_repo(&context.current_dir, crate::config::PROJECT_CONFIG_FILE)
+        else {
+            return Ok(context);
+        };
+        let contents = std::fs::read_to_string(&path).map_err(|e| CoreError::ConfigRead {
+            path: path.display().to_string(),
+            source: e,
+        })?;
+        let config =
+            self.config
+                .with_project_overrides(&contents)
+                .map_err(|e| CoreError::ConfigParse {
+                    path: path.display().to_string(),
+                    source: e,
+                })?;
+        config.validate()?;
+        Ok(Context::new(input.clone(), config))
+    }
+
+    /// Render modules (optionally in parallel when feature enabled).
+    /// Both paths yield outputs and logs in format order.
+    fn module_outputs(&self, context: &Context, logger: &DebugLogger) -> Vec<(String, String)> {
+        outputs(self.module_runs(context, logger))
+    }
+
+    /// Render every module referenced by the format, timing each one
+    fn module_runs(&self, context: &Context, logger: &DebugLogger) -> Vec<ModuleRun> {
+        let module_names = extract_modules_from_format(&context.config.format);
+        prime_context(&self.registry, &module_names, context);
+        #[cfg(feature = "parallel")]
+        let runs = render_modules_parallel(&self.registry, &module_names, context, logger);
+        #[cfg(all(feature = "parallel-threads", not(feature = "parallel")))]
+        let runs = render_modules_threaded(&self.registry, &module_names, context, logger);
+        #[cfg(not(any(feature = "parallel", feature = "parallel-threads")))]
+        let runs = render_modules_sequential(&self.registry, &module_names, context, logger);
+        runs
+    }
+}
+
+/// Result of [`Engine::render_measured`]
+#[derive(Debug, Clone)]
+pub struct RenderMeasurement {
+    /// The rendered status line, as returned by [`Engine::render`]
+    pub line: String,
+    /// Wall-clock time of the whole render
+    pub total: Duration,
+    /// Time spent per referenced module in format order, including
+    /// modules that rendered nothing
+    pub modules: Vec<(String, Duration)>,
+}
+
+/// One module's output (`None` when hidden) and how long it took
+#[derive(Debug, Clone)]
+struct ModuleRun {
+    name: String,
+    output: Option<String>,
+    elapsed: Duration,
+}
+
+/// Render `name` through the registry, timing the call
+fn run_module(
+    registry: &Arc<Registry>,
+    name: &str,
+    context: &Context,
+    logger: &DebugLogger,
+) -> ModuleRun {
+    let start = Instant::now();
+    let output = render_module_with_registry(registry, name, context, logger);
+    ModuleRun {
+        name: name.to_string(),
+        output,
+        elapsed: start.elapsed(),
+    }
+}
+
+/// Nearest `name` between `dir` and the repository root (the first ancestor
+/// with `.git`); `None` outside a repository
+fn find_in_repo(dir: &std::path::Path, name: &str) -> Option<std::path::PathBuf> {
+    let mut found = None;
+    for dir in dir.ancestors() {
+        let candidate = dir.join(name);
+        if found.is_none() && candidate.is_file() {
+            found = Some(candidate);
+        }
+        if dir.join(".git").exists() {
+            return found;
+        }
+    }
+    None
+}
+
+/// `(name, output)` pairs of the modules that rendered something
+fn outputs(runs: Vec<ModuleRun>) -> Vec<(String, String)> {
+    runs.into_iter()
+        .filter_map(|run| run.output.map(|out| (run.name, out)))
+        .collect()
+}
+
+/// Resolve the shared context state that the modules in `names` require
+///
+/// Only state shared by the per-module context clones is resolved, so doing
+/// the work once up front keeps concurrent modules from racing to repeat it.
+/// Nothing is resolved when no enabled module declares a requirement.
+fn prime_context(registry: &Registry, names: &[String], context: &Context) {
+    let needs = names
+        .iter()
+        .filter_map(|name| {
+            let (module, _) = crate::parser::split_variant(name);
+            if registry.config(module, context)?.disabled() {
+                return None;
+            }
+            registry.create(module, context)
+        })
+        .fold(ModuleRequirements::default(), |acc, module| {
+            acc.union(module.requires())
+        });
+    #[cfg(feature = "git")]
+    if needs.git {
+        let _ = context.git_snapshot();
+    }
+    // Reuses the snapshot's discovery when one was taken
+    if needs.repo_root {
+        let _ = context.repo_root();
+    }
+    if needs.dir_scan {
+        let _ = context.dir_contents();
+    }
+}
+
+/// Render each module in format order, one after another
+#[cfg_attr(
+    any(feature = "parallel", feature = "parallel-threads"),
+    allow(dead_code)
+)]
+fn render_modules_sequential(
+    registry: &Arc<Registry>,
+    names: &[String],
+    context: &Context,
+    logger: &DebugLogger,
+) -> Vec<ModuleRun> {
+    names
+        .iter()
+        .filter(|name| name.as_str() != "character")
+        .map(|name| run_module(registry, name, context, logger))
+        .collect()
+}
+
+/// Render modules concurrently while keeping results and logs in format order
+///
+/// Each module logs into its own deferred buffer; buffers are replayed in
+/// format order once all modules are done.
+#[cfg(feature = "parallel")]
+fn render_modules_parallel(
+    registry: &Arc<Registry>,
+    names: &[String],
+    context: &Context,
+    logger: &DebugLogger,
+) -> Vec<ModuleRun> {
+    use rayon::prelude::*;
+    let rendered: Vec<_> = names
+        .par_iter()
+        .filter(|name| name.as_str() != "character")
+        .map(|name| {
+            let buffered = logger.deferred();
+            let run = run_module(registry, name, context, &buffered);
+            (run, buffered)
+        })
+        .collect();
+    rendered
+        .into_iter()
+        .map(|(run, buffered)| {
+            buffered.replay_into(logger);
+            run
+        })
+        .collect()
+}
+
+/// Render modules on scoped threads, one per module, keeping format order
+///
+/// Dependency-free alternative to [`render_modules_parallel`]; logs are
+/// deferred and replayed in format order the same way.
+#[cfg(feature = "parallel-threads")]
+#[cfg_attr(feature = "parallel", allow(dead_code))]
+fn render_modules_threaded(
+    registry: &Arc<Registry>,
+    names: &[String],
+    context: &Context,
+    logger: &DebugLogger,
+) -> Vec<ModuleRun> {
+    let rendered: Vec<_> = std::thread::scope(|scope| {
+        let handles: Vec<_> = names
+            .iter()
+            .filter(|name| name.as_str() != "character")
+            .map(|name| {
+                scope.spawn(move || {
+                    let buffered = logger.deferred();
+                    let run = run_module(registry, name, context, &buffered);
+                    (run, buffered)
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .filter_map(|handle| handle.join().ok())
+            .collect()
+    });
+    rendered
+        .into_iter()
+        .map(|(run, buffered)| {
+            buffered.replay_into(logger);
+            run
+        })
+        .collect()
+}
+
+/// Remove literal `[...](style)` groups of `format` that would separate nothing
+///
+/// A group without tokens (e.g. a Powerline separator `[](fg:prev_bg bg:#333)`)
+/// is dropped when the module just before it is empty, so separators don't
+/// float next to a missing segment. Groups referencing modules are always
+/// kept, and literal groups before the first module are kept too.
+fn drop_empty_groups(format: &str, tokens: &HashMap<&str, String>) -> String {
+    // Whether every module token in `text` resolves to nothing visible;
+    // `None` when `text` references no module
+    let all_empty = |text: &str| -> Option<bool> {
+        let mut found = false;
+        let mut empty = true;
+        let mut i = 0;
+        while let Some(off) = text[i..].find('$') {
+            let at = i + off;
+            match crate::parser::scan_token(text, at) {
+                Some(tok) if tok.name != "style" => {
+                    found = true;
+                    let value = tokens.get(tok.name).map(String::as_str);
+                    if crate::parser::resolve_token(&tok, value)
+                        .is_some_and(crate::style::has_visible_text)
+                    {
+                        empty = false;
+                    }
+                    i = tok.end;
+                }
+                _ => i = at + 1,
+            }
+        }
+        found.then_some(empty)
+    };
+
+    let mut out = String::with_capacity(format.len());
+    // Whether the most recent module reference rendered nothing
+    let mut last_empty = false;
+    let mut rest = format;
+    while let Some(open) = rest.find('[') {
+        let group = rest[open..].find("](").and_then(|close| {
+            let style_start = open + close + 2;
+            crate::style::annotation_end(&rest.as_bytes()[style_start..])
+                .map(|end| (open + close, style_start + end + 1))
+        });
+        let Some((close, end)) = group.filter(|(close, _)| !rest[open + 1..*close].contains('['))
+        else {
+            if let Some(empty) = all_empty(&rest[..open]) {
+                last_empty = empty;
+            }
+            out.push_str(&rest[..=open]);
+            rest = &rest[open + 1..];
+            continue;
+        };
+        let before = &rest[..open];
+        if let Some(empty) = all_empty(before) {
+            last_empty = empty;
+        }
+        out.push_str(before);
+        let keep = match all_empty(&rest[open + 1..close]) {
+            Some(empty) => {
+                last_empty = empty;
+                true
+            }
+            None => !last_empty,
+        };
+        if keep {
+            out.push_str(&rest[open..end]);
+        }
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Style that top-level `$style` resolves to
+///
+/// Empty unless `accent_from_model` is enabled and the model id matches a
+/// tier in `accents`.
+fn accent_style(context: &Context) -> &str {
+    if !context.config.accent_from_model {
+        return "";
     }
+    crate::modules::claude_model::match_model_family(
+        &context.config.accents,
+        &context.input.model.id,
+    )
+    .map_or("", String::as_str)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::types::claude::{ClaudeInput, ModelInfo, WorkspaceInfo};
+    use rstest::rstest;
+
+    fn input_with_model_id(id: &str) -> ClaudeInput {
+        ClaudeInput {
+            hook_event_name: None,
+            session_id: "test-session".into(),
+            transcript_path: None,
+            cwd: "/tmp".into(),
+            model: ModelInfo {
+                id: id.into(),
+                display_name: "Model".into(),
+            },
+            workspace: None,
+            version: None,
+            output_style: None,
+            exit_status: None,
+        }
+    }
+
+    #[rstest]
+    #[case("claude-opus-4-1", true, "\x1b[35m|\x1b[0m")]
+    #[case("claude-3-5-haiku", true, "\x1b[32m|\x1b[0m")]
+    #[case("some-other-model", true, "|")]
+    #[case("claude-opus-4-1", false, "|")]
+    fn accent_follows_model_tier(#[case] id: &str, #[case] enabled: bool, #[case] expected: &str) {
+        let cfg = Config {
+            format: "[|]($style)".into(),
+            accent_from_model: enabled,
+            ..Default::default()
+        };
+        let out = Engine::new(cfg)
+            .render(&input_with_model_id(id))
+            .expect("render ok");
+        assert_eq!(out, format!("{expected}\x1b[0m"));
+    }
+
+    #[test]
+    fn accent_does_not_override_explicit_styles() {
+        let cfg = Config {
+            format: "[|](red)".into(),
+            accent_from_model: true,
+            ..Default::default()
+        };
+        let out = Engine::new(cfg)
+            .render(&input_with_model_id("claude-opus-4-1"))
+            .expect("render ok");
+        assert_eq!(out, "\x1b[31m|\x1b[0m\x1b[0m");
+    }
+
+    #[rstest]
+    #[case::all_present(
+        "a b c",
+        "[ $a ](bg:blue)[>](bg:red)[ $b ](bg:red)[>](bg:green)[ $c ](bg:green)"
+    )]
+    #[case::middle_empty("a c", "[ $a ](bg:blue)[>](bg:red)[ $b ](bg:red)[ $c ](bg:green)")]
+    #[case::first_empty("b c", "[ $a ](bg:blue)[ $b ](bg:red)[>](bg:green)[ $c ](bg:green)")]
+    // Only the module before a separator decides, as for Powerline arrows
+    #[case::last_empty(
+        "a b",
+        "[ $a ](bg:blue)[>](bg:red)[ $b ](bg:red)[>](bg:green)[ $c ](bg:green)"
+    )]
+    fn literal_separators_collapse_after_empty_modules(
+        #[case] present: &str,
+        #[case] expected: &str,
+    ) {
+        let format = "[ $a ](bg:blue)[>](bg:red)[ $b ](bg:red)[>](bg:green)[ $c ](bg:green)";
+        let tokens: HashMap<&str, String> = present
+            .split(' ')
+            .map(|name| (name, name.to_uppercase()))
+            .collect();
+        assert_eq!(drop_empty_groups(format, &tokens), expected);
+    }
+
+    #[test]
+    fn leading_separator_and_default_tokens_are_kept() {
+        let tokens: HashMap<&str, String> = HashMap::new();
+        assert_eq!(drop_empty_groups("[>](red)", &tokens), "[>](red)");
+        assert_eq!(
+            drop_empty_groups("[${a:-none}](red)[|](dim)", &tokens),
+            "[${a:-none}](red)[|](dim)"
+        );
+        // Groups with module tokens stay even when the module is empty
+        assert_eq!(
+            drop_empty_groups("[Model: $a](bold)[|](dim)", &tokens),
+            "[Model: $a](bold)"
+        );
+        // Bare tokens count as the module before a separator too
+        assert_eq!(drop_empty_groups("$a[|](dim)", &tokens), "$a");
+        // Nested parentheses stay inside the style annotation
+        assert_eq!(
+            drop_empty_groups("$a[|](fg:gradient(#ff0000,#0000ff))!", &tokens),
+            "$a!"
+        );
+    }
+
+    #[test]
+    fn inline_gradient_style_colors_each_character() {
+        let cfg = Config {
+            format: "[abc](fg:gradient(#ff0000,#0000ff))".into(),
+            color_depth: Some(crate::style::ColorDepth::TrueColor),
+            ..Default::default()
+        };
+        let out = Engine::new(cfg)
+            .render(&input_with_model_id("claude-opus"))
+            .unwrap();
+        assert_eq!(crate::style::strip_ansi(&out), "abc");
+        let codes: std::collections::HashSet<&str> = out
+            .split("\x1b[")
+            .filter_map(|seq| seq.strip_prefix("38;2;"))
+            .map(|seq| seq.split('m').next().unwrap())
+            .collect();
+        assert_eq!(codes.len(), 3, "{out:?}");
+    }
+
+    #[test]
+    fn powerline_separator_is_dropped_after_empty_middle_module() {
+        // cc_version is hidden without a version in the input
+        let cfg = Config {
+            format: "[ $directory ](bg:blue)[>](fg:prev_bg bg:magenta)[ ${cc_version} ](bg:magenta)[>](fg:prev_bg bg:green)[ $claude_model ](bg:green)[>](fg:prev_bg)".into(),
+            ..Default::default()
+        };
+        let engine = Engine::new(cfg);
+        let render = |version: Option<&str>| {
+            let mut input = input_with_model_id("claude-opus-4-1");
+            input.version = version.map(str::to_string);
+            let out = engine.render(&input).expect("render ok");
+            String::from_utf8(strip_ansi_escapes::strip(out)).unwrap()
+        };
+
+        // The empty segment keeps its padding; only the arrow after it goes
+        assert_eq!(render(None), " /tmp >   Model >");
+        assert_eq!(render(Some("1.0.0")).matches('>').count(), 3);
+    }
+
+    #[test]
+    fn measured_render_times_every_referenced_module() {
+        let engine = Engine::new(Config {
+            format: "$directory $cc_version $claude_model".into(),
+            ..Default::default()
+        });
+        let input = input_with_model_id("claude-opus-4-1");
+        let measured = engine.render_measured(&input).expect("render ok");
+
+        assert_eq!(measured.line, engine.render(&input).unwrap());
+        // Hidden modules (no version in the input) are timed too
+        let names: Vec<&str> = measured.modules.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, ["directory", "cc_version", "claude_model"]);
+        let slowest = measured.modules.iter().map(|(_, d)| *d).max().unwrap();
+        assert!(measured.total >= slowest);
+    }
+
+    /// Assert that `render` produces the sequential path's module outputs,
+    /// and that the engine's line is built from them
+    #[cfg(any(feature = "parallel", feature = "parallel-threads"))]
+    fn assert_matches_sequential(
+        render: fn(&Arc<Registry>, &[String], &Context, &DebugLogger) -> Vec<ModuleRun>,
+    ) {
+        let dir = tempfile::tempdir().unwrap();
+        let mut input = input_with_model_id("claude-opus-4-1");
+        input.cwd = dir.path().to_string_lossy().to_string();
+        input.version = Some("1.0.80".into());
+        let cfg = Config {
+            format: "[$directory]($style) $claude_model $unknown $session $cc_version $directory"
+                .into(),
+            ..Default::default()
+        };
+        let context = Context::new(input, cfg);
+        let names = extract_modules_from_format(&context.config.format);
+        let logger = DebugLogger::new(false);
+        let registry = Arc::new(Registry::with_defaults());
+
+        let sequential = outputs(render_modules_sequential(
+            &registry, &names, &context, &logger,
+        ));
+        let concurrent = outputs(render(&registry, &names, &context, &logger));
+        assert_eq!(concurrent, sequential);
+
+        let out = Engine::new(context.config.clone())
+            .render(&context.input)
+            .expect("render ok");
+        let mut tokens: HashMap<&str, String> = HashMap::new();
+        for (k, v) in &sequential {
+            tokens.insert(k.as_str(), v.clone());
+        }
+        let expected =
+            crate::style::render_with_style_template(&context.config.format, &tokens, "");
+        assert_eq!(out.as_bytes(), format!("{expected}\x1b[0m").as_bytes());
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_and_sequential_rendering_match() {
+        assert_matches_sequential(render_modules_parallel);
+    }
+
+    #[cfg(feature = "parallel-threads")]
+    #[test]
+    fn threaded_and_sequential_rendering_match() {
+        assert_matches_sequential(render_modules_threaded);
+    }
+
+    #[test]
+    fn module_variants_render_side_by_side() {
+        let cfg = Config::from_toml_str(
+            r#"
+            format = "$directory | $directory#short | ${directory#tail}"
+
+            [directory]
+            format = "$path"
+            truncation_length = 3
+            truncate_outside_repo = true
+
+            [directory.short]
+            truncation_length = 1
+
+            [directory.tail]
+            format = "<$path>"
+            truncation_length = 2
+        "#,
+        )
+        .unwrap();
+        let mut input = input_with_model_id("claude-opus");
+        input.cwd = "/var/a/b/c/d".into();
+        let out = Engine::new(cfg).render(&input).expect("render ok");
+        let plain = String::from_utf8(strip_ansi_escapes::strip(out)).unwrap();
+        assert_eq!(plain, "b/c/d | d | <c/d>");
+    }
+
+    #[rstest]
+    #[case::git_free("$claude_model $cc_version", false)]
+    #[case::directory("$claude_model $directory", true)]
+    #[case::variant("$directory#short", true)]
+    fn git_discovery_runs_only_when_a_module_requires_it(
+        #[case] format: &str,
+        #[case] resolved: bool,
+    ) {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join(".git")).unwrap();
+        let mut input = input_with_model_id("claude-opus");
+        input.cwd = tmp.path().to_string_lossy().to_string();
+        let cfg = Config {
+            format: format.into(),
+            ..Default::default()
+        };
+        let context = Context::new(input, cfg);
+        let names = extract_modules_from_format(&context.config.format);
+
+        prime_context(&Registry::with_defaults(), &names, &context);
+        #[cfg(feature = "git")]
+        assert_eq!(context.test_repo_discovers(), usize::from(resolved));
+        #[cfg(not(feature = "git"))]
+        assert_eq!(context.test_repo_root_resolved(), resolved);
+    }
+
+    #[test]
+    fn disabled_modules_are_not_primed() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join(".git")).unwrap();
+        let mut input = input_with_model_id("claude-opus");
+        input.cwd = tmp.path().to_string_lossy().to_string();
+        let mut cfg = Config {
+            format: "$claude_model $directory".into(),
+            ..Default::default()
+        };
+        cfg.directory.disabled = true;
+        let context = Context::new(input, cfg);
+        let names = extract_modules_from_format(&context.config.format);
+
+        prime_context(&Registry::with_defaults(), &names, &context);
+        #[cfg(feature = "git")]
+        assert_eq!(context.test_repo_discovers(), 0);
+        assert!(!context.test_repo_root_resolved());
+    }
+
+    #[cfg(feature = "git")]
+    #[rstest]
+    // Priming, plus the status read on git_status's own handle
+    #[case::auto("auto", 2, true)]
+    #[case::libgit2("libgit2", 2, true)]
+    #[case::cli("cli", 0, false)]
+    fn git_backend_decides_which_modules_read_the_repository(
+        #[case] backend: &str,
+        #[case] discovers: usize,
+        #[case] status_shown: bool,
+    ) {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().join("repo");
+        let repo = git2::Repository::init(&root).unwrap();
+        let sig = git2::Signature::now("Tester", "tester@example.com").unwrap();
+        let tree = repo
+            .find_tree(repo.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "initial", &tree, &[])
+            .unwrap();
+        std::fs::write(root.join("new.txt"), "x").unwrap();
+
+        let mut input = input_with_model_id("claude-opus");
+        input.cwd = root.to_string_lossy().to_string();
+        let mut cfg: Config = toml::from_str(&format!(
+            "format = \"$directory $git_branch $git_status\"\ngit_backend = \"{backend}\""
+        ))
+        .unwrap();
+        cfg.directory.format = "$path".into();
+        cfg.git_branch.format = "$branch".into();
+        let context = Context::new(input, cfg.clone());
+        let outputs = Engine::new(cfg).module_outputs(&context, &DebugLogger::new(false));
+
+        // The repository root is still found through `.git` under `cli`
+        assert_eq!(outputs[0], ("directory".to_string(), "repo".to_string()));
+        assert!(outputs.iter().any(|(name, _)| name == "git_branch"));
+        let status_rendered = outputs
+            .iter()
+            .any(|(name, out)| name == "git_status" && !out.is_empty());
+        assert_eq!(status_rendered, status_shown, "{outputs:?}");
+        assert_eq!(context.test_repo_discovers(), discovers);
+    }
+
+    #[cfg(feature = "git")]
+    #[test]
+    fn priming_shares_one_repository_discovery() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(tmp.path()).unwrap();
+        let sig = git2::Signature::now("Tester", "tester@example.com").unwrap();
+        let tree = repo
+            .find_tree(repo.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "initial", &tree, &[])
+            .unwrap();
+        let mut input = input_with_model_id("claude-opus");
+        input.cwd = tmp.path().to_string_lossy().to_string();
+        let cfg = Config {
+            format: "$directory $git_branch".into(),
+            ..Default::default()
+        };
+        let context = Context::new(input, cfg.clone());
+        let outputs = Engine::new(cfg).module_outputs(&context, &DebugLogger::new(false));
+
+        let rendered: Vec<&str> = outputs.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(rendered, ["directory", "git_branch"]);
+        // The repository root and branch both come from the primed snapshot
+        assert_eq!(context.test_repo_discovers(), 1);
+    }
+
+    #[cfg(feature = "git")]
+    #[test]
+    fn git_status_is_read_once_per_frame() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(tmp.path()).unwrap();
+        let sig = git2::Signature::now("Tester", "tester@example.com").unwrap();
+        let tree = repo
+            .find_tree(repo.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "initial", &tree, &[])
+            .unwrap();
+        std::fs::write(tmp.path().join("new.txt"), "x").unwrap();
+
+        let mut input = input_with_model_id("claude-opus");
+        input.cwd = tmp.path().to_string_lossy().to_string();
+        let mut cfg = Config {
+            format: "$git_branch $git_status".into(),
+            ..Default::default()
+        };
+        cfg.git_status.format = "[$all_status]($style)".into();
+        let context = Context::new(input, cfg.clone());
+        let outputs = Engine::new(cfg).module_outputs(&context, &DebugLogger::new(false));
+
+        let rendered: Vec<&str> = outputs.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(rendered, ["git_branch", "git_status"]);
+        // Later readers (including other clones) reuse the memoized counts
+        assert_eq!(context.clone().git_status_counts().unwrap().untracked, 1);
+        assert_eq!(context.test_git_status_reads(), 1);
+    }
+
+    #[rstest]
+    #[case::unset("", "")]
+    #[case::plain("—", "—")]
+    #[case::styled("[no modules](dim)", "no modules")]
+    fn empty_line_renders_placeholder(#[case] placeholder: &str, #[case] expected: &str) {
+        let mut cfg = Config {
+            format: "${directory} ${claude_model}".into(),
+            empty_placeholder: placeholder.into(),
+            ..Default::default()
+        };
+        cfg.directory.disabled = true;
+        cfg.claude_model.disabled = true;
+        let out = Engine::new(cfg)
+            .render(&input_with_model_id("claude-opus"))
+            .unwrap();
+        let plain = crate::style::strip_ansi(&out);
+        assert_eq!(plain.trim(), expected);
+    }
+
+    #[test]
+    fn placeholder_is_not_used_when_a_module_renders() {
+        let cfg = Config {
+            format: "$claude_model".into(),
+            empty_placeholder: "—".into(),
+            ..Default::default()
+        };
+        let out = Engine::new(cfg)
+            .render(&input_with_model_id("claude-opus"))
+            .unwrap();
+        assert!(!crate::style::strip_ansi(&out).contains('—'), "{out:?}");
+    }
 
     #[test]
     fn engine_renders_default_format() {
@@ -101,6 +852,7 @@ mod tests {
             }),
             version: Some("1.0.0".into()),
             output_style: None,
+            exit_status: None,
         };
         let cfg = Config::default();
         let engine = Engine::new(cfg);
@@ -109,4 +861,107 @@ mod tests {
         assert!(plain.contains("/tmp"));
         assert!(plain.contains("Opus"));
     }
+
+    #[test]
+    fn project_config_overrides_format_up_to_the_repo_root() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path().join("repo");
+        let nested = repo.join("src").join("app");
+        std::fs::create_dir_all(repo.join(".git")).unwrap();
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(
+            repo.join(crate::config::PROJECT_CONFIG_FILE),
+            "format = \"<$claude_model>\"\n[claude_model]\nformat = \"$model\"\n",
+        )
+        .unwrap();
+        // Above the repository root: never reached
+        std::fs::write(
+            tmp.path().join(crate::config::PROJECT_CONFIG_FILE),
+            "format = \"outside\"\n",
+        )
+        .unwrap();
+        let mut input = input_with_model_id("claude-opus");
+        input.cwd = nested.to_string_lossy().into_owned();
+        let render = |input: &ClaudeInput, project_config| {
+            let cfg = Config {
+                format: "$claude_model".into(),
+                project_config,
+                ..Default::default()
+            };
+            let out = Engine::new(cfg).render(input).unwrap();
+            crate::style::strip_ansi(&out)
+        };
+
+        assert_eq!(render(&input, true), "<Model>");
+        assert_eq!(render(&input, false), "Model");
+
+        // Outside any repository no project file is read
+        input.cwd = tmp.path().join("elsewhere").to_string_lossy().into_owned();
+        assert_eq!(render(&input, true), "Model");
+    }
+
+    #[rstest]
+    #[case::when("[claude_model]\nwhen = \"touch pwned\"\n")]
+    #[case::variant_when("[claude_model.short]\nwhen = \"touch pwned\"\n")]
+    #[case::module_timeout("[claude_model]\ntimeout_ms = 600000\n")]
+    #[case::command_timeout("command_timeout = 600000\n")]
+    #[case::debug("debug = true\n")]
+    fn project_config_cannot_set_untrusted_keys(#[case] contents: &str) {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join(".git")).unwrap();
+        std::fs::write(
+            tmp.path().join(crate::config::PROJECT_CONFIG_FILE),
+            contents,
+        )
+        .unwrap();
+        let mut input = input_with_model_id("claude-opus");
+        input.cwd = tmp.path().to_string_lossy().into_owned();
+        let cfg = Config {
+            format: "$claude_model".into(),
+            project_config: true,
+            ..Default::default()
+        };
+        let err = Engine::new(cfg).render(&input).unwrap_err();
+        assert!(matches!(err, CoreError::ConfigParse { .. }), "{err:?}");
+        assert!(!tmp.path().join("pwned").exists());
+    }
+
+    #[test]
+    fn merged_project_config_is_validated() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join(".git")).unwrap();
+        std::fs::write(
+            tmp.path().join(crate::config::PROJECT_CONFIG_FILE),
+            "format = \"$claude_model\"\n",
+        )
+        .unwrap();
+        let mut input = input_with_model_id("claude-opus");
+        input.cwd = tmp.path().to_string_lossy().into_owned();
+        let cfg = Config {
+            command_timeout: 10,
+            project_config: true,
+            ..Default::default()
+        };
+        let err = Engine::new(cfg).render(&input).unwrap_err();
+        assert!(matches!(err, CoreError::InvalidConfig(_)), "{err:?}");
+    }
+
+    #[test]
+    fn invalid_project_config_is_a_parse_error() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join(".git")).unwrap();
+        std::fs::write(
+            tmp.path().join(crate::config::PROJECT_CONFIG_FILE),
+            "format = [",
+        )
+        .unwrap();
+        let mut input = input_with_model_id("claude-opus");
+        input.cwd = tmp.path().to_string_lossy().into_owned();
+        let cfg = Config {
+            project_config: true,
+            ..Default::default()
+        };
+        let err = Engine::new(cfg).render(&input).unwrap_err();
+        assert!(matches!(err, CoreError::ConfigParse { .. }), "{err:?}");
+    }
 }
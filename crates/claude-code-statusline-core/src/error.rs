@@ -1,4 +1,5 @@
 //! Core error types
+use crate::messages::{MSG_FAILED_INVALID_CONFIG, MSG_FAILED_INVALID_JSON, MSG_FAILED_RENDER};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -10,10 +11,14 @@ pub enum CoreError {
     MissingConfig(String),
     #[error("module timed out: {0}")]
     ModuleTimeout(String),
+    #[error("module '{name}' panicked: {message}")]
+    ModulePanic { name: String, message: String },
 
     // Config and parsing
     #[error("invalid JSON input: {0}")]
     InvalidJson(#[from] serde_json::Error),
+    #[error("input larger than {max} bytes")]
+    InputTooLarge { max: usize },
     #[error("invalid TOML: {0}")]
     InvalidToml(#[from] toml::de::Error),
 
@@ -36,6 +41,8 @@ pub enum CoreError {
     TaskPanic,
     #[error("worker disconnected")]
     WorkerDisconnected,
+    #[error("too many background workers still running (limit {0})")]
+    WorkerLimit(usize),
 
     // Validation
     #[error("invalid config: {0}")]
@@ -46,3 +53,128 @@ pub enum CoreError {
     #[error(transparent)]
     Git(#[from] git2::Error),
 }
+
+/// What a [`CoreError`] is about, for choosing the fallback line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCategory {
+    /// Stdin is not usable Claude Code JSON
+    InvalidInput,
+    /// The config file can't be read, parsed or validated
+    InvalidConfig,
+    /// Anything that fails while rendering
+    Render,
+}
+
+impl CoreError {
+    /// The category this error belongs to
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            CoreError::InvalidJson(_) | CoreError::InputTooLarge { .. } => {
+                ErrorCategory::InvalidInput
+            }
+            CoreError::InvalidToml(_)
+            | CoreError::ConfigRead { .. }
+            | CoreError::ConfigParse { .. }
+            | CoreError::InvalidConfig(_) => ErrorCategory::InvalidConfig,
+            CoreError::UnknownModule(_)
+            | CoreError::MissingConfig(_)
+            | CoreError::ModuleTimeout(_)
+            | CoreError::ModulePanic { .. }
+            | CoreError::Io(_)
+            | CoreError::TaskPanic
+            | CoreError::WorkerDisconnected
+            | CoreError::WorkerLimit(_) => ErrorCategory::Render,
+            #[cfg(feature = "git")]
+            CoreError::Git(_) => ErrorCategory::Render,
+        }
+    }
+
+    /// Fallback line to show in place of the status line for this error
+    ///
+    /// Input errors map to [`MSG_FAILED_INVALID_JSON`], config errors to
+    /// [`MSG_FAILED_INVALID_CONFIG`] and everything else to
+    /// [`MSG_FAILED_RENDER`] (see [`CoreError::category`]). Use
+    /// [`MessagesConfig::for_error`](crate::types::config::MessagesConfig::for_error)
+    /// to honor the user's `[messages]` overrides once a config is loaded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use claude_code_statusline_core::{CoreError, messages};
+    ///
+    /// let err = CoreError::InputTooLarge { max: 16 };
+    /// assert_eq!(err.user_message(), messages::MSG_FAILED_INVALID_JSON);
+    /// ```
+    pub fn user_message(&self) -> &'static str {
+        match self.category() {
+            ErrorCategory::InvalidInput => MSG_FAILED_INVALID_JSON,
+            ErrorCategory::InvalidConfig => MSG_FAILED_INVALID_CONFIG,
+            ErrorCategory::Render => MSG_FAILED_RENDER,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn io_error() -> std::io::Error {
+        std::io::Error::other("boom")
+    }
+
+    fn toml_error() -> toml::de::Error {
+        toml::from_str::<toml::Table>("=").unwrap_err()
+    }
+
+    #[rstest]
+    #[case::invalid_json(
+        CoreError::InvalidJson(serde_json::from_str::<()>("{").unwrap_err()),
+        MSG_FAILED_INVALID_JSON
+    )]
+    #[case::input_too_large(CoreError::InputTooLarge { max: 1 }, MSG_FAILED_INVALID_JSON)]
+    #[case::invalid_toml(CoreError::InvalidToml(toml_error()), MSG_FAILED_INVALID_CONFIG)]
+    #[case::config_read(
+        CoreError::ConfigRead { path: "c.toml".into(), source: io_error() },
+        MSG_FAILED_INVALID_CONFIG
+    )]
+    #[case::config_parse(
+        CoreError::ConfigParse { path: "c.toml".into(), source: toml_error() },
+        MSG_FAILED_INVALID_CONFIG
+    )]
+    #[case::invalid_config(CoreError::InvalidConfig("x".into()), MSG_FAILED_INVALID_CONFIG)]
+    #[case::unknown_module(CoreError::UnknownModule("x".into()), MSG_FAILED_RENDER)]
+    #[case::missing_config(CoreError::MissingConfig("x".into()), MSG_FAILED_RENDER)]
+    #[case::module_timeout(CoreError::ModuleTimeout("x".into()), MSG_FAILED_RENDER)]
+    #[case::module_panic(
+        CoreError::ModulePanic { name: "x".into(), message: "y".into() },
+        MSG_FAILED_RENDER
+    )]
+    #[case::io(CoreError::Io(io_error()), MSG_FAILED_RENDER)]
+    #[case::task_panic(CoreError::TaskPanic, MSG_FAILED_RENDER)]
+    #[case::worker_disconnected(CoreError::WorkerDisconnected, MSG_FAILED_RENDER)]
+    #[case::worker_limit(CoreError::WorkerLimit(4), MSG_FAILED_RENDER)]
+    fn each_variant_maps_to_a_fallback_message(#[case] err: CoreError, #[case] expected: &str) {
+        assert_eq!(err.user_message(), expected);
+    }
+
+    #[rstest]
+    #[case::input(CoreError::InputTooLarge { max: 1 }, "(bad json)")]
+    #[case::config(CoreError::InvalidConfig("x".into()), "(config error)")]
+    #[case::render(CoreError::TaskPanic, MSG_FAILED_RENDER)]
+    fn messages_config_overrides_by_category(#[case] err: CoreError, #[case] expected: &str) {
+        let messages = crate::types::config::MessagesConfig {
+            fallback_invalid_json: Some("(bad json)".into()),
+            fallback_invalid_config: Some("(config error)".into()),
+            ..Default::default()
+        };
+        assert_eq!(messages.for_error(&err), expected);
+    }
+
+    #[cfg(feature = "git")]
+    #[test]
+    fn git_errors_map_to_render_message() {
+        let err = CoreError::Git(git2::Error::from_str("boom"));
+        assert_eq!(err.user_message(), MSG_FAILED_RENDER);
+    }
+}
@@ -10,15 +10,40 @@ pub mod error;
 pub mod messages;
 pub mod modules;
 pub mod parser;
+pub mod presets;
 pub mod style;
+pub mod symbols;
+#[cfg(test)]
+mod test_env;
 pub mod timeout;
+pub mod transcript;
 pub mod types;
 
+/// Names of the optional cargo features this build was compiled with
+pub fn enabled_features() -> Vec<&'static str> {
+    let mut features = Vec::new();
+    if cfg!(feature = "git") {
+        features.push("git");
+    }
+    if cfg!(feature = "parallel") {
+        features.push("parallel");
+    }
+    if cfg!(feature = "parallel-threads") {
+        features.push("parallel-threads");
+    }
+    if cfg!(feature = "schema") {
+        features.push("schema");
+    }
+    features
+}
+
 // Convenience re-exports for common types/functions
 pub use config::Config;
 pub use config::ConfigProvider;
 pub use config::config_path;
-pub use engine::Engine;
-pub use error::CoreError;
+pub use config::loaded_config_path;
+pub use config::writable_config_path;
+pub use engine::{Engine, RenderMeasurement};
+pub use error::{CoreError, ErrorCategory};
 pub use parser::parse_claude_input;
 pub use types::context::Context;
@@ -13,6 +13,9 @@ pub const MSG_FAILED_EMPTY_INPUT: &str = "Failed to build status line due to emp
 /// Error message displayed when JSON parsing fails
 pub const MSG_FAILED_INVALID_JSON: &str = "Failed to build status line due to invalid json";
 
+/// Error message displayed when rendering fails for any other reason
+pub const MSG_FAILED_RENDER: &str = "Failed to build status line";
+
 /// Generates a warning message for unknown style tokens
 ///
 /// # Arguments
@@ -57,3 +60,37 @@ pub fn warn_unknown_style_token(module_name: &str, token: &str) -> String {
 pub fn warn_unknown_format_token(token: &str) -> String {
     format!("Unknown format token: '${token}'")
 }
+
+/// Generates a warning message for `$style` used outside a style annotation
+///
+/// # Examples
+///
+/// ```
+/// use claude_code_statusline_core::messages::warn_bare_style_token;
+///
+/// let msg = warn_bare_style_token("format");
+/// assert_eq!(
+///     msg,
+///     "'$style' in format only applies inside a style annotation, e.g. '[$directory]($style)'"
+/// );
+/// ```
+pub fn warn_bare_style_token(location: &str) -> String {
+    format!(
+        "'$style' in {location} only applies inside a style annotation, e.g. '[$directory]($style)'"
+    )
+}
+
+/// Generates a warning message for a `directory.redact` entry that is not a
+/// valid regular expression
+///
+/// # Examples
+///
+/// ```
+/// use claude_code_statusline_core::messages::warn_redact_pattern_literal;
+///
+/// let msg = warn_redact_pattern_literal("a(b");
+/// assert_eq!(msg, "Invalid regex in directory.redact: 'a(b' (matched literally)");
+/// ```
+pub fn warn_redact_pattern_literal(pattern: &str) -> String {
+    format!("Invalid regex in directory.redact: '{pattern}' (matched literally)")
+}
@@ -0,0 +1,143 @@
+//! Claude Code version module
+//!
+//! This module shows the version of the Claude Code client reported in the
+//! hook input, which helps when comparing behavior across releases.
+
+use super::{Module, ModuleConfig};
+use crate::types::context::Context;
+
+/// Module that displays the Claude Code client version
+///
+/// # Configuration
+///
+/// ```toml
+/// [cc_version]
+/// format = "[$symbol$version]($style)"
+/// style = "bold white"
+/// symbol = "v"
+/// disabled = false
+/// ```
+///
+/// # Display Rules
+///
+/// - Only displays when the input carries a non-empty `version`
+/// - Can be disabled via configuration
+pub struct CcVersionModule;
+
+impl CcVersionModule {
+    /// Create a new CcVersionModule instance
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Create from Context (kept for compatibility)
+    pub fn from_context(_context: &Context) -> Self {
+        Self::new()
+    }
+}
+
+impl Default for CcVersionModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Module for CcVersionModule {
+    fn name(&self) -> &str {
+        "cc_version"
+    }
+
+    fn should_display(&self, context: &Context, config: &dyn ModuleConfig) -> bool {
+        if let Some(cfg) = config
+            .as_any()
+            .downcast_ref::<crate::types::config::CcVersionConfig>()
+        {
+            if cfg.disabled {
+                return false;
+            }
+        }
+        context
+            .input
+            .version
+            .as_deref()
+            .is_some_and(|v| !v.trim().is_empty())
+    }
+
+    fn render(&self, context: &Context, config: &dyn ModuleConfig) -> String {
+        let Some(version) = context.input.version.as_deref() else {
+            return String::new();
+        };
+
+        if let Some(cfg) = config
+            .as_any()
+            .downcast_ref::<crate::types::config::CcVersionConfig>()
+        {
+            use std::collections::HashMap;
+            let mut tokens = HashMap::new();
+            tokens.insert("version", version.to_string());
+            tokens.insert("symbol", cfg.symbol.clone());
+            return crate::style::render_with_style_template_depth(
+                cfg.format(),
+                &tokens,
+                cfg.style(),
+                context.color_depth(),
+            );
+        }
+
+        version.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::types::claude::{ClaudeInput, ModelInfo};
+    use crate::types::context::Context;
+    use rstest::*;
+
+    fn context_with_version(version: Option<&str>) -> Context {
+        let input = ClaudeInput {
+            hook_event_name: None,
+            session_id: "test-session".to_string(),
+            transcript_path: None,
+            cwd: "/test/dir".to_string(),
+            model: ModelInfo {
+                id: "claude-opus".to_string(),
+                display_name: "Opus".to_string(),
+            },
+            workspace: None,
+            version: version.map(str::to_string),
+            output_style: None,
+            exit_status: None,
+        };
+        Context::new(input, Config::default())
+    }
+
+    #[rstest]
+    fn renders_version_with_symbol() {
+        let module = CcVersionModule::new();
+        let context = context_with_version(Some("1.0.80"));
+        assert!(module.should_display(&context, &context.config.cc_version));
+        let rendered = module.render(&context, &context.config.cc_version);
+        let plain = String::from_utf8(strip_ansi_escapes::strip(rendered)).unwrap();
+        assert_eq!(plain, "v1.0.80");
+    }
+
+    #[rstest]
+    #[case(None)]
+    #[case(Some(""))]
+    fn hidden_without_version(#[case] version: Option<&str>) {
+        let module = CcVersionModule::new();
+        let context = context_with_version(version);
+        assert!(!module.should_display(&context, &context.config.cc_version));
+    }
+
+    #[rstest]
+    fn respects_disabled_flag() {
+        let module = CcVersionModule::new();
+        let mut context = context_with_version(Some("1.0.80"));
+        context.config.cc_version.disabled = true;
+        assert!(!module.should_display(&context, &context.config.cc_version));
+    }
+}
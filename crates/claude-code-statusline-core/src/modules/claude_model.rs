@@ -20,10 +20,19 @@ use crate::types::context::Context;
 /// style = "bold yellow"
 /// symbol = "<"
 /// disabled = false
+///
+/// [claude_model.aliases]
+/// "claude-3-5-sonnet" = "S3.5"
 /// ```
 ///
 /// # Display Rules
 ///
+/// - `aliases` maps a model id or display name to a short label
+/// - `symbols` selects `$symbol` by a substring of the model id
+/// - `strip_suffixes` / `strip_parenthetical` tidy the display name
+///   (e.g., "Sonnet 3.5 (New)" → "Sonnet 3.5") before compaction;
+///   `strip_parenthetical` also rewrites legacy names to the current order
+///   (e.g., "Claude 3.5 Sonnet (New)" → "Sonnet 3.5")
 /// - Compacts spaces before digits (e.g., "Sonnet 3.5" → "Sonnet3.5")
 /// - Only displays when model name is non-empty
 /// - Can be disabled via configuration
@@ -66,12 +75,32 @@ impl Module for ClaudeModelModule {
     }
 
     fn render(&self, context: &Context, config: &dyn ModuleConfig) -> String {
-        let model = context.model_display_name();
+        let cfg = config
+            .as_any()
+            .downcast_ref::<crate::types::config::ClaudeModelConfig>();
+
+        // Aliases take precedence: match the model id first, then display name
+        let alias = cfg.and_then(|c| {
+            c.aliases
+                .get(&context.input.model.id)
+                .or_else(|| c.aliases.get(context.model_display_name()))
+        });
+        let model = match alias {
+            Some(a) => a.as_str(),
+            None => context.model_display_name(),
+        };
 
         // Compact pattern like "Opus 4.1" or "Sonnet 4" -> "Opus4.1" / "Sonnet4"
         // Rule: remove a single space immediately before a digit.
-        let compacted_model = {
-            let s = model;
+        // Aliases are user-chosen labels and are used verbatim.
+        let compacted_model = if alias.is_some() {
+            model.to_string()
+        } else {
+            let tidied = match cfg {
+                Some(cfg) => tidy_display_name(model, cfg),
+                None => model.to_string(),
+            };
+            let s = tidied.as_str();
             let mut out = String::with_capacity(s.len());
             let chars: Vec<char> = s.chars().collect();
             let len = chars.len();
@@ -89,21 +118,97 @@ impl Module for ClaudeModelModule {
             out
         };
 
-        if let Some(cfg) = config
-            .as_any()
-            .downcast_ref::<crate::types::config::ClaudeModelConfig>()
-        {
+        if let Some(cfg) = cfg {
             use std::collections::HashMap;
             let mut tokens = HashMap::new();
             tokens.insert("model", compacted_model);
-            tokens.insert("symbol", cfg.symbol.clone());
-            return crate::style::render_with_style_template(cfg.format(), &tokens, cfg.style());
+            tokens.insert(
+                "symbol",
+                select_symbol(cfg, &context.input.model.id, context.config.symbol_mode).to_string(),
+            );
+            return crate::style::render_with_style_template_depth(
+                cfg.format(),
+                &tokens,
+                cfg.style(),
+                context.color_depth(),
+            );
         }
 
         compacted_model
     }
 }
 
+/// Remove the configured suffixes and, with `strip_parenthetical`, any
+/// `(...)` parts from a display name
+///
+/// Suffixes are stripped repeatedly so their order doesn't matter; the
+/// result has single spaces and no surrounding whitespace. With
+/// `strip_parenthetical`, legacy `Claude <version> <family>` names are
+/// rewritten as `<family> <version>` to match current display names.
+fn tidy_display_name(name: &str, cfg: &crate::types::config::ClaudeModelConfig) -> String {
+    let mut name = name.to_string();
+    if cfg.strip_parenthetical {
+        let mut depth = 0usize;
+        name.retain(|c| match c {
+            '(' => {
+                depth += 1;
+                false
+            }
+            ')' if depth > 0 => {
+                depth -= 1;
+                false
+            }
+            _ => depth == 0,
+        });
+    }
+    let mut trimmed = name.trim_end();
+    while let Some(rest) = cfg
+        .strip_suffixes
+        .iter()
+        .filter(|s| !s.is_empty())
+        .find_map(|s| trimmed.strip_suffix(s.as_str()))
+    {
+        trimmed = rest.trim_end();
+    }
+    let words: Vec<&str> = trimmed.split_whitespace().collect();
+    if cfg.strip_parenthetical {
+        if let ["Claude", version, family @ ..] = words.as_slice() {
+            if version.starts_with(|c: char| c.is_ascii_digit()) && !family.is_empty() {
+                return format!("{} {version}", family.join(" "));
+            }
+        }
+    }
+    words.join(" ")
+}
+
+/// Pick the symbol whose key occurs in the model id, else `cfg.symbol`
+/// (whose default follows `symbol_mode`)
+fn select_symbol<'a>(
+    cfg: &'a crate::types::config::ClaudeModelConfig,
+    id: &str,
+    mode: crate::symbols::SymbolMode,
+) -> &'a str {
+    match_model_family(&cfg.symbols, id)
+        .map(String::as_str)
+        .unwrap_or_else(|| crate::symbols::resolve("claude_model", &cfg.symbol, mode))
+}
+
+/// Look up the value whose key is a (case-insensitive) substring of `id`
+///
+/// The longest matching key wins so that overlapping keys (e.g. `sonnet`
+/// and `sonnet-4`) resolve deterministically regardless of map order.
+pub(crate) fn match_model_family<'a, V>(
+    table: &'a std::collections::HashMap<String, V>,
+    id: &str,
+) -> Option<&'a V> {
+    let id = id.to_ascii_lowercase();
+    table
+        .iter()
+        .filter(|(key, _)| !key.is_empty() && id.contains(&key.to_ascii_lowercase()))
+        .max_by(|(a, _), (b, _)| a.len().cmp(&b.len()).then_with(|| b.cmp(a)))
+        .map(|(_, value)| value)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -129,6 +234,7 @@ mod tests {
             }),
             version: Some("1.0.0".to_string()),
             output_style: None,
+            exit_status: None,
         };
         Context::new(input, Config::default())
     }
@@ -181,6 +287,90 @@ mod tests {
         assert_eq!(module.name(), "claude_model");
     }
 
+    #[rstest]
+    #[case("claude-3-5-sonnet", "Claude 3.5 Sonnet (New)", "S3.5")]
+    #[case("claude-unknown-id", "Opus 4.1", "O4.1")]
+    fn alias_hit_uses_short_label(
+        #[case] id: &str,
+        #[case] display_name: &str,
+        #[case] expected: &str,
+    ) {
+        let module = ClaudeModelModule::new();
+        let mut context = context_with_model(display_name);
+        context.input.model.id = id.to_string();
+        context
+            .config
+            .claude_model
+            .aliases
+            .insert("claude-3-5-sonnet".into(), "S3.5".into());
+        context
+            .config
+            .claude_model
+            .aliases
+            .insert("Opus 4.1".into(), "O4.1".into());
+        let rendered = module.render(&context, &context.config.claude_model);
+        let plain = String::from_utf8(strip_ansi_escapes::strip(rendered)).unwrap();
+        assert_eq!(plain, expected);
+    }
+
+    #[rstest]
+    #[case("claude-opus-4-1-20250805", "◆")]
+    #[case("claude-sonnet-4-20250514", "◇")]
+    #[case("claude-sonnet-4-5", "◈")]
+    #[case("some-unknown-model", "<")]
+    fn symbol_follows_model_id_family(#[case] id: &str, #[case] expected: &str) {
+        let module = ClaudeModelModule::new();
+        let mut context = context_with_model("Model");
+        context.input.model.id = id.to_string();
+        context.config.claude_model.symbol = "<".into();
+        let symbols = &mut context.config.claude_model.symbols;
+        symbols.insert("opus".into(), "◆".into());
+        symbols.insert("sonnet".into(), "◇".into());
+        symbols.insert("sonnet-4-5".into(), "◈".into());
+        let rendered = module.render(&context, &context.config.claude_model);
+        let plain = String::from_utf8(strip_ansi_escapes::strip(rendered)).unwrap();
+        assert_eq!(plain, format!("{expected}Model"));
+    }
+
+    #[rstest]
+    fn alias_miss_falls_through_to_compaction() {
+        let module = ClaudeModelModule::new();
+        let mut context = context_with_model("Sonnet 4");
+        context
+            .config
+            .claude_model
+            .aliases
+            .insert("claude-3-5-sonnet".into(), "S3.5".into());
+        let rendered = module.render(&context, &context.config.claude_model);
+        let plain = String::from_utf8(strip_ansi_escapes::strip(rendered)).unwrap();
+        assert_eq!(plain, "Sonnet4");
+    }
+
+    #[rstest]
+    #[case("Sonnet 3.5 (New)", true, &[], "Sonnet3.5")]
+    #[case("Claude 3.5 Sonnet (New)", true, &[], "Sonnet3.5")]
+    #[case("Claude 3.5 Sonnet", true, &[], "Sonnet3.5")]
+    #[case("Claude 3.5 Sonnet (New)", false, &[], "Claude3.5 Sonnet (New)")]
+    #[case("Claude Code", true, &[], "Claude Code")]
+    #[case("Opus (beta) 4.1", true, &[], "Opus4.1")]
+    #[case("Opus 4.1-20250805 (New)", false, &["(New)", "-20250805"], "Opus4.1")]
+    #[case("Opus 4.1", false, &[""], "Opus4.1")]
+    fn display_name_is_tidied_before_compaction(
+        #[case] display_name: &str,
+        #[case] strip_parenthetical: bool,
+        #[case] suffixes: &[&str],
+        #[case] expected: &str,
+    ) {
+        let module = ClaudeModelModule::new();
+        let mut context = context_with_model(display_name);
+        let cfg = &mut context.config.claude_model;
+        cfg.strip_parenthetical = strip_parenthetical;
+        cfg.strip_suffixes = suffixes.iter().map(|s| s.to_string()).collect();
+        let rendered = module.render(&context, &context.config.claude_model);
+        let plain = String::from_utf8(strip_ansi_escapes::strip(rendered)).unwrap();
+        assert_eq!(plain, expected);
+    }
+
     #[rstest]
     fn compacts_space_before_digits() {
         let module = ClaudeModelModule::new();
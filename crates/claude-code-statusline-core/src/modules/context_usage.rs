@@ -0,0 +1,231 @@
+//! Context usage module for displaying how full the context window is
+//!
+//! This module reads the latest token usage from the session transcript
+//! and shows it against the model's context window size.
+
+use super::{Module, ModuleConfig};
+use crate::types::context::Context;
+
+/// Module that displays context window usage as a percentage and gauge
+///
+/// # Configuration
+///
+/// ```toml
+/// [context_usage]
+/// format = "[$bar $percentage%]($style)"
+/// style = "bold blue"
+/// default_max_tokens = 200000
+/// bar_width = 10
+/// bar_filled = "█"
+/// bar_empty = "░"
+/// disabled = false
+///
+/// [context_usage.max_tokens]
+/// "sonnet-4" = 1000000
+/// ```
+///
+/// # Display Rules
+///
+/// - Used tokens are the prompt-side tokens (input plus cache reads and
+///   writes) of the most recent main-thread response in the transcript
+/// - Hidden when the input has no `transcript_path`, the file can't be
+///   read, or it has no usage yet
+/// - `$percentage` can exceed 100; `$bar` is capped at full
+/// - Tokens: `$percentage`, `$bar`, `$used` and `$max` (e.g. `45k`)
+pub struct ContextUsageModule;
+
+impl ContextUsageModule {
+    /// Create a new ContextUsageModule instance
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Create from Context (kept for compatibility)
+    pub fn from_context(_context: &Context) -> Self {
+        Self::new()
+    }
+}
+
+impl Default for ContextUsageModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Module for ContextUsageModule {
+    fn name(&self) -> &str {
+        "context_usage"
+    }
+
+    fn should_display(&self, context: &Context, config: &dyn ModuleConfig) -> bool {
+        if let Some(cfg) = config
+            .as_any()
+            .downcast_ref::<crate::types::config::ContextUsageConfig>()
+        {
+            if cfg.disabled {
+                return false;
+            }
+        }
+        context
+            .input
+            .transcript_path
+            .as_deref()
+            .is_some_and(|p| !p.is_empty())
+    }
+
+    fn render(&self, context: &Context, config: &dyn ModuleConfig) -> String {
+        let Some(cfg) = config
+            .as_any()
+            .downcast_ref::<crate::types::config::ContextUsageConfig>()
+        else {
+            return String::new();
+        };
+        let Some(usage) = context
+            .input
+            .transcript_path
+            .as_deref()
+            .and_then(|p| crate::transcript::last_usage(std::path::Path::new(p)))
+        else {
+            return String::new();
+        };
+        let max = super::claude_model::match_model_family(&cfg.max_tokens, &context.input.model.id)
+            .copied()
+            .unwrap_or(cfg.default_max_tokens);
+        if max == 0 {
+            return String::new();
+        }
+        let used = usage.context_tokens();
+        let percentage = used.saturating_mul(100) / max;
+
+        // Default cells follow the top-level `symbol_mode`
+        let mode = context.config.symbol_mode;
+        let filled_sym = crate::symbols::resolve("context_usage.bar_filled", &cfg.bar_filled, mode);
+        let empty_sym = crate::symbols::resolve("context_usage.bar_empty", &cfg.bar_empty, mode);
+        let filled = (percentage.min(100) as usize * cfg.bar_width).div_ceil(100);
+        let bar = format!(
+            "{}{}",
+            filled_sym.repeat(filled),
+            empty_sym.repeat(cfg.bar_width - filled)
+        );
+
+        use std::collections::HashMap;
+        let mut tokens = HashMap::new();
+        tokens.insert("percentage", percentage.to_string());
+        tokens.insert("bar", bar);
+        tokens.insert("used", abbreviate_tokens(used));
+        tokens.insert("max", abbreviate_tokens(max));
+        crate::style::render_with_style_template_depth(
+            cfg.format(),
+            &tokens,
+            cfg.style(),
+            context.color_depth(),
+        )
+    }
+}
+
+/// Compact token count: `950`, `45k`, `1.2M`
+fn abbreviate_tokens(n: u64) -> String {
+    match n {
+        0..1_000 => n.to_string(),
+        1_000..1_000_000 => format!("{}k", n / 1_000),
+        _ if n % 1_000_000 == 0 => format!("{}M", n / 1_000_000),
+        _ => format!("{:.1}M", n as f64 / 1_000_000.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::types::claude::{ClaudeInput, ModelInfo};
+    use crate::types::context::Context;
+    use rstest::*;
+
+    /// Transcript whose latest response used `tokens` prompt tokens
+    fn write_transcript(dir: &std::path::Path, tokens: u64) -> String {
+        let path = dir.join("transcript.jsonl");
+        let lines = [
+            r#"{"type":"user","message":{"role":"user","content":"hi"}}"#.to_string(),
+            r#"{"type":"assistant","message":{"usage":{"input_tokens":1,"output_tokens":1}}}"#
+                .to_string(),
+            format!(
+                r#"{{"type":"assistant","message":{{"usage":{{"input_tokens":{},"cache_read_input_tokens":{},"output_tokens":12}}}}}}"#,
+                tokens / 10,
+                tokens - tokens / 10
+            ),
+        ];
+        std::fs::write(&path, lines.join("\n")).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    fn context_with_transcript(model_id: &str, transcript: Option<String>) -> Context {
+        let input = ClaudeInput {
+            hook_event_name: None,
+            session_id: "test-session".to_string(),
+            transcript_path: transcript,
+            cwd: "/test/dir".to_string(),
+            model: ModelInfo {
+                id: model_id.to_string(),
+                display_name: "Model".to_string(),
+            },
+            workspace: None,
+            version: None,
+            output_style: None,
+            exit_status: None,
+        };
+        Context::new(input, Config::default())
+    }
+
+    fn render_plain(context: &Context) -> String {
+        let module = ContextUsageModule::new();
+        if !module.should_display(context, &context.config.context_usage) {
+            return String::new();
+        }
+        let rendered = module.render(context, &context.config.context_usage);
+        String::from_utf8(strip_ansi_escapes::strip(rendered)).unwrap()
+    }
+
+    #[rstest]
+    #[case::quarter(50_000, "███░░░░░░░ 25%")]
+    #[case::empty(0, "░░░░░░░░░░ 0%")]
+    #[case::over_full(250_000, "██████████ 125%")]
+    fn renders_gauge_against_default_max(#[case] tokens: u64, #[case] expected: &str) {
+        let tmp = tempfile::tempdir().unwrap();
+        let context = context_with_transcript(
+            "claude-opus-4-1",
+            Some(write_transcript(tmp.path(), tokens)),
+        );
+        assert_eq!(render_plain(&context), expected);
+    }
+
+    #[rstest]
+    fn per_model_max_and_count_tokens() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut context = context_with_transcript(
+            "claude-sonnet-4-5",
+            Some(write_transcript(tmp.path(), 45_000)),
+        );
+        let cfg = &mut context.config.context_usage;
+        cfg.max_tokens.insert("sonnet".into(), 100_000);
+        cfg.max_tokens.insert("sonnet-4".into(), 1_000_000);
+        cfg.format = "$used/$max ($percentage%)".into();
+        assert_eq!(render_plain(&context), "45k/1M (4%)");
+    }
+
+    #[rstest]
+    #[case::no_path(None)]
+    #[case::missing_file(Some("/nonexistent/transcript.jsonl".to_string()))]
+    fn hidden_without_transcript(#[case] transcript: Option<String>) {
+        let context = context_with_transcript("claude-opus", transcript);
+        assert_eq!(render_plain(&context), "");
+    }
+
+    #[rstest]
+    fn respects_disabled_flag() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut context =
+            context_with_transcript("claude-opus", Some(write_transcript(tmp.path(), 1_000)));
+        context.config.context_usage.disabled = true;
+        assert_eq!(render_plain(&context), "");
+    }
+}
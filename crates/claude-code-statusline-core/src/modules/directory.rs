@@ -3,10 +3,14 @@
 //! This module shows the current directory path with home directory
 //! abbreviation (~) and optional truncation for long paths.
 
-use super::{Module, ModuleConfig};
+use super::{Module, ModuleConfig, ModuleRequirements};
 use crate::types::context::Context;
+use std::io::Read as _;
 use std::path::Path;
 
+/// Largest manifest read for `use_project_name`; bigger files are skipped
+const MANIFEST_MAX_BYTES: u64 = 64 * 1024;
+
 /// Module that displays the current working directory
 ///
 /// Features:
@@ -23,6 +27,11 @@ use std::path::Path;
 /// style = "bold cyan"
 /// truncation_length = 3
 /// truncate_to_repo = true
+/// truncate_outside_repo = false
+/// fish_style_pwd_dir_length = 0
+///
+/// [directory.substitutions]
+/// "~/work" = "💼"
 /// ```
 pub struct DirectoryModule;
 
@@ -45,6 +54,166 @@ impl DirectoryModule {
         }
     }
 
+    /// Keep the last `length` components of `path`, prefixed by `symbol`
+    /// when any component was dropped (the root `/` is not a component)
+    fn truncate_tail(&self, path: &str, length: usize, symbol: &str) -> String {
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        let tl = std::cmp::max(1, length);
+        if segments.len() <= tl {
+            return path.to_string();
+        }
+        format!("{symbol}{}", segments[segments.len() - tl..].join("/"))
+    }
+
+    /// Shorten every component of `path` except the last to its first
+    /// `length` characters, fish `prompt_pwd` style (`~/p/m/src`)
+    ///
+    /// `~` and the root are kept; hidden directories keep their leading `.`
+    /// on top of `length` characters.
+    fn abbreviate_components(&self, path: &str, length: usize) -> String {
+        let mut components: Vec<String> = path.split('/').map(str::to_string).collect();
+        let last = components.len() - 1;
+        for component in &mut components[..last] {
+            let keep = if component.starts_with('.') {
+                length + 1
+            } else {
+                length
+            };
+            if let Some((idx, _)) = component.char_indices().nth(keep) {
+                component.truncate(idx);
+            }
+        }
+        components.join("/")
+    }
+
+    /// Replace every match of the `patterns` in `path` with `mask`
+    ///
+    /// Patterns are regular expressions; one that fails to compile is
+    /// matched as a literal substring instead.
+    fn redact(&self, path: &str, patterns: &[String], mask: &str) -> String {
+        let mut out = path.to_string();
+        for pattern in patterns.iter().filter(|p| !p.is_empty()) {
+            let re =
+                regex::Regex::new(pattern).or_else(|_| regex::Regex::new(&regex::escape(pattern)));
+            if let Ok(re) = re {
+                out = re.replace_all(&out, regex::NoExpand(mask)).into_owned();
+            }
+        }
+        out
+    }
+
+    /// `substitutions` with home-abbreviated keys, longest key first
+    fn substitution_prefixes<'a>(
+        &self,
+        substitutions: &'a std::collections::HashMap<String, String>,
+    ) -> Vec<(String, &'a String)> {
+        let mut prefixes: Vec<(String, &String)> = substitutions
+            .iter()
+            .map(|(from, to)| {
+                let from = self.abbreviate_home(Path::new(from));
+                let trimmed = from.trim_end_matches('/');
+                let from = if trimmed.is_empty() { "/" } else { trimmed };
+                (from.to_string(), to)
+            })
+            .collect();
+        // Longest first; ties broken by key so the result is deterministic
+        prefixes.sort_by(|a, b| b.0.len().cmp(&a.0.len()).then_with(|| a.0.cmp(&b.0)));
+        prefixes
+    }
+
+    /// Replace the longest prefix of `path` found in `substitutions`
+    ///
+    /// Keys match whole components of the home-abbreviated path, so
+    /// `/Users/me/work` and `~/work` are equivalent and neither matches
+    /// `~/workshop`.
+    fn substitute(
+        &self,
+        path: &str,
+        substitutions: &std::collections::HashMap<String, String>,
+    ) -> String {
+        for (from, to) in self.substitution_prefixes(substitutions) {
+            if path == from {
+                return to.clone();
+            }
+            if let Some(rest) = path.strip_prefix(from.as_str()) {
+                if from == "/" {
+                    return format!("{to}/{rest}");
+                }
+                if rest.starts_with('/') {
+                    return format!("{to}{rest}");
+                }
+            }
+        }
+        path.to_string()
+    }
+
+    /// The shell's logical working directory (`PWD`) when it is the same
+    /// directory as `current_dir` once symlinks are resolved
+    fn logical_dir(&self, current_dir: &Path) -> Option<std::path::PathBuf> {
+        let pwd = std::path::PathBuf::from(std::env::var_os("PWD")?);
+        if !pwd.is_absolute() {
+            return None;
+        }
+        let same = std::fs::canonicalize(&pwd).ok()? == std::fs::canonicalize(current_dir).ok()?;
+        same.then_some(pwd)
+    }
+
+    /// Name of the main repository when the current repo is a linked worktree
+    #[cfg(feature = "git")]
+    fn main_repo_name(&self, context: &Context) -> Option<String> {
+        let repo = context.repo().ok()?;
+        if !repo.is_worktree() {
+            return None;
+        }
+        // `commondir()` is `<main>/.git/` (or the bare repository itself)
+        let common = repo.commondir();
+        let main = match common.file_name() {
+            Some(name) if name == ".git" => common.parent()?,
+            _ => common,
+        };
+        main.file_name().map(|s| s.to_string_lossy().to_string())
+    }
+
+    #[cfg(not(feature = "git"))]
+    fn main_repo_name(&self, _context: &Context) -> Option<String> {
+        None
+    }
+
+    /// Package name declared by the first manifest found in `root`
+    ///
+    /// Checks `Cargo.toml` (`[package]`), `package.json` and `pyproject.toml`
+    /// (`[project]`, then `[tool.poetry]`). Manifests that are too large,
+    /// unreadable or unparsable are skipped.
+    fn project_name(&self, root: &Path) -> Option<String> {
+        let read = |file: &str| {
+            let mut contents = String::new();
+            std::fs::File::open(root.join(file))
+                .ok()?
+                .take(MANIFEST_MAX_BYTES + 1)
+                .read_to_string(&mut contents)
+                .ok()?;
+            (contents.len() as u64 <= MANIFEST_MAX_BYTES).then_some(contents)
+        };
+        let toml_name = |file: &str, tables: &[&[&str]]| {
+            let manifest: toml::Table = toml::from_str(&read(file)?).ok()?;
+            tables.iter().find_map(|path| {
+                let mut value = manifest.get(path[0])?;
+                for key in &path[1..] {
+                    value = value.get(key)?;
+                }
+                value.get("name")?.as_str().map(str::to_string)
+            })
+        };
+        let json_name = |file: &str| {
+            let manifest: serde_json::Value = serde_json::from_str(&read(file)?).ok()?;
+            manifest.get("name")?.as_str().map(str::to_string)
+        };
+        toml_name("Cargo.toml", &[&["package"]])
+            .or_else(|| json_name("package.json"))
+            .or_else(|| toml_name("pyproject.toml", &[&["project"], &["tool", "poetry"]]))
+            .filter(|name| !name.trim().is_empty())
+    }
+
     /// Abbreviate home directory to ~ (cross-platform)
     fn abbreviate_home(&self, path: &Path) -> String {
         if let Some(home) = self.resolve_home_dir() {
@@ -70,6 +239,14 @@ impl Module for DirectoryModule {
         "directory"
     }
 
+    /// Repository-relative paths need the repo root
+    fn requires(&self) -> ModuleRequirements {
+        ModuleRequirements {
+            repo_root: true,
+            ..Default::default()
+        }
+    }
+
     fn should_display(&self, _context: &Context, config: &dyn ModuleConfig) -> bool {
         // Check if the module is disabled in config
         if let Some(cfg) = config
@@ -90,43 +267,39 @@ impl Module for DirectoryModule {
             // If truncate_to_repo is enabled and we're inside a repo, construct
             // a repository-relative path: `<repo-name>/<sub/dirs>`, truncated to
             // at most `truncation_length` segments, always keeping the repo name.
-            let mut repo_root: Option<std::path::PathBuf> = None;
-
-            if cfg.truncate_to_repo {
-                #[cfg(feature = "git")]
-                {
-                    if let Ok(repo) = context.repo() {
-                        if let Some(wd) = repo.workdir() {
-                            if context.current_dir.starts_with(wd) {
-                                repo_root = Some(wd.to_path_buf());
-                            }
-                        }
-                    }
-                }
-                // Fallback discovery without git feature: look for a `.git` directory or file (worktrees)
-                if repo_root.is_none() {
-                    let mut p = context.current_dir.as_path();
-                    loop {
-                        let dot_git = p.join(".git");
-                        // In worktrees, `.git` can be a file; treat either as a repository marker
-                        if dot_git.is_dir() || dot_git.is_file() {
-                            repo_root = Some(p.to_path_buf());
-                            break;
-                        }
-                        match p.parent() {
-                            Some(parent) => p = parent,
-                            None => break,
-                        }
-                    }
-                }
-            }
+            let repo_root = if cfg.truncate_to_repo {
+                context.repo_root()
+            } else {
+                None
+            };
 
             let path_str = if let Some(root) = repo_root {
-                // repo name
-                let repo_name = root
-                    .file_name()
-                    .map(|s| s.to_string_lossy().to_string())
-                    .unwrap_or_else(|| root.display().to_string());
+                // repo name (optionally the main repository's for worktrees)
+                let main_name = if cfg.worktree_show_main {
+                    self.main_repo_name(context)
+                } else {
+                    None
+                };
+                // A substitution for exactly the repo root replaces its name
+                let root_display = self.abbreviate_home(&root);
+                let substituted = self
+                    .substitution_prefixes(&cfg.substitutions)
+                    .into_iter()
+                    .find(|(from, _)| *from == root_display)
+                    .map(|(_, to)| to.clone());
+                let project_name = if cfg.use_project_name {
+                    self.project_name(&root)
+                } else {
+                    None
+                };
+                let repo_name = substituted
+                    .or(project_name)
+                    .or(main_name)
+                    .unwrap_or_else(|| {
+                        root.file_name()
+                            .map(|s| s.to_string_lossy().to_string())
+                            .unwrap_or_else(|| root.display().to_string())
+                    });
 
                 // relative components from repo root to current dir
                 let mut segments: Vec<String> = vec![repo_name];
@@ -142,13 +315,27 @@ impl Module for DirectoryModule {
                     }
                 }
 
+                // At the root itself, optionally add the parent for context
+                if cfg.root_shows_parent && segments.len() == 1 {
+                    if let Some(parent) = root.parent().and_then(Path::file_name) {
+                        segments[0] = format!("{}/{}", parent.to_string_lossy(), segments[0]);
+                    }
+                }
+
                 // Truncate to at most `truncation_length` segments, preserving repo name
                 let tl = std::cmp::max(1, cfg.truncation_length);
-                if segments.len() > tl {
+                if cfg.fish_style_pwd_dir_length > 0 {
+                    self.abbreviate_components(&segments.join("/"), cfg.fish_style_pwd_dir_length)
+                } else if segments.len() > tl {
                     let keep_tail = tl.saturating_sub(1);
                     if keep_tail == 0 {
-                        // Only show repo name when nothing else is kept
-                        segments[0].clone()
+                        // Only the repo name is kept; mark the dropped tail
+                        let symbol = cfg.truncation_symbol.trim_end_matches('/');
+                        if symbol.is_empty() {
+                            segments[0].clone()
+                        } else {
+                            format!("{}/{symbol}", segments[0])
+                        }
                     } else {
                         let start = segments.len() - keep_tail;
                         let tail = &segments[start..];
@@ -165,14 +352,40 @@ impl Module for DirectoryModule {
                     segments.join("/")
                 }
             } else {
-                // Fallback to home abbreviation (legacy behavior)
-                self.abbreviate_home(&context.current_dir)
+                // Outside repositories: home abbreviation, substitutions, then
+                // (when enabled) keep the last `truncation_length` components
+                let logical = if cfg.use_logical_path {
+                    self.logical_dir(&context.current_dir)
+                } else {
+                    None
+                };
+                let path = self.abbreviate_home(logical.as_deref().unwrap_or(&context.current_dir));
+                let path = self.substitute(&path, &cfg.substitutions);
+                if cfg.fish_style_pwd_dir_length > 0 {
+                    self.abbreviate_components(&path, cfg.fish_style_pwd_dir_length)
+                } else if cfg.truncate_outside_repo {
+                    self.truncate_tail(&path, cfg.truncation_length, &cfg.truncation_symbol)
+                } else {
+                    path
+                }
+            };
+
+            // Masking runs last so it also covers the repository name
+            let path_str = if cfg.redact.is_empty() {
+                path_str
+            } else {
+                self.redact(&path_str, &cfg.redact, &cfg.redact_mask)
             };
 
             use std::collections::HashMap;
             let mut tokens: HashMap<&str, String> = HashMap::new();
             tokens.insert("path", path_str.clone());
-            return crate::style::render_with_style_template(cfg.format(), &tokens, cfg.style());
+            return crate::style::render_with_style_template_depth(
+                cfg.format(),
+                &tokens,
+                cfg.style(),
+                context.color_depth(),
+            );
         }
 
         // No config found: return plain abbreviated path
@@ -184,11 +397,11 @@ impl Module for DirectoryModule {
 mod tests {
     use super::*;
     use crate::config::Config;
+    use crate::test_env::env_lock;
     use crate::types::claude::{ClaudeInput, ModelInfo, WorkspaceInfo};
     use crate::types::context::Context;
     use rstest::*;
     use std::fs::create_dir_all;
-    use std::sync::{Mutex, OnceLock};
 
     /// Fixture for creating test contexts
     #[fixture]
@@ -208,6 +421,7 @@ mod tests {
             }),
             version: Some("1.0.0".to_string()),
             output_style: None,
+            exit_status: None,
         };
         Context::new(input, Config::default())
     }
@@ -229,6 +443,7 @@ mod tests {
             }),
             version: Some("1.0.0".to_string()),
             output_style: None,
+            exit_status: None,
         };
         Context::new(input, Config::default())
     }
@@ -246,9 +461,7 @@ mod tests {
     #[case("/Users/test/Documents/code", "~/Documents/code")]
     fn test_home_directory_abbreviation(#[case] cwd: &str, #[case] expected: &str) {
         let module = DirectoryModule::new();
-        // Serialize HOME mutation to avoid test flakiness in parallel runs
-        static HOME_ENV_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
-        let _guard = HOME_ENV_LOCK.get_or_init(|| Mutex::new(())).lock().unwrap();
+        let _guard = env_lock();
         // Save and set HOME environment variable
         let original_home = std::env::var("HOME").ok();
         unsafe {
@@ -270,6 +483,53 @@ mod tests {
         }
     }
 
+    #[rstest]
+    #[case::home("/Users/test/projects/myapp/src", 1, "~/p/m/src")]
+    #[case::longer("/Users/test/projects/myapp/src", 2, "~/pr/my/src")]
+    #[case::hidden("/Users/test/.config/nvim", 1, "~/.c/nvim")]
+    #[case::absolute("/var/www/html/app/src", 1, "/v/w/h/a/src")]
+    fn fish_style_abbreviates_all_but_last_component(
+        #[case] cwd: &str,
+        #[case] length: usize,
+        #[case] expected: &str,
+    ) {
+        let _guard = env_lock();
+        let original_home = std::env::var("HOME").ok();
+        unsafe {
+            std::env::set_var("HOME", "/Users/test");
+        }
+
+        let mut context = context_with_cwd(cwd);
+        context.config.directory.fish_style_pwd_dir_length = length;
+        let module = DirectoryModule::new();
+        let rendered = module.render(&context, &context.config.directory);
+        let plain = String::from_utf8(strip_ansi_escapes::strip(rendered)).unwrap();
+
+        unsafe {
+            match original_home {
+                Some(home) => std::env::set_var("HOME", home),
+                None => std::env::remove_var("HOME"),
+            }
+        }
+        assert_eq!(plain, expected);
+    }
+
+    #[rstest]
+    fn fish_style_applies_to_repo_relative_path() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().join("myrepo");
+        create_dir_all(root.join(".git")).unwrap();
+        let cwd = root.join("crates/core/src");
+        create_dir_all(&cwd).unwrap();
+
+        let mut context = context_with_cwd(cwd.to_str().unwrap());
+        context.config.directory.fish_style_pwd_dir_length = 1;
+        let module = DirectoryModule::new();
+        let rendered = module.render(&context, &context.config.directory);
+        let plain = String::from_utf8(strip_ansi_escapes::strip(rendered)).unwrap();
+        assert_eq!(plain, "m/c/c/src");
+    }
+
     #[rstest]
     #[case("/var/www/html", "/var/www/html")]
     #[case("/tmp/test", "/tmp/test")]
@@ -282,6 +542,225 @@ mod tests {
         assert_eq!(plain, expected);
     }
 
+    #[rstest]
+    #[case::dropped_with_symbol("/var/www/html/app/src", 3, "…/", "…/html/app/src")]
+    #[case::dropped_without_symbol("/var/www/html/app/src", 3, "", "html/app/src")]
+    #[case::exact_fit("/usr/local/bin", 3, "…/", "/usr/local/bin")]
+    #[case::shorter("/var/www", 3, "…/", "/var/www")]
+    #[case::length_one("/var/www/html", 1, "…/", "…/html")]
+    #[case::root("/", 1, "…/", "/")]
+    fn non_repo_truncation_symbol_only_when_truncated(
+        #[case] cwd: &str,
+        #[case] length: usize,
+        #[case] symbol: &str,
+        #[case] expected: &str,
+    ) {
+        let mut context = context_with_cwd(cwd);
+        context.config.directory.truncate_outside_repo = true;
+        context.config.directory.truncation_length = length;
+        context.config.directory.truncation_symbol = symbol.to_string();
+        let module = DirectoryModule::new();
+        let rendered = module.render(&context, &context.config.directory);
+        let plain = String::from_utf8(strip_ansi_escapes::strip(rendered)).unwrap();
+        assert_eq!(plain, expected);
+    }
+
+    #[rstest]
+    #[case::root_anchor("/usr/local/lib/python3", "/usr/local/lib/python3")]
+    #[case::home_anchor("~/a/b/c/d", "~/a/b/c/d")]
+    fn non_repo_path_is_not_truncated_by_default(#[case] cwd: &str, #[case] expected: &str) {
+        let home = dirs::home_dir().unwrap();
+        let cwd = match cwd.strip_prefix("~/") {
+            Some(rest) => home.join(rest),
+            None => std::path::PathBuf::from(cwd),
+        };
+        let context = context_with_cwd(cwd.to_str().unwrap());
+        let module = DirectoryModule::new();
+        let rendered = module.render(&context, &context.config.directory);
+        let plain = String::from_utf8(strip_ansi_escapes::strip(rendered)).unwrap();
+        assert_eq!(plain, expected);
+    }
+
+    #[rstest]
+    #[case::dropped_with_symbol("a/b/c", 2, "…/", "repo/…/c")]
+    #[case::dropped_without_symbol("a/b/c", 2, "", "repo/c")]
+    #[case::only_repo_kept_with_symbol("a/b", 1, "…/", "repo/…")]
+    #[case::only_repo_kept_without_symbol("a/b", 1, "", "repo")]
+    #[case::exact_fit("a/b", 3, "…/", "repo/a/b")]
+    #[case::at_root("", 1, "…/", "repo")]
+    fn repo_truncation_symbol_only_when_truncated(
+        #[case] sub: &str,
+        #[case] length: usize,
+        #[case] symbol: &str,
+        #[case] expected: &str,
+    ) {
+        // A bare `.git` marker directory is enough for repo detection
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().join("repo");
+        create_dir_all(root.join(".git")).unwrap();
+        let cwd = root.join(sub);
+        create_dir_all(&cwd).unwrap();
+
+        let mut context = context_with_cwd(cwd.to_str().unwrap());
+        context.config.directory.truncation_length = length;
+        context.config.directory.truncation_symbol = symbol.to_string();
+        let module = DirectoryModule::new();
+        let rendered = module.render(&context, &context.config.directory);
+        let plain = String::from_utf8(strip_ansi_escapes::strip(rendered)).unwrap();
+        assert_eq!(plain, expected);
+    }
+
+    #[rstest]
+    #[case::prefix("/var/work/app", &[("/var/work", "💼")], "💼/app")]
+    #[case::exact("/var/work", &[("/var/work", "💼")], "💼")]
+    #[case::longest_wins("/var/work/app/src", &[("/var/work", "W"), ("/var/work/app", "A")], "A/src")]
+    #[case::component_boundary("/var/workshop/x", &[("/var/work", "W")], "/var/workshop/x")]
+    #[case::trailing_slash_key("/var/work/app", &[("/var/work/", "W")], "W/app")]
+    #[case::before_truncation("/var/a/b/c/d", &[("/var/a", "V")], "b/c/d")]
+    #[case::label_counts_as_segment("/var/a/b/c", &[("/var/a", "V")], "V/b/c")]
+    fn substitutions_replace_longest_prefix(
+        #[case] cwd: &str,
+        #[case] subs: &[(&str, &str)],
+        #[case] expected: &str,
+    ) {
+        let mut context = context_with_cwd(cwd);
+        context.config.directory.truncate_outside_repo = true;
+        context.config.directory.truncation_length = 3;
+        context.config.directory.truncation_symbol = String::new();
+        context.config.directory.substitutions = subs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let module = DirectoryModule::new();
+        let rendered = module.render(&context, &context.config.directory);
+        let plain = String::from_utf8(strip_ansi_escapes::strip(rendered)).unwrap();
+        assert_eq!(plain, expected);
+    }
+
+    #[rstest]
+    #[case::substring(&["alice"], "/Users/***/work")]
+    #[case::regex(&["/Users/[^/]+"], "***/work")]
+    #[case::invalid_regex_is_literal(&["ali(ce"], "/Users/alice/work")]
+    #[case::every_match(&["a"], "/Users/***lice/work")]
+    fn redact_masks_matches_after_truncation(#[case] patterns: &[&str], #[case] expected: &str) {
+        let mut context = context_with_cwd("/Users/alice/work");
+        context.config.directory.truncation_length = 3;
+        context.config.directory.redact = patterns.iter().map(|p| p.to_string()).collect();
+        let module = DirectoryModule::new();
+        let rendered = module.render(&context, &context.config.directory);
+        let plain = String::from_utf8(strip_ansi_escapes::strip(rendered)).unwrap();
+        assert_eq!(plain, expected);
+    }
+
+    #[test]
+    fn redact_applies_to_the_repository_name() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().join("alice-notes");
+        create_dir_all(root.join(".git")).unwrap();
+        let cwd = root.join("src");
+        create_dir_all(&cwd).unwrap();
+
+        let mut context = context_with_cwd(cwd.to_str().unwrap());
+        context.config.directory.redact = vec!["alice".to_string()];
+        context.config.directory.redact_mask = "#".to_string();
+        let module = DirectoryModule::new();
+        let rendered = module.render(&context, &context.config.directory);
+        let plain = String::from_utf8(strip_ansi_escapes::strip(rendered)).unwrap();
+        assert_eq!(plain, "#-notes/src");
+    }
+
+    #[rstest]
+    #[case::cargo("Cargo.toml", "[package]\nname = \"my-crate\"\n", "my-crate/src")]
+    #[case::cargo_workspace("Cargo.toml", "[workspace]\nmembers = []\n", "repo/src")]
+    #[case::npm(
+        "package.json",
+        "{\"name\": \"@scope/web\", \"version\": \"1.0.0\"}",
+        "@scope/web/src"
+    )]
+    #[case::npm_invalid("package.json", "{\"name\": ", "repo/src")]
+    #[case::pyproject("pyproject.toml", "[tool.poetry]\nname = \"py-app\"\n", "py-app/src")]
+    fn project_name_labels_the_repository(
+        #[case] manifest: &str,
+        #[case] contents: &str,
+        #[case] expected: &str,
+    ) {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().join("repo");
+        create_dir_all(root.join(".git")).unwrap();
+        let cwd = root.join("src");
+        create_dir_all(&cwd).unwrap();
+        std::fs::write(root.join(manifest), contents).unwrap();
+
+        let mut context = context_with_cwd(cwd.to_str().unwrap());
+        context.config.directory.use_project_name = true;
+        let module = DirectoryModule::new();
+        let rendered = module.render(&context, &context.config.directory);
+        let plain = String::from_utf8(strip_ansi_escapes::strip(rendered)).unwrap();
+        assert_eq!(plain, expected);
+
+        context.config.directory.use_project_name = false;
+        let rendered = module.render(&context, &context.config.directory);
+        let plain = String::from_utf8(strip_ansi_escapes::strip(rendered)).unwrap();
+        assert_eq!(plain, "repo/src");
+    }
+
+    #[test]
+    fn oversized_manifest_is_ignored() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().join("repo");
+        create_dir_all(root.join(".git")).unwrap();
+        let padding = " ".repeat(MANIFEST_MAX_BYTES as usize);
+        std::fs::write(
+            root.join("Cargo.toml"),
+            format!("[package]\nname = \"big\"\n{padding}"),
+        )
+        .unwrap();
+        assert_eq!(DirectoryModule::new().project_name(&root), None);
+    }
+
+    #[rstest]
+    #[case::repo_root_label(true, "📦/a")]
+    #[case::parent_prefix_ignored(false, "repo/a")]
+    fn substitutions_in_repo_apply_to_the_root(#[case] exact: bool, #[case] expected: &str) {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().join("repo");
+        create_dir_all(root.join(".git")).unwrap();
+        let cwd = root.join("a");
+        create_dir_all(&cwd).unwrap();
+
+        let mut context = context_with_cwd(cwd.to_str().unwrap());
+        let key = if exact { root.as_path() } else { tmp.path() };
+        context.config.directory.substitutions =
+            [(key.to_string_lossy().to_string(), "📦".to_string())].into();
+        let module = DirectoryModule::new();
+        let rendered = module.render(&context, &context.config.directory);
+        let plain = String::from_utf8(strip_ansi_escapes::strip(rendered)).unwrap();
+        assert_eq!(plain, expected);
+    }
+
+    #[rstest]
+    #[case::root_on(true, "", "work/myrepo")]
+    #[case::root_off(false, "", "myrepo")]
+    #[case::subdir_on(true, "src", "myrepo/src")]
+    fn root_shows_parent_only_at_repo_root(
+        #[case] enabled: bool,
+        #[case] sub: &str,
+        #[case] expected: &str,
+    ) {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().join("work").join("myrepo");
+        create_dir_all(root.join(".git")).unwrap();
+        let cwd = root.join(sub);
+        create_dir_all(&cwd).unwrap();
+
+        let mut context = context_with_cwd(cwd.to_str().unwrap());
+        context.config.directory.root_shows_parent = enabled;
+        let module = DirectoryModule::new();
+        let rendered = module.render(&context, &context.config.directory);
+        let plain = String::from_utf8(strip_ansi_escapes::strip(rendered)).unwrap();
+        assert_eq!(plain, expected);
+    }
+
     #[cfg(feature = "git")]
     fn init_git_repo(root: &std::path::Path) -> git2::Repository {
         use git2::Repository;
@@ -304,6 +783,47 @@ mod tests {
         repo
     }
 
+    #[cfg(feature = "git")]
+    #[rstest]
+    #[case::worktree_name(false, "feature-wt/src")]
+    #[case::main_name(true, "main-repo/src")]
+    fn worktree_show_main_uses_main_repo_name(#[case] show_main: bool, #[case] expected: &str) {
+        let tmp = tempfile::tempdir().unwrap();
+        let main = tmp.path().join("main-repo");
+        create_dir_all(&main).unwrap();
+        let repo = init_git_repo(&main);
+        let wt_path = tmp.path().join("feature-wt");
+        repo.worktree("feature", &wt_path, None).unwrap();
+        let sub = wt_path.join("src");
+        create_dir_all(&sub).unwrap();
+
+        let mut ctx = context_with_cwd(sub.to_str().unwrap());
+        ctx.config.directory.worktree_show_main = show_main;
+
+        let module = DirectoryModule::new();
+        let rendered = module.render(&ctx, &ctx.config.directory);
+        let plain = String::from_utf8(strip_ansi_escapes::strip(rendered)).unwrap();
+        assert_eq!(plain, expected);
+    }
+
+    #[cfg(feature = "git")]
+    #[rstest]
+    fn bare_repo_renders_plain_path() {
+        let tmp = tempfile::tempdir().unwrap();
+        let bare = tmp.path().join("repo.git");
+        git2::Repository::init_bare(&bare).unwrap();
+
+        let mut ctx = context_with_cwd(bare.to_str().unwrap());
+        ctx.config.directory.truncate_outside_repo = true;
+        ctx.config.directory.truncation_length = 1;
+        assert_eq!(ctx.repo_root(), None);
+
+        let module = DirectoryModule::new();
+        let rendered = module.render(&ctx, &ctx.config.directory);
+        let plain = String::from_utf8(strip_ansi_escapes::strip(rendered)).unwrap();
+        assert_eq!(plain, "repo.git");
+    }
+
     #[cfg(feature = "git")]
     #[rstest]
     fn repo_root_displays_repo_name_only() {
@@ -327,6 +847,7 @@ mod tests {
             }),
             version: Some("1.0.0".into()),
             output_style: None,
+            exit_status: None,
         };
         let mut cfg = crate::config::Config::default();
         cfg.directory.truncate_to_repo = true;
@@ -364,6 +885,7 @@ mod tests {
             }),
             version: Some("1.0.0".into()),
             output_style: None,
+            exit_status: None,
         };
         let mut cfg = crate::config::Config::default();
         cfg.directory.truncate_to_repo = true;
@@ -404,6 +926,7 @@ mod tests {
             }),
             version: Some("1.0.0".into()),
             output_style: None,
+            exit_status: None,
         };
         let mut cfg = crate::config::Config::default();
         cfg.directory.truncate_to_repo = true;
@@ -444,6 +967,7 @@ mod tests {
             }),
             version: Some("1.0.0".into()),
             output_style: None,
+            exit_status: None,
         };
         let mut cfg = crate::config::Config::default();
         cfg.directory.truncate_to_repo = true;
@@ -482,6 +1006,7 @@ mod tests {
             }),
             version: Some("1.0.0".into()),
             output_style: None,
+            exit_status: None,
         };
         let mut cfg = crate::config::Config::default();
         cfg.directory.truncate_to_repo = true;
@@ -521,6 +1046,7 @@ mod tests {
             }),
             version: Some("1.0.0".into()),
             output_style: None,
+            exit_status: None,
         };
         let mut cfg = crate::config::Config::default();
         cfg.directory.truncate_to_repo = true;
@@ -536,4 +1062,38 @@ mod tests {
             format!("{repo}/{a}/{b}", repo = repo_name, a = "src", b = "module")
         );
     }
+
+    #[cfg(unix)]
+    #[rstest]
+    #[case::physical(false, "real")]
+    #[case::logical(true, "alias")]
+    fn logical_path_keeps_symlink_name_from_pwd(#[case] logical: bool, #[case] expected: &str) {
+        let _guard = env_lock();
+        let tmp = tempfile::tempdir().unwrap();
+        let real = tmp.path().join("real");
+        create_dir_all(&real).unwrap();
+        let alias = tmp.path().join("alias");
+        std::os::unix::fs::symlink(&real, &alias).unwrap();
+
+        let original_pwd = std::env::var_os("PWD");
+        unsafe {
+            std::env::set_var("PWD", &alias);
+        }
+        let mut context = context_with_cwd(real.to_str().unwrap());
+        context.config.directory.truncate_to_repo = false;
+        context.config.directory.truncate_outside_repo = true;
+        context.config.directory.truncation_length = 1;
+        context.config.directory.use_logical_path = logical;
+        let module = DirectoryModule::new();
+        let rendered = module.render(&context, &context.config.directory);
+        unsafe {
+            match original_pwd {
+                Some(pwd) => std::env::set_var("PWD", pwd),
+                None => std::env::remove_var("PWD"),
+            }
+        }
+
+        let plain = String::from_utf8(strip_ansi_escapes::strip(rendered)).unwrap();
+        assert_eq!(plain, expected);
+    }
 }
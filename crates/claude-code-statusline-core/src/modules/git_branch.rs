@@ -3,7 +3,7 @@
 //! This module shows the current git branch or commit SHA when
 //! in a git repository.
 
-use super::{Module, ModuleConfig};
+use super::{Module, ModuleConfig, ModuleRequirements};
 use crate::types::context::Context;
 use std::process::Command;
 
@@ -17,17 +17,36 @@ use std::process::Command;
 ///
 /// ```toml
 /// [git_branch]
-/// format = "[$symbol$branch(:$remote_branch)]($style)"
+/// format = "[$symbol $branch]($style)"
 /// style = "bold purple"
 /// symbol = ""
+/// truncation_length = 20
+/// truncation_symbol = "…"
+/// symbols = [["main", "🏠"], ["feature/*", "🌱"]]
+/// strip_prefixes = ["feature/"]
+/// prefer_tag = false
+/// show_ahead_behind = false
 /// disabled = false
 /// ```
 ///
 /// # Display Behavior
 ///
 /// - Branch name: Shows current branch (e.g., "main", "feature/xyz")
-/// - Detached HEAD: Shows short commit SHA
-/// - Outside repo: Module is hidden
+/// - `$symbol`: the symbol of the first `symbols` pattern matching the
+///   full branch name, or `symbol` when none matches (or on a detached HEAD)
+/// - The first matching entry of `strip_prefixes` is removed from the name
+/// - Long branch names keep their first `truncation_length` characters
+///   followed by `truncation_symbol` (`0` disables truncation)
+/// - Detached HEAD: Shows short commit SHA (never truncated), or with
+///   `prefer_tag = true` the name of a tag pointing at HEAD when one exists
+/// - `$remote`: upstream of the current branch (e.g. "origin/main"),
+///   empty when no upstream is configured; not part of the default format
+/// - `$ahead_behind`: with `show_ahead_behind = true`, the same indicator
+///   as `git_status` (e.g. "⇡2"); empty without an upstream. Add it to
+///   `format` to show it
+/// - Outside repo, or unborn HEAD (no commits yet): Module is hidden
+/// - The top-level `git_backend` picks libgit2 (`libgit2`), the `git`
+///   command (`cli`) or libgit2 with the command as fallback (`auto`)
 pub struct GitBranchModule;
 
 impl GitBranchModule {
@@ -52,6 +71,13 @@ impl Module for GitBranchModule {
         "git_branch"
     }
 
+    fn requires(&self) -> ModuleRequirements {
+        ModuleRequirements {
+            git: true,
+            ..Default::default()
+        }
+    }
+
     fn should_display(&self, context: &Context, config: &dyn ModuleConfig) -> bool {
         // disabled フラグを確認
         if let Some(cfg) = config
@@ -63,49 +89,74 @@ impl Module for GitBranchModule {
             }
         }
 
-        // Display only when inside a Git repository (fallback to `git` command on failure)
-        if context.repo().is_ok() {
-            return true;
+        // Display only when inside a Git repository with a born HEAD
+        // (fallback to `git` command on failure, as `git_backend` allows)
+        let backend = context.config.git_backend;
+        if backend.uses_libgit2() {
+            if let Some(snapshot) = context.git_snapshot() {
+                return !snapshot.unborn;
+            }
+        }
+        if !backend.uses_cli() {
+            return false;
         }
-        // Fallback: `git -C <cwd> rev-parse --is-inside-work-tree`
-        if let Ok(out) = Command::new("git")
+        // Fallback: `git -C <cwd> rev-parse --verify --quiet HEAD` succeeds
+        // only inside a work tree whose HEAD points at a commit
+        Command::new("git")
             .args([
                 "-C",
                 context.current_dir.to_string_lossy().as_ref(),
                 "rev-parse",
-                "--is-inside-work-tree",
+                "--verify",
+                "--quiet",
+                "HEAD",
             ])
             .output()
-        {
-            if out.status.success() {
-                let s = String::from_utf8_lossy(&out.stdout);
-                return s.trim() == "true";
-            }
-        }
-        false
+            .map(|out| out.status.success())
+            .unwrap_or(false)
     }
 
     fn render(&self, context: &Context, config: &dyn ModuleConfig) -> String {
-        // Try git2 first via memoized Context repo
-        let value = match context.repo() {
-            Ok(repo) => {
-                if let Ok(head) = repo.head() {
-                    if head.is_branch() {
-                        head.shorthand().unwrap_or("").to_string()
-                    } else if let Some(oid) = head.target() {
-                        let s = oid.to_string();
-                        s.chars().take(7).collect()
-                    } else {
-                        String::new()
-                    }
-                } else {
-                    String::new()
+        let branch_cfg = config
+            .as_any()
+            .downcast_ref::<crate::types::config::GitBranchConfig>();
+        let prefer_tag = branch_cfg.is_some_and(|cfg| cfg.prefer_tag);
+        // The upstream costs an extra `git` call on the command fallback
+        let wants_remote = branch_cfg.is_some_and(|cfg| {
+            crate::parser::extract_modules_from_format(&cfg.format)
+                .iter()
+                .any(|name| name == "remote")
+        });
+        // Whether `value` is a short SHA (or tag) rather than a branch name
+        let mut detached = false;
+        let mut remote = None;
+
+        // Try the frame's shared git snapshot first
+        let backend = context.config.git_backend;
+        let snapshot = backend
+            .uses_libgit2()
+            .then(|| context.git_snapshot())
+            .flatten();
+        let value = match snapshot {
+            Some(snapshot) => match (&snapshot.branch, snapshot.head) {
+                (Some(branch), _) => {
+                    remote = snapshot.upstream.clone();
+                    branch.clone()
                 }
-            }
-            Err(_) => String::new(),
+                (None, Some(oid)) => {
+                    detached = true;
+                    prefer_tag
+                        .then(|| context.repo().ok())
+                        .flatten()
+                        .and_then(|repo| tag_pointing_at(&repo, oid))
+                        .unwrap_or_else(|| oid.to_string().chars().take(7).collect())
+                }
+                (None, None) => String::new(),
+            },
+            None => String::new(),
         };
 
-        let value = if value.is_empty() {
+        let value = if value.is_empty() && backend.uses_cli() {
             // Fallback using `git` command
             let cwd = context.current_dir.to_string_lossy().to_string();
             // Try branch name first
@@ -116,10 +167,33 @@ impl Module for GitBranchModule {
                 if out.status.success() {
                     let s = String::from_utf8_lossy(&out.stdout).trim().to_string();
                     if !s.is_empty() && s != "HEAD" {
+                        remote = wants_remote
+                            .then(|| {
+                                Command::new("git")
+                                    .args(["-C", &cwd, "rev-parse", "--abbrev-ref", "@{upstream}"])
+                                    .output()
+                                    .ok()
+                                    .filter(|o| o.status.success())
+                                    .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+                            })
+                            .flatten();
                         s
                     } else {
-                        // Detached HEAD -> short sha
-                        if let Ok(out2) = Command::new("git")
+                        // Detached HEAD -> tag (when preferred) or short sha
+                        detached = true;
+                        let tag = prefer_tag
+                            .then(|| {
+                                Command::new("git")
+                                    .args(["-C", &cwd, "describe", "--tags", "--exact-match"])
+                                    .output()
+                                    .ok()
+                                    .filter(|o| o.status.success())
+                                    .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+                            })
+                            .flatten();
+                        if let Some(tag) = tag {
+                            tag
+                        } else if let Ok(out2) = Command::new("git")
                             .args(["-C", &cwd, "rev-parse", "--short", "HEAD"])
                             .output()
                         {
@@ -142,26 +216,124 @@ impl Module for GitBranchModule {
             value
         };
 
+        // Nothing to show (e.g. unborn HEAD): collapse instead of leaving a
+        // dangling symbol
+        if value.is_empty() {
+            return String::new();
+        }
+
         if let Some(cfg) = config
             .as_any()
             .downcast_ref::<crate::types::config::GitBranchConfig>()
         {
             use std::collections::HashMap;
+            let branch = if detached {
+                value.clone()
+            } else {
+                let stripped = cfg
+                    .strip_prefixes
+                    .iter()
+                    .filter(|prefix| !prefix.is_empty())
+                    .find_map(|prefix| value.strip_prefix(prefix.as_str()))
+                    .unwrap_or(&value);
+                truncate_branch(
+                    stripped,
+                    cfg.truncation_length,
+                    crate::symbols::resolve(
+                        "git_branch.truncation_symbol",
+                        &cfg.truncation_symbol,
+                        context.config.symbol_mode,
+                    ),
+                )
+            };
             let mut tokens = HashMap::new();
-            tokens.insert("branch", value.clone());
-            tokens.insert("symbol", cfg.symbol.clone());
-            return crate::style::render_with_style_template(cfg.format(), &tokens, cfg.style());
+            tokens.insert("branch", branch);
+            // Counted through libgit2 only
+            let ahead_behind = if cfg.show_ahead_behind && remote.is_some() && snapshot.is_some() {
+                super::git_status::ahead_behind_text(context, &context.config.git_status.symbols)
+            } else {
+                String::new()
+            };
+            tokens.insert("ahead_behind", ahead_behind);
+            tokens.insert("remote", remote.unwrap_or_default());
+            let mode = context.config.symbol_mode;
+            let symbol = (!detached)
+                .then(|| {
+                    cfg.symbols
+                        .iter()
+                        .find(|(pattern, _)| glob_match(pattern, &value))
+                })
+                .flatten()
+                .map_or_else(
+                    || crate::symbols::resolve("git_branch", &cfg.symbol, mode),
+                    |(_, symbol)| symbol.as_str(),
+                );
+            tokens.insert("symbol", symbol.to_string());
+            return crate::style::render_with_style_template_depth(
+                cfg.format(),
+                &tokens,
+                cfg.style(),
+                context.color_depth(),
+            );
         }
 
         value
     }
 }
 
+/// Name of a tag pointing at `oid` (first in sorted order), if any
+fn tag_pointing_at(repo: &git2::Repository, oid: git2::Oid) -> Option<String> {
+    let names = repo.tag_names(None).ok()?;
+    names.iter().flatten().find_map(|name| {
+        let target = repo
+            .revparse_single(&format!("refs/tags/{name}"))
+            .and_then(|obj| obj.peel_to_commit())
+            .ok()?;
+        (target.id() == oid).then(|| name.to_string())
+    })
+}
+
+/// Whether `name` matches `pattern`, where `*` stands for any run of
+/// characters (including none) and everything else matches literally
+fn glob_match(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    // `split` always yields at least one part
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts: Vec<&str> = parts.collect();
+    let Some(last) = parts.pop() else {
+        // No `*`: exact match
+        return rest.is_empty();
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(idx) => rest = &rest[idx + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+/// Keep the first `length` characters of `branch`, appending `symbol` when
+/// anything was cut. `length == 0` leaves the name untouched.
+fn truncate_branch(branch: &str, length: usize, symbol: &str) -> String {
+    if length == 0 {
+        return branch.to_string();
+    }
+    match branch.char_indices().nth(length) {
+        Some((idx, _)) => format!("{}{symbol}", &branch[..idx]),
+        None => branch.to_string(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::config::Config;
     use crate::types::claude::{ClaudeInput, ModelInfo, WorkspaceInfo};
+    use crate::types::config::GitBackend;
     use crate::types::context::Context;
     use rstest::*;
 
@@ -189,6 +361,7 @@ mod tests {
             }),
             version: Some("1.0.0".to_string()),
             output_style: None,
+            exit_status: None,
         };
         Context::new(input, Config::default())
     }
@@ -268,6 +441,38 @@ mod tests {
         drop(repo);
     }
 
+    #[rstest]
+    fn unborn_head_hides_module_and_renders_nothing(temp_repo: (tempfile::TempDir, PathBuf)) {
+        let (_d, root) = temp_repo;
+        let _repo = Repository::init(&root).unwrap();
+
+        let ctx = make_context(root.to_str().unwrap());
+        let module = crate::modules::git_branch::GitBranchModule::new();
+        assert!(!module.should_display(&ctx, &ctx.config.git_branch));
+        assert_eq!(module.render(&ctx, &ctx.config.git_branch), "");
+    }
+
+    #[rstest]
+    fn bare_repo_renders_plain_branch_name() {
+        let tmp = tempdir().unwrap();
+        let bare = tmp.path().join("repo.git");
+        let repo = Repository::init_bare(&bare).unwrap();
+        // Commit an empty tree; bare repositories have no index/worktree
+        let sig = Signature::now("Tester", "tester@example.com").unwrap();
+        let tree_id = repo.treebuilder(None).unwrap().write().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "initial", &tree, &[])
+            .unwrap();
+        let branch = repo.head().unwrap().shorthand().unwrap().to_string();
+
+        let ctx = make_context(bare.to_str().unwrap());
+        let module = crate::modules::git_branch::GitBranchModule::new();
+        assert!(module.should_display(&ctx, &ctx.config.git_branch));
+        let rendered = module.render(&ctx, &ctx.config.git_branch);
+        let plain = String::from_utf8(strip_ansi_escapes::strip(rendered)).unwrap();
+        assert!(plain.ends_with(&branch), "{plain}");
+    }
+
     #[rstest]
     fn detached_head_renders_short_sha(temp_repo: (tempfile::TempDir, PathBuf)) {
         let (_d, root) = temp_repo;
@@ -284,6 +489,254 @@ mod tests {
         assert!(last.chars().all(|c| c.is_ascii_hexdigit()));
     }
 
+    #[rstest]
+    #[case::annotated_tag(true, Some(true), "v1.0")]
+    #[case::lightweight_tag(true, Some(false), "v1.0")]
+    #[case::no_tag_falls_back_to_sha(true, None, "")]
+    #[case::not_preferred(false, Some(true), "")]
+    fn detached_head_prefers_tag_when_configured(
+        temp_repo: (tempfile::TempDir, PathBuf),
+        #[case] prefer_tag: bool,
+        #[case] annotated: Option<bool>,
+        #[case] expected: &str,
+    ) {
+        let (_d, root) = temp_repo;
+        let repo = init_repo_with_branch(&root, "main");
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        match annotated {
+            Some(true) => {
+                let sig = Signature::now("Tester", "tester@example.com").unwrap();
+                repo.tag("v1.0", head.as_object(), &sig, "release", false)
+                    .unwrap();
+            }
+            Some(false) => {
+                repo.tag_lightweight("v1.0", head.as_object(), false)
+                    .unwrap();
+            }
+            None => {}
+        }
+        detach_head(&repo);
+
+        let mut ctx = make_context(root.to_str().unwrap());
+        ctx.config.git_branch.prefer_tag = prefer_tag;
+        ctx.config.git_branch.format = "$branch".to_string();
+        let module = GitBranchModule::new();
+        let rendered = module.render(&ctx, &ctx.config.git_branch);
+        let short_sha: String = head.id().to_string().chars().take(7).collect();
+        let expected = if expected.is_empty() {
+            short_sha.as_str()
+        } else {
+            expected
+        };
+        assert_eq!(rendered, expected);
+    }
+
+    // Helper: Create a branch at HEAD and check it out
+    fn checkout_new_branch(repo: &Repository, name: &str) {
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch(name, &head, false).unwrap();
+        repo.set_head(&format!("refs/heads/{name}")).unwrap();
+    }
+
+    #[rstest]
+    #[case("feature/JIRA-1234-really-long-description", "feature/JIRA-1234-re…")]
+    #[case("feature/exactly-20ch", "feature/exactly-20ch")]
+    fn long_branch_names_are_truncated(
+        temp_repo: (tempfile::TempDir, PathBuf),
+        #[case] branch: &str,
+        #[case] expected: &str,
+    ) {
+        let (_d, root) = temp_repo;
+        let repo = init_repo_with_branch(&root, "main");
+        checkout_new_branch(&repo, branch);
+
+        let mut ctx = make_context(root.to_str().unwrap());
+        ctx.config.git_branch.format = "$branch".to_string();
+        ctx.config.git_branch.truncation_length = 20;
+        let module = crate::modules::git_branch::GitBranchModule::new();
+        let rendered = module.render(&ctx, &ctx.config.git_branch);
+        assert_eq!(rendered, expected);
+    }
+
+    #[rstest]
+    #[case("feature/x", "x")]
+    #[case("refs-heads/main", "main")]
+    #[case("bugfix/feature/y", "bugfix/feature/y")]
+    fn configured_prefixes_are_stripped(
+        temp_repo: (tempfile::TempDir, PathBuf),
+        #[case] branch: &str,
+        #[case] expected: &str,
+    ) {
+        let (_d, root) = temp_repo;
+        let repo = init_repo_with_branch(&root, "main");
+        checkout_new_branch(&repo, branch);
+
+        let mut ctx = make_context(root.to_str().unwrap());
+        ctx.config.git_branch.format = "$branch".to_string();
+        ctx.config.git_branch.strip_prefixes = vec!["feature/".into(), "refs-heads/".into()];
+        let module = crate::modules::git_branch::GitBranchModule::new();
+        let rendered = module.render(&ctx, &ctx.config.git_branch);
+        assert_eq!(rendered, expected);
+    }
+
+    #[rstest]
+    #[case::exact("main", "main", true)]
+    #[case::exact_is_not_prefix("main", "main-2", false)]
+    #[case::trailing_star("feature/*", "feature/login", true)]
+    #[case::star_matches_empty("feature/*", "feature/", true)]
+    #[case::leading_star("*-hotfix", "v2-hotfix", true)]
+    #[case::inner_star("release/*/rc", "release/1.2/rc", true)]
+    #[case::inner_star_mismatch("release/*/rc", "release/1.2/final", false)]
+    #[case::parts_do_not_overlap("ab*ba", "aba", false)]
+    #[case::star_only("*", "anything", true)]
+    fn glob_patterns_match_branch_names(
+        #[case] pattern: &str,
+        #[case] name: &str,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(glob_match(pattern, name), expected);
+    }
+
+    #[rstest]
+    #[case::exact("trunk", "home trunk")]
+    #[case::glob_on_full_name("feature/login", "sprout login")]
+    #[case::fallthrough("bugfix/crash", "base bugfix/crash")]
+    fn symbol_is_picked_by_branch_pattern(
+        temp_repo: (tempfile::TempDir, PathBuf),
+        #[case] branch: &str,
+        #[case] expected: &str,
+    ) {
+        let (_d, root) = temp_repo;
+        let repo = init_repo_with_branch(&root, "main");
+        checkout_new_branch(&repo, branch);
+
+        let mut ctx = make_context(root.to_str().unwrap());
+        ctx.config.git_branch.format = "$symbol $branch".to_string();
+        ctx.config.git_branch.symbol = "base".to_string();
+        ctx.config.git_branch.strip_prefixes = vec!["feature/".into()];
+        ctx.config.git_branch.symbols = vec![
+            ("trunk".into(), "home".into()),
+            ("feature/*".into(), "sprout".into()),
+            ("feature/login".into(), "unreachable".into()),
+        ];
+        let module = crate::modules::git_branch::GitBranchModule::new();
+        let rendered = module.render(&ctx, &ctx.config.git_branch);
+        assert_eq!(rendered, expected);
+    }
+
+    #[rstest]
+    fn remote_token_shows_upstream_when_configured(temp_repo: (tempfile::TempDir, PathBuf)) {
+        let (_d, root) = temp_repo;
+        let repo = init_repo_with_branch(&root, "main");
+        checkout_new_branch(&repo, "topic");
+        let module = crate::modules::git_branch::GitBranchModule::new();
+
+        let render = |format: Option<&str>| {
+            let mut ctx = make_context(root.to_str().unwrap());
+            if let Some(format) = format {
+                ctx.config.git_branch.format = format.to_string();
+            }
+            let rendered = module.render(&ctx, &ctx.config.git_branch);
+            String::from_utf8(strip_ansi_escapes::strip(rendered)).unwrap()
+        };
+
+        // Without upstream: `$remote` is empty
+        assert_eq!(render(Some("$branch <$remote>")), "topic <>");
+
+        // Track a remote-tracking branch as upstream
+        let head = repo.head().unwrap().target().unwrap();
+        repo.remote("upstream", "https://example.invalid/repo.git")
+            .unwrap();
+        repo.reference("refs/remotes/upstream/topic", head, false, "test")
+            .unwrap();
+        repo.find_branch("topic", git2::BranchType::Local)
+            .unwrap()
+            .set_upstream(Some("upstream/topic"))
+            .unwrap();
+
+        assert_eq!(render(Some("$branch <$remote>")), "topic <upstream/topic>");
+        // Opt-in only: the default format leaves the upstream out
+        assert_eq!(render(None), "🌿 topic");
+    }
+
+    #[rstest]
+    fn ahead_behind_token_shows_commits_ahead_of_upstream(temp_repo: (tempfile::TempDir, PathBuf)) {
+        let (_d, root) = temp_repo;
+        let repo = init_repo_with_branch(&root, "main");
+        checkout_new_branch(&repo, "topic");
+        let module = crate::modules::git_branch::GitBranchModule::new();
+        let render = || {
+            let mut ctx = make_context(root.to_str().unwrap());
+            ctx.config.git_branch.show_ahead_behind = true;
+            ctx.config.git_branch.format = "$symbol $branch $ahead_behind".to_string();
+            let rendered = module.render(&ctx, &ctx.config.git_branch);
+            String::from_utf8(strip_ansi_escapes::strip(rendered)).unwrap()
+        };
+
+        // No upstream: the token stays empty
+        assert_eq!(render(), "🌿 topic ");
+
+        // Upstream at the current commit, then two commits on top of it
+        let head = repo.head().unwrap().target().unwrap();
+        repo.remote("origin", "https://example.invalid/repo.git")
+            .unwrap();
+        repo.reference("refs/remotes/origin/topic", head, false, "test")
+            .unwrap();
+        repo.find_branch("topic", git2::BranchType::Local)
+            .unwrap()
+            .set_upstream(Some("origin/topic"))
+            .unwrap();
+        let sig = Signature::now("Tester", "tester@example.com").unwrap();
+        for msg in ["second", "third"] {
+            let parent = repo.head().unwrap().peel_to_commit().unwrap();
+            repo.commit(
+                Some("HEAD"),
+                &sig,
+                &sig,
+                msg,
+                &parent.tree().unwrap(),
+                &[&parent],
+            )
+            .unwrap();
+        }
+
+        assert_eq!(render(), "🌿 topic ⇡2");
+    }
+
+    #[rstest]
+    #[case::auto(GitBackend::Auto)]
+    #[case::libgit2(GitBackend::Libgit2)]
+    #[case::cli(GitBackend::Cli)]
+    fn every_git_backend_renders_the_branch(
+        temp_repo: (tempfile::TempDir, PathBuf),
+        #[case] backend: GitBackend,
+    ) {
+        let (_d, root) = temp_repo;
+        let repo = init_repo_with_branch(&root, "main");
+        checkout_new_branch(&repo, "topic");
+
+        let mut ctx = make_context(root.to_str().unwrap());
+        ctx.config.git_backend = backend;
+        ctx.config.git_branch.format = "$branch".to_string();
+        let module = crate::modules::git_branch::GitBranchModule::new();
+        assert!(module.should_display(&ctx, &ctx.config.git_branch));
+        assert_eq!(module.render(&ctx, &ctx.config.git_branch), "topic");
+    }
+
+    #[rstest]
+    fn detached_head_sha_is_not_truncated(temp_repo: (tempfile::TempDir, PathBuf)) {
+        let (_d, root) = temp_repo;
+        let repo = init_repo_with_branch(&root, "main");
+        detach_head(&repo);
+
+        let mut ctx = make_context(root.to_str().unwrap());
+        ctx.config.git_branch.format = "$branch".to_string();
+        ctx.config.git_branch.truncation_length = 3;
+        let module = crate::modules::git_branch::GitBranchModule::new();
+        let rendered = module.render(&ctx, &ctx.config.git_branch);
+        assert_eq!(rendered.len(), 7, "got: {rendered}");
+    }
+
     #[rstest]
     fn disabled_flag_hides_output(temp_repo: (tempfile::TempDir, PathBuf)) {
         let (_d, root) = temp_repo;
@@ -296,4 +749,32 @@ mod tests {
         let module = crate::modules::git_branch::GitBranchModule::new();
         assert!(!module.should_display(&ctx, &ctx.config.git_branch));
     }
+
+    #[rstest]
+    #[case::none(None, " feature/Login ")]
+    #[case::upper(Some(crate::style::Transform::Upper), " FEATURE/LOGIN ")]
+    #[case::lower(Some(crate::style::Transform::Lower), " feature/login ")]
+    #[case::trim(Some(crate::style::Transform::Trim), "feature/Login")]
+    #[case::capitalize(Some(crate::style::Transform::Capitalize), " Feature/Login ")]
+    fn transform_post_processes_rendered_branch(
+        temp_repo: (tempfile::TempDir, PathBuf),
+        #[case] transform: Option<crate::style::Transform>,
+        #[case] expected: &str,
+    ) {
+        let (_d, root) = temp_repo;
+        let repo = init_repo_with_branch(&root, "main");
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("feature/Login", &head, false).unwrap();
+        repo.set_head("refs/heads/feature/Login").unwrap();
+
+        let mut ctx = make_context(root.to_str().unwrap());
+        ctx.config.git_branch.format = " [$branch]($style) ".to_string();
+        ctx.config.git_branch.transform = transform;
+        let logger = crate::debug::DebugLogger::new(false);
+        let rendered =
+            crate::modules::render_module_with_timeout("git_branch", &ctx, &logger).unwrap();
+        let plain = String::from_utf8(strip_ansi_escapes::strip(&rendered)).unwrap();
+        assert_eq!(plain, expected);
+        assert!(rendered.contains("\x1b["), "style kept: {rendered:?}");
+    }
 }
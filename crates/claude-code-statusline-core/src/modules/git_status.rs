@@ -3,7 +3,8 @@
 //! This module shows the current state of the git repository including
 //! modified files, staged changes, and branch divergence.
 
-use super::{Module, ModuleConfig};
+use super::{Module, ModuleConfig, ModuleRequirements};
+use crate::types::config::GitStatusSymbolsConfig;
 use crate::types::context::Context;
 
 /// Module that summarizes Git working tree and index state
@@ -12,8 +13,13 @@ use crate::types::context::Context;
 /// - Modified files (working tree changes)
 /// - Staged files (index changes)
 /// - Untracked files
-/// - Branch ahead/behind status relative to upstream
+/// - Branch ahead/behind status relative to upstream (or `compare_to`)
 /// - Conflicted files during merge
+/// - Ignored files (`$ignored`, only with `show_ignored = true`)
+/// - A clean working tree via `$clean` (when `symbols.clean` is set)
+/// - The number of changed files via `$changed` (e.g. `±3`), each file
+///   counted once even when it is in several categories; with
+///   `summary = true` it replaces the per-category symbols in `$all_status`
 ///
 /// # Configuration
 ///
@@ -31,6 +37,8 @@ use crate::types::context::Context;
 /// staged = "+"
 /// renamed = "»"
 /// deleted = "✘"
+/// ignored = "◌"
+/// show_ignored = false
 /// disabled = false
 /// ```
 ///
@@ -64,6 +72,13 @@ impl Module for GitStatusModule {
         "git_status"
     }
 
+    fn requires(&self) -> ModuleRequirements {
+        ModuleRequirements {
+            git: true,
+            ..Default::default()
+        }
+    }
+
     fn should_display(&self, context: &Context, config: &dyn ModuleConfig) -> bool {
         // disabled フラグ
         if let Some(cfg) = config
@@ -74,15 +89,13 @@ impl Module for GitStatusModule {
                 return false;
             }
         }
-        context.repo().is_ok()
+        // Bare repositories have no working tree to summarize
+        context
+            .git_snapshot()
+            .is_some_and(|snapshot| !snapshot.bare)
     }
 
     fn render(&self, context: &Context, config: &dyn ModuleConfig) -> String {
-        let mut repo = match context.repo() {
-            Ok(r) => r,
-            Err(_) => return String::new(),
-        };
-
         // Resolve config
         let cfg = match config
             .as_any()
@@ -91,90 +104,31 @@ impl Module for GitStatusModule {
             Some(c) => c,
             None => return String::new(),
         };
+        // Counts come from the frame's shared snapshot (hidden outside a
+        // repository and in bare ones)
+        let Some(counts) = context.git_status_counts() else {
+            return String::new();
+        };
 
-        // Count statuses (index vs worktree)
-        let mut conflicted = 0u32;
-        // presence only (count stashes)
-        let mut deleted = 0u32; // staged deletions
-        let mut renamed = 0u32; // staged renames
-        let mut modified = 0u32; // working tree modifications (unstaged)
-        let mut typechanged = 0u32; // staged type changes
-        let mut staged = 0u32; // staged (added/modified/renamed/deleted)
-        let mut untracked = 0u32; // untracked files
-
-        // Statuses
-        if let Ok(stats) = repo.statuses(None) {
-            use git2::Status;
-            for s in stats.iter().map(|e| e.status()) {
-                if s.intersects(Status::CONFLICTED) {
-                    conflicted += 1;
-                    continue;
-                }
-                if s.intersects(Status::WT_NEW) {
-                    untracked += 1;
-                }
-                if s.intersects(Status::WT_MODIFIED) {
-                    modified += 1;
-                }
-                if s.intersects(Status::INDEX_NEW | Status::INDEX_MODIFIED) {
-                    staged += 1;
-                }
-                if s.intersects(Status::INDEX_RENAMED) {
-                    renamed += 1;
-                    staged += 1;
-                }
-                if s.intersects(Status::INDEX_DELETED) {
-                    deleted += 1;
-                    staged += 1;
-                }
-                if s.intersects(Status::INDEX_TYPECHANGE) {
-                    typechanged += 1;
-                    staged += 1;
-                }
-            }
-        }
+        // Default symbols follow the top-level `symbol_mode`
+        let mode = context.config.symbol_mode;
+        let sym = |name: &str, configured: &str| {
+            crate::symbols::resolve(&format!("git_status.{name}"), configured, mode).to_string()
+        };
+        let deleted_sym = sym("deleted", &cfg.symbols.deleted);
+        let renamed_sym = sym("renamed", &cfg.symbols.renamed);
+        let ignored_sym = sym("ignored", &cfg.symbols.ignored);
+        let changed_sym = sym("changed", &cfg.symbols.changed);
 
-        // Stash presence (count stashes)
-        let mut stash_count = 0u32;
-        let _ = repo.stash_foreach(|_, _, _| {
-            stash_count += 1;
-            true
-        });
-        let stashed = stash_count;
-
-        // Ahead/behind/diverged
-        let mut ahead_behind = String::new();
-        if let Ok(head) = repo.head() {
-            if head.is_branch() {
-                if let Some(local_oid) = head.target() {
-                    let shorthand = head.shorthand().unwrap_or("");
-                    if let Ok(local_branch) = repo.find_branch(shorthand, git2::BranchType::Local) {
-                        if let Ok(up_branch) = local_branch.upstream() {
-                            if let Some(up_oid) = up_branch.get().target() {
-                                if let Ok((ahead, behind)) =
-                                    repo.graph_ahead_behind(local_oid, up_oid)
-                                {
-                                    if ahead > 0 && behind > 0 {
-                                        if !cfg.symbols.diverged.is_empty() {
-                                            ahead_behind = cfg.symbols.diverged.clone();
-                                        }
-                                    } else if ahead > 0 {
-                                        if !cfg.symbols.ahead.is_empty() {
-                                            ahead_behind =
-                                                format!("{}{}", cfg.symbols.ahead, ahead);
-                                        }
-                                    } else if behind > 0 && !cfg.symbols.behind.is_empty() {
-                                        ahead_behind = format!("{}{}", cfg.symbols.behind, behind);
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
+        let ahead_behind = ahead_behind_text(context, &cfg.symbols);
+
+        let changed = if counts.changed > 0 {
+            format!("{changed_sym}{}", counts.changed)
+        } else {
+            String::new()
+        };
 
-        // Compose $all_status: conflicted stashed deleted renamed modified typechanged staged untracked
+        // Compose $all_status: conflicted stashed deleted renamed modified typechanged staged untracked ignored
         let mut all_status = String::new();
         let mut push_sym = |sym: &str, count: u32| {
             if count > 0 && !sym.is_empty() {
@@ -183,29 +137,80 @@ impl Module for GitStatusModule {
             }
         };
 
-        push_sym(&cfg.symbols.conflicted, conflicted);
-        push_sym(&cfg.symbols.stashed, stashed);
-        push_sym(&cfg.symbols.deleted, deleted);
-        push_sym(&cfg.symbols.renamed, renamed);
-        push_sym(&cfg.symbols.modified, modified);
-        push_sym(&cfg.symbols.typechanged, typechanged);
-        push_sym(&cfg.symbols.staged, staged);
-        push_sym(&cfg.symbols.untracked, untracked);
+        push_sym(&cfg.symbols.conflicted, counts.conflicted);
+        push_sym(&cfg.symbols.stashed, counts.stashed);
+        push_sym(&deleted_sym, counts.deleted);
+        push_sym(&renamed_sym, counts.renamed);
+        push_sym(&cfg.symbols.modified, counts.modified);
+        push_sym(&cfg.symbols.typechanged, counts.typechanged);
+        push_sym(&cfg.symbols.staged, counts.staged);
+        push_sym(&cfg.symbols.untracked, counts.untracked);
+        push_sym(&ignored_sym, counts.ignored);
+        let ignored = if counts.ignored > 0 && !ignored_sym.is_empty() {
+            format!("{ignored_sym}{}", counts.ignored)
+        } else {
+            String::new()
+        };
+        if cfg.summary {
+            all_status = changed.clone();
+        }
 
         // If repository is completely clean (no status symbols and no ahead/behind),
-        // suppress the entire module output to avoid showing empty parentheses like `()`.
-        if all_status.is_empty() && ahead_behind.is_empty() {
+        // suppress the entire module output to avoid showing empty parentheses like `()`,
+        // unless a `clean` symbol is configured to confirm the state.
+        let is_clean = all_status.is_empty() && ahead_behind.is_empty();
+        if is_clean && cfg.symbols.clean.is_empty() {
             return String::new();
         }
+        let clean = if is_clean {
+            cfg.symbols.clean.clone()
+        } else {
+            String::new()
+        };
 
         // Tokens for template
         use std::collections::HashMap;
         let mut tokens = HashMap::new();
         tokens.insert("all_status", all_status);
         tokens.insert("ahead_behind", ahead_behind);
+        tokens.insert("clean", clean);
+        tokens.insert("ignored", ignored);
+        tokens.insert("changed", changed);
         tokens.insert("style", cfg.style.clone());
 
-        crate::style::render_with_style_template(cfg.format(), &tokens, cfg.style())
+        crate::style::render_with_style_template_depth(
+            cfg.format(),
+            &tokens,
+            cfg.style(),
+            context.color_depth(),
+        )
+    }
+}
+
+/// Ahead/behind/diverged indicator for the current branch, empty when in
+/// sync or without an upstream (or `compare_to`)
+///
+/// Shared with `git_branch`; default symbols follow the top-level
+/// `symbol_mode`.
+pub(crate) fn ahead_behind_text(context: &Context, symbols: &GitStatusSymbolsConfig) -> String {
+    let Some((ahead, behind)) = context.git_ahead_behind() else {
+        return String::new();
+    };
+    let mode = context.config.symbol_mode;
+    let sym = |name: &str, configured: &str| {
+        crate::symbols::resolve(&format!("git_status.{name}"), configured, mode).to_string()
+    };
+    let ahead_sym = sym("ahead", &symbols.ahead);
+    let behind_sym = sym("behind", &symbols.behind);
+    let diverged_sym = sym("diverged", &symbols.diverged);
+    if ahead > 0 && behind > 0 {
+        diverged_sym
+    } else if ahead > 0 && !ahead_sym.is_empty() {
+        format!("{ahead_sym}{ahead}")
+    } else if behind > 0 && !behind_sym.is_empty() {
+        format!("{behind_sym}{behind}")
+    } else {
+        String::new()
     }
 }
 
@@ -238,6 +243,7 @@ mod tests {
             }),
             version: Some("1.0.0".into()),
             output_style: None,
+            exit_status: None,
         };
         Context::new(input, Config::default())
     }
@@ -289,6 +295,50 @@ mod tests {
         assert!(!show);
     }
 
+    #[rstest]
+    #[case::unset(None, "")]
+    #[case::remote_ref(Some("origin/main"), "⇡1")]
+    #[case::missing_ref(Some("origin/nope"), "")]
+    fn compare_to_used_without_upstream(
+        temp_repo: (tempfile::TempDir, PathBuf, Repository),
+        #[case] compare_to: Option<&str>,
+        #[case] expected: &str,
+    ) {
+        use strip_ansi_escapes::strip;
+        let (_d, root, repo) = temp_repo;
+
+        // Remote-tracking ref at the initial commit, no upstream configured
+        let base = repo.head().unwrap().target().unwrap();
+        repo.reference("refs/remotes/origin/main", base, true, "test")
+            .unwrap();
+        let sig = Signature::now("Tester", "tester@example.com").unwrap();
+        let parent = repo.find_commit(base).unwrap();
+        let tree = parent.tree().unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "second", &tree, &[&parent])
+            .unwrap();
+        let main = repo.find_branch("main", BranchType::Local).unwrap();
+        assert!(main.upstream().is_err());
+
+        let mut ctx = make_context(root.to_str().unwrap());
+        ctx.config.git_status.compare_to = compare_to.map(str::to_string);
+        ctx.config.git_status.format = "[$ahead_behind]($style)".to_string();
+        let module = GitStatusModule::new();
+        let rendered = module.render(&ctx, &ctx.config.git_status);
+        assert_eq!(String::from_utf8(strip(rendered)).unwrap(), expected);
+    }
+
+    #[rstest]
+    fn bare_repo_is_hidden() {
+        let tmp = tempdir().unwrap();
+        let bare = tmp.path().join("repo.git");
+        let _repo = Repository::init_bare(&bare).unwrap();
+
+        let ctx = make_context(bare.to_str().unwrap());
+        let module = GitStatusModule::new();
+        assert!(!module.should_display(&ctx, &ctx.config.git_status));
+        assert_eq!(module.render(&ctx, &ctx.config.git_status), "");
+    }
+
     #[rstest]
     fn renders_counts_and_ahead(temp_repo: (tempfile::TempDir, PathBuf, Repository)) {
         use strip_ansi_escapes::strip;
@@ -347,6 +397,64 @@ mod tests {
         assert!(plain.contains("⇡1"));
     }
 
+    #[rstest]
+    #[case::detailed(false, "!1+1?1")]
+    #[case::summary(true, "±3")]
+    fn summary_total_is_sum_of_separate_categories(
+        temp_repo: (tempfile::TempDir, PathBuf, Repository),
+        #[case] summary: bool,
+        #[case] expected: &str,
+    ) {
+        use strip_ansi_escapes::strip;
+        let (_d, root, repo) = temp_repo;
+        // One file per category: staged, modified and untracked
+        std::fs::write(root.join("staged.txt"), "staged\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("staged.txt")).unwrap();
+        index.write().unwrap();
+        std::fs::write(root.join("README.md"), "changed\n").unwrap();
+        std::fs::write(root.join("untracked.txt"), "u\n").unwrap();
+
+        let mut ctx = make_context(root.to_str().unwrap());
+        ctx.config.git_status.summary = summary;
+        ctx.config.git_status.format = "[$all_status]($style)".to_string();
+        let counts = ctx.git_status_counts().unwrap();
+        assert_eq!(
+            counts.changed,
+            counts.modified + counts.staged + counts.untracked
+        );
+        let module = GitStatusModule::new();
+        let rendered = module.render(&ctx, &ctx.config.git_status);
+        assert_eq!(String::from_utf8(strip(rendered)).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case::detailed(false, "!1+1")]
+    #[case::summary(true, "±1")]
+    fn summary_counts_a_file_in_several_categories_once(
+        temp_repo: (tempfile::TempDir, PathBuf, Repository),
+        #[case] summary: bool,
+        #[case] expected: &str,
+    ) {
+        use strip_ansi_escapes::strip;
+        let (_d, root, repo) = temp_repo;
+        // Staged, then modified again in the working tree
+        std::fs::write(root.join("README.md"), "staged\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("README.md")).unwrap();
+        index.write().unwrap();
+        std::fs::write(root.join("README.md"), "modified\n").unwrap();
+
+        let mut ctx = make_context(root.to_str().unwrap());
+        ctx.config.git_status.summary = summary;
+        ctx.config.git_status.format = "[$all_status]($style)".to_string();
+        let counts = ctx.git_status_counts().unwrap();
+        assert_eq!((counts.staged, counts.modified, counts.changed), (1, 1, 1));
+        let module = GitStatusModule::new();
+        let rendered = module.render(&ctx, &ctx.config.git_status);
+        assert_eq!(String::from_utf8(strip(rendered)).unwrap(), expected);
+    }
+
     #[rstest]
     fn disabled_flag_hides_output(temp_repo: (tempfile::TempDir, PathBuf, Repository)) {
         let (_d, root, _repo) = temp_repo;
@@ -357,14 +465,103 @@ mod tests {
     }
 
     #[rstest]
-    fn clean_repo_renders_nothing(temp_repo: (tempfile::TempDir, PathBuf, Repository)) {
+    #[case::default_hides("", "[$all_status$ahead_behind$clean]($style)", "")]
+    #[case::clean_symbol("✓", "[$all_status$ahead_behind$clean]($style)", "✓")]
+    fn clean_repo_renders_clean_symbol_only_when_set(
+        temp_repo: (tempfile::TempDir, PathBuf, Repository),
+        #[case] clean: &str,
+        #[case] format: &str,
+        #[case] expected: &str,
+    ) {
         use strip_ansi_escapes::strip;
         let (_d, root, _repo) = temp_repo;
-        let ctx = make_context(root.to_str().unwrap());
+        let mut ctx = make_context(root.to_str().unwrap());
+        ctx.config.git_status.symbols.clean = clean.to_string();
+        ctx.config.git_status.format = format.to_string();
         let module = GitStatusModule::new();
         let rendered = module.render(&ctx, &ctx.config.git_status);
         let plain = String::from_utf8(strip(rendered)).unwrap();
-        println!("clean repo git_status plain='{plain}'");
-        assert!(plain.is_empty());
+        assert_eq!(plain, expected);
+    }
+
+    #[rstest]
+    #[case::default_hides("", false)]
+    #[case::clean_symbol("✓", true)]
+    fn default_format_shows_clean_symbol(
+        temp_repo: (tempfile::TempDir, PathBuf, Repository),
+        #[case] clean: &str,
+        #[case] shown: bool,
+    ) {
+        use strip_ansi_escapes::strip;
+        let (_d, root, _repo) = temp_repo;
+        let mut ctx = make_context(root.to_str().unwrap());
+        ctx.config.git_status.symbols.clean = clean.to_string();
+        let module = GitStatusModule::new();
+        let rendered = module.render(&ctx, &ctx.config.git_status);
+        let plain = String::from_utf8(strip(rendered)).unwrap();
+        assert_eq!(plain.contains('✓'), shown, "{plain}");
+        assert_eq!(plain.is_empty(), !shown, "{plain}");
+    }
+
+    #[rstest]
+    #[case::detected(true, "»1+1")]
+    #[case::as_delete_and_add(false, "✘1+2")]
+    fn staged_rename_is_counted_as_renamed(
+        temp_repo: (tempfile::TempDir, PathBuf, Repository),
+        #[case] detect_renames: bool,
+        #[case] expected: &str,
+    ) {
+        use strip_ansi_escapes::strip;
+        let (_d, root, repo) = temp_repo;
+        std::fs::rename(root.join("README.md"), root.join("MOVED.md")).unwrap();
+        let mut index = repo.index().unwrap();
+        index.remove_path(Path::new("README.md")).unwrap();
+        index.add_path(Path::new("MOVED.md")).unwrap();
+        index.write().unwrap();
+
+        let mut ctx = make_context(root.to_str().unwrap());
+        ctx.config.git_status.detect_renames = detect_renames;
+        ctx.config.git_status.format = "[$all_status]($style)".to_string();
+        let module = GitStatusModule::new();
+        let rendered = module.render(&ctx, &ctx.config.git_status);
+        assert_eq!(String::from_utf8(strip(rendered)).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case::shown(true, "[$all_status]($style)", "?1◌2")]
+    #[case::token_only(true, "[$ignored]($style)", "◌2")]
+    #[case::not_read_by_default(false, "[$all_status]($style)", "?1")]
+    fn ignored_files_are_counted_with_show_ignored(
+        temp_repo: (tempfile::TempDir, PathBuf, Repository),
+        #[case] show_ignored: bool,
+        #[case] format: &str,
+        #[case] expected: &str,
+    ) {
+        use strip_ansi_escapes::strip;
+        let (_d, root, _repo) = temp_repo;
+        std::fs::write(root.join(".gitignore"), "build/\n*.log\n").unwrap();
+        std::fs::write(root.join("debug.log"), "x").unwrap();
+        create_dir_all(root.join("build")).unwrap();
+        std::fs::write(root.join("build").join("out.o"), "x").unwrap();
+
+        let mut ctx = make_context(root.to_str().unwrap());
+        ctx.config.git_status.show_ignored = show_ignored;
+        ctx.config.git_status.format = format.to_string();
+        let module = GitStatusModule::new();
+        let rendered = module.render(&ctx, &ctx.config.git_status);
+        assert_eq!(String::from_utf8(strip(rendered)).unwrap(), expected);
+    }
+
+    #[rstest]
+    fn clean_symbol_is_empty_when_dirty(temp_repo: (tempfile::TempDir, PathBuf, Repository)) {
+        use strip_ansi_escapes::strip;
+        let (_d, root, _repo) = temp_repo;
+        std::fs::write(root.join("new.txt"), "x").unwrap();
+        let mut ctx = make_context(root.to_str().unwrap());
+        ctx.config.git_status.symbols.clean = "✓".to_string();
+        ctx.config.git_status.format = "[$all_status$clean]($style)".to_string();
+        let module = GitStatusModule::new();
+        let rendered = module.render(&ctx, &ctx.config.git_status);
+        assert_eq!(String::from_utf8(strip(rendered)).unwrap(), "?1");
     }
 }
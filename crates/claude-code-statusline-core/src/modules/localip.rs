@@ -0,0 +1,192 @@
+//! Local IP module for displaying the machine's primary LAN address
+//!
+//! Handy in remote or forwarded sessions to see which host the status
+//! line belongs to.
+
+use super::{Module, ModuleConfig};
+use crate::types::context::Context;
+use std::net::{IpAddr, Ipv4Addr, UdpSocket};
+
+/// Module that displays the primary non-loopback IPv4 address
+///
+/// # Configuration
+///
+/// ```toml
+/// [localip]
+/// format = "[$localipv4]($style)"
+/// style = "bold yellow"
+/// ssh_only = true
+/// disabled = false
+/// ```
+///
+/// # Display Rules
+///
+/// - With `ssh_only` (default) the module shows only inside SSH sessions
+/// - Renders nothing when no address can be determined
+pub struct LocalIpModule {
+    /// Source of the local address ([`primary_ipv4`] outside tests)
+    resolve: fn() -> Option<Ipv4Addr>,
+}
+
+impl LocalIpModule {
+    /// Create a new LocalIpModule instance
+    pub fn new() -> Self {
+        Self {
+            resolve: primary_ipv4,
+        }
+    }
+
+    /// Create a module that reads the local address from `resolve`
+    #[cfg(test)]
+    fn with_resolver(resolve: fn() -> Option<Ipv4Addr>) -> Self {
+        Self { resolve }
+    }
+
+    /// Create from Context (kept for compatibility)
+    pub fn from_context(_context: &Context) -> Self {
+        Self::new()
+    }
+}
+
+impl Default for LocalIpModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Whether the environment describes an SSH session
+fn is_ssh_session(env: impl Fn(&str) -> Option<String>) -> bool {
+    ["SSH_CONNECTION", "SSH_CLIENT", "SSH_TTY"]
+        .iter()
+        .any(|key| env(key).is_some_and(|v| !v.is_empty()))
+}
+
+/// Resolve the address of the interface used for outbound traffic
+///
+/// Connecting a UDP socket only selects a route; no packet is sent.
+fn primary_ipv4() -> Option<Ipv4Addr> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).ok()?;
+    socket.connect((Ipv4Addr::new(192, 0, 2, 1), 80)).ok()?;
+    match socket.local_addr().ok()?.ip() {
+        IpAddr::V4(ip) => Some(ip),
+        IpAddr::V6(_) => None,
+    }
+}
+
+impl Module for LocalIpModule {
+    fn name(&self) -> &str {
+        "localip"
+    }
+
+    fn should_display(&self, _context: &Context, config: &dyn ModuleConfig) -> bool {
+        if let Some(cfg) = config
+            .as_any()
+            .downcast_ref::<crate::types::config::LocalIpConfig>()
+        {
+            if cfg.disabled {
+                return false;
+            }
+            if cfg.ssh_only && !is_ssh_session(|key| std::env::var(key).ok()) {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn render(&self, context: &Context, config: &dyn ModuleConfig) -> String {
+        let Some(ip) = (self.resolve)().filter(|ip| !ip.is_loopback() && !ip.is_unspecified())
+        else {
+            return String::new();
+        };
+
+        if let Some(cfg) = config
+            .as_any()
+            .downcast_ref::<crate::types::config::LocalIpConfig>()
+        {
+            use std::collections::HashMap;
+            let mut tokens = HashMap::new();
+            tokens.insert("localipv4", ip.to_string());
+            return crate::style::render_with_style_template_depth(
+                cfg.format(),
+                &tokens,
+                cfg.style(),
+                context.color_depth(),
+            );
+        }
+
+        ip.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::types::claude::{ClaudeInput, ModelInfo};
+    use crate::types::context::Context;
+    use rstest::*;
+
+    fn make_context() -> Context {
+        let input = ClaudeInput {
+            hook_event_name: None,
+            session_id: "test-session".to_string(),
+            transcript_path: None,
+            cwd: "/test/dir".to_string(),
+            model: ModelInfo {
+                id: "claude-opus".to_string(),
+                display_name: "Opus".to_string(),
+            },
+            workspace: None,
+            version: None,
+            output_style: None,
+            exit_status: None,
+        };
+        Context::new(input, Config::default())
+    }
+
+    #[rstest]
+    #[case(&[], false)]
+    #[case(&[("SSH_CONNECTION", "10.0.0.2 5022 10.0.0.1 22")], true)]
+    #[case(&[("SSH_TTY", "/dev/pts/1")], true)]
+    #[case(&[("SSH_CLIENT", "")], false)]
+    fn detects_ssh_sessions(#[case] vars: &[(&str, &str)], #[case] expected: bool) {
+        let env = |key: &str| {
+            vars.iter()
+                .find(|(k, _)| *k == key)
+                .map(|(_, v)| v.to_string())
+        };
+        assert_eq!(is_ssh_session(env), expected);
+    }
+
+    #[rstest]
+    fn renders_the_resolved_address_with_style() {
+        let module = LocalIpModule::with_resolver(|| Some(Ipv4Addr::new(192, 168, 1, 20)));
+        let context = context_without_ssh_only();
+        let rendered = module.render(&context, &context.config.localip);
+        assert_eq!(rendered, "\x1b[1;33m192.168.1.20\x1b[0m");
+    }
+
+    #[rstest]
+    #[case::unresolved(|| None)]
+    #[case::loopback(|| Some(Ipv4Addr::LOCALHOST))]
+    #[case::unspecified(|| Some(Ipv4Addr::UNSPECIFIED))]
+    fn renders_nothing_without_a_usable_address(#[case] resolve: fn() -> Option<Ipv4Addr>) {
+        let module = LocalIpModule::with_resolver(resolve);
+        let context = context_without_ssh_only();
+        assert_eq!(module.render(&context, &context.config.localip), "");
+    }
+
+    #[rstest]
+    fn respects_disabled_flag() {
+        let module = LocalIpModule::new();
+        let mut context = context_without_ssh_only();
+        context.config.localip.disabled = true;
+        assert!(!module.should_display(&context, &context.config.localip));
+    }
+
+    fn context_without_ssh_only() -> Context {
+        let mut context = make_context();
+        context.config.localip.ssh_only = false;
+        context
+    }
+}
@@ -15,6 +15,12 @@
 //!
 //! - `directory`: Current directory display
 //! - `claude_model`: Claude model information
+//! - `cc_version`: Claude Code client version
+//! - `session`: Truncated session id
+//! - `claude_session`: Alias of `session`, sharing its `[session]` config
+//! - `localip`: Primary LAN IPv4 address
+//! - `status`: Nonzero exit status passed in the input
+//! - `context_usage`: Context window usage read from the transcript
 //! - `git_branch`: Current git branch
 //! - `git_status`: Git repository status
 
@@ -23,7 +29,9 @@ use crate::error::CoreError;
 use crate::timeout::run_with_timeout;
 use crate::types::context::Context;
 use std::any::Any;
-use std::time::Duration;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
 
 /// Trait for module-specific configuration
 ///
@@ -45,6 +53,31 @@ pub trait ModuleConfig: Any + Send + Sync {
     fn style(&self) -> &str {
         ""
     }
+
+    /// Module-specific timeout in milliseconds, overriding `command_timeout`
+    fn timeout_ms(&self) -> Option<u64> {
+        None
+    }
+
+    /// Placeholder rendered when the module times out (empty omits it)
+    fn timeout_symbol(&self) -> &str {
+        ""
+    }
+
+    /// Optional `when` predicate gating display (see [`when_allows`])
+    fn when(&self) -> Option<&str> {
+        None
+    }
+
+    /// Optional transform applied to the rendered output
+    fn transform(&self) -> Option<crate::style::Transform> {
+        None
+    }
+
+    /// Whether the module is turned off by its `disabled` setting
+    fn disabled(&self) -> bool {
+        false
+    }
 }
 
 /// Default implementation for cases where no config is provided
@@ -79,20 +112,61 @@ pub trait Module: Send + Sync {
 
     /// Renders the module's output as a string
     fn render(&self, context: &Context, config: &dyn ModuleConfig) -> String;
+
+    /// Shared context this module reads (nothing by default)
+    ///
+    /// The engine prepares the memoized state of every referenced module's
+    /// requirements once before rendering, and skips it when none needs it.
+    fn requires(&self) -> ModuleRequirements {
+        ModuleRequirements::default()
+    }
+}
+
+/// Expensive, memoized [`Context`] state a module depends on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ModuleRequirements {
+    /// Git repository state ([`Context::git_snapshot`]); the modules that
+    /// `--no-git` turns off
+    pub git: bool,
+    /// Repository root only ([`Context::repo_root`])
+    pub repo_root: bool,
+    /// Scan of the current directory ([`Context::dir_contents`])
+    pub dir_scan: bool,
+}
+
+impl ModuleRequirements {
+    /// Requirements of both `self` and `other`
+    pub fn union(self, other: Self) -> Self {
+        Self {
+            git: self.git || other.git,
+            repo_root: self.repo_root || other.repo_root,
+            dir_scan: self.dir_scan || other.dir_scan,
+        }
+    }
 }
 
 // Re-export module implementations
+pub mod cc_version;
 pub mod claude_model;
+pub mod context_usage;
 pub mod directory;
 #[cfg(feature = "git")]
 pub mod git_branch;
 #[cfg(feature = "git")]
 pub mod git_status;
+pub mod localip;
 pub mod registry;
+pub mod session;
+pub mod status;
 
+pub use cc_version::CcVersionModule;
 pub use claude_model::ClaudeModelModule;
+pub use context_usage::ContextUsageModule;
 pub use directory::DirectoryModule;
+pub use localip::LocalIpModule;
 pub use registry::{ModuleFactory, Registry};
+pub use session::SessionModule;
+pub use status::StatusModule;
 
 /// Central module dispatcher - creates module instances based on name
 ///
@@ -114,16 +188,81 @@ pub fn handle_module(name: &str, context: &Context) -> Option<Box<dyn Module>> {
     registry.create(name, context)
 }
 
-fn module_config_for<'a>(name: &str, context: &'a Context) -> Option<&'a dyn ModuleConfig> {
-    let registry = Registry::with_defaults();
-    registry.config(name, context)
+/// Run module code, converting a panic into `CoreError::ModulePanic`
+///
+/// Keeps the module name and panic message so a misbehaving (e.g.
+/// third-party) module can be identified in the debug log.
+fn catch_module_panic<T>(name: &str, f: impl FnOnce() -> T) -> Result<T, CoreError> {
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)).map_err(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic payload".to_string());
+        CoreError::ModulePanic {
+            name: name.to_string(),
+            message,
+        }
+    })
 }
 
+/// Evaluate a module's `when` predicate
+///
+/// - `env:VAR`: true while the environment variable `VAR` is set
+/// - anything else: a shell command run in the current directory, true
+///   when it exits with status 0 (failing to spawn counts as false)
+///
+/// A command still running at `deadline` is killed and counts as false, so
+/// a module timeout never leaves the shell behind.
+pub fn when_allows(expr: &str, context: &Context, deadline: Instant) -> bool {
+    if let Some(var) = expr.strip_prefix("env:") {
+        return std::env::var_os(var.trim()).is_some();
+    }
+
+    #[cfg(windows)]
+    let mut cmd = std::process::Command::new("cmd");
+    #[cfg(windows)]
+    cmd.args(["/C", expr]);
+    #[cfg(not(windows))]
+    let mut cmd = std::process::Command::new("sh");
+    #[cfg(not(windows))]
+    cmd.args(["-c", expr]);
+
+    if context.current_dir.is_dir() {
+        cmd.current_dir(&context.current_dir);
+    }
+    let Ok(mut child) = cmd
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+    else {
+        return false;
+    };
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => return status.success(),
+            Ok(None) if Instant::now() < deadline => std::thread::sleep(WHEN_POLL_INTERVAL),
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return false;
+            }
+        }
+    }
+}
+
+/// How often a running `when` command is checked for completion
+const WHEN_POLL_INTERVAL: Duration = Duration::from_millis(5);
+
 /// Renders a module with timeout protection
 ///
-/// Executes both `should_display` and `render` methods with a timeout
-/// based on the configuration's `command_timeout` value. This ensures
-/// that slow modules don't block the status line generation.
+/// Executes `should_display` and then `render` on a single module instance
+/// within one timeout, based on the module's `timeout_ms` override and
+/// falling back to the configuration's `command_timeout` value. This
+/// ensures that slow modules don't block the status line generation. A
+/// configured `when` predicate is checked first, within the same timeout;
+/// `render` is never called for a hidden module.
 ///
 /// # Arguments
 ///
@@ -134,67 +273,123 @@ fn module_config_for<'a>(name: &str, context: &'a Context) -> Option<&'a dyn Mod
 /// # Returns
 ///
 /// * `Some(String)` - Rendered module output on success
-/// * `None` - On timeout, error, or when module shouldn't display
+/// * `None` - On timeout, error, panic, or when module shouldn't display
+///
+/// A panicking module is reported as `CoreError::ModulePanic` through the
+/// logger and simply omitted; other modules keep rendering.
 ///
 /// # Timeout Behavior
 ///
-/// If a module exceeds the configured timeout (default 500ms),
-/// it will be skipped and an error logged to stderr.
+/// If a module exceeds the configured timeout (default 500ms), an error is
+/// logged to stderr and the module's `timeout_symbol` is returned in its
+/// place, or `None` when no placeholder is configured.
+///
+/// Only built-in modules are resolved; use [`render_module_with_registry`]
+/// for a custom registry.
 pub fn render_module_with_timeout(
     name: &str,
     context: &Context,
     logger: &DebugLogger,
 ) -> Option<String> {
-    let timeout_ms = context.config.command_timeout;
-    let timeout = Duration::from_millis(timeout_ms);
+    render_module_with_registry(&Arc::new(Registry::with_defaults()), name, context, logger)
+}
 
-    // should_display with timeout (fresh module instance)
-    match run_with_timeout(timeout, {
-        let ctx1 = context.clone();
-        let name1 = name.to_string();
-        move || {
-            let module = handle_module(&name1, &ctx1)
-                .ok_or_else(|| CoreError::UnknownModule(name1.clone()))?;
-            let cfg = module_config_for(&name1, &ctx1)
-                .ok_or_else(|| CoreError::MissingConfig(name1.clone()))?;
-            Ok(module.should_display(&ctx1, cfg))
-        }
-    }) {
-        Ok(Some(true)) => {}
-        Ok(Some(false)) => return None,
-        Ok(None) => {
-            logger.log_stderr(&format!(
-                "Module '{name}' timed out in should_display after {timeout_ms}ms"
-            ));
-            return None;
-        }
-        Err(e) => {
-            logger.log_stderr(&format!("Module '{name}' error in should_display: {e}"));
-            return None;
-        }
+/// Same as [`render_module_with_timeout`], resolving modules from `registry`
+///
+/// Lets callers (e.g. [`crate::Engine::new_with_registry`]) render modules
+/// registered through [`Registry::register_factory`]. A variant name such as
+/// `directory#short` renders its module with [`Config::with_variant`].
+///
+/// [`Config::with_variant`]: crate::Config::with_variant
+pub fn render_module_with_registry(
+    registry: &Arc<Registry>,
+    name: &str,
+    context: &Context,
+    logger: &DebugLogger,
+) -> Option<String> {
+    if let (module, Some(variant)) = crate::parser::split_variant(name) {
+        let config = match context.config.with_variant(module, variant) {
+            Ok(config) => config,
+            Err(e) => {
+                logger.log_stderr(&format!(
+                    "Module '{name}' has an invalid variant config: {e}"
+                ));
+                return None;
+            }
+        };
+        // A fresh context: the shared caches (git snapshot included) were
+        // computed under the base config's options
+        let variant_context = Context::new(context.input.clone(), config);
+        return render_module_with_registry(registry, module, &variant_context, logger);
     }
 
-    // render with timeout (fresh module instance)
-    match run_with_timeout(timeout, {
-        let ctx2 = context.clone();
-        let name2 = name.to_string();
+    let module_cfg = registry.config(name, context);
+    let timeout_ms = module_cfg
+        .and_then(|cfg| cfg.timeout_ms())
+        .unwrap_or(context.config.command_timeout);
+    let timeout = Duration::from_millis(timeout_ms);
+    let timeout_fallback = || {
+        module_cfg
+            .map(|cfg| cfg.timeout_symbol())
+            .filter(|symbol| !symbol.is_empty())
+            .map(str::to_string)
+    };
+
+    // One timed run per module: the instance and its config are resolved
+    // once, and `render` only runs after `when` and `should_display` allow it
+    let rendering = Arc::new(AtomicBool::new(false));
+    let deadline = Instant::now() + timeout;
+    let result = run_with_timeout(timeout, {
+        let registry = Arc::clone(registry);
+        let context = context.clone();
+        let name = name.to_string();
+        let rendering = Arc::clone(&rendering);
         move || {
-            let module = handle_module(&name2, &ctx2)
-                .ok_or_else(|| CoreError::UnknownModule(name2.clone()))?;
-            let cfg = module_config_for(&name2, &ctx2)
-                .ok_or_else(|| CoreError::MissingConfig(name2.clone()))?;
-            Ok(module.render(&ctx2, cfg))
+            let module = registry
+                .create(&name, &context)
+                .ok_or_else(|| CoreError::UnknownModule(name.clone()))?;
+            let cfg = registry
+                .config(&name, &context)
+                .ok_or_else(|| CoreError::MissingConfig(name.clone()))?;
+            if cfg
+                .when()
+                .is_some_and(|expr| !when_allows(expr, &context, deadline))
+            {
+                return Ok(None);
+            }
+            if !catch_module_panic(&name, || module.should_display(&context, cfg))? {
+                return Ok(None);
+            }
+            rendering.store(true, Ordering::Relaxed);
+            catch_module_panic(&name, || module.render(&context, cfg)).map(Some)
         }
-    }) {
-        Ok(Some(s)) => Some(s),
+    });
+    // Phase the module was in when it timed out or failed
+    let phase = if rendering.load(Ordering::Relaxed) {
+        "render"
+    } else {
+        "should_display"
+    };
+
+    match result {
+        Ok(Some(Some(s))) => Some(match module_cfg.and_then(|cfg| cfg.transform()) {
+            Some(transform) => transform.apply(&s),
+            None => s,
+        }),
+        Ok(Some(None)) => None,
         Ok(None) => {
+            logger.warn_module(
+                name,
+                &format!("module timed out in {phase} after {timeout_ms}ms"),
+            );
             logger.log_stderr(&format!(
-                "Module '{name}' timed out in render after {timeout_ms}ms"
+                "Module '{name}' timed out in {phase} after {timeout_ms}ms"
             ));
-            None
+            timeout_fallback()
         }
         Err(e) => {
-            logger.log_stderr(&format!("Module '{name}' error in render: {e}"));
+            logger.warn_module(name, &format!("module failed in {phase}: {e}"));
+            logger.log_stderr(&format!("Module '{name}' error in {phase}: {e}"));
             None
         }
     }
@@ -206,16 +401,8 @@ mod timeout_tests {
     use crate::config::Config;
     use crate::types::claude::{ClaudeInput, ModelInfo, WorkspaceInfo};
 
-    #[allow(dead_code)]
     struct SleepyModule;
 
-    impl SleepyModule {
-        #[allow(dead_code)]
-        fn from_context(_context: &Context) -> Self {
-            Self
-        }
-    }
-
     impl Module for SleepyModule {
         fn name(&self) -> &str {
             "sleepy"
@@ -229,15 +416,88 @@ mod timeout_tests {
         }
     }
 
-    // Extend dispatcher only in tests
-    #[allow(dead_code)]
-    pub fn handle_module(name: &str, context: &Context) -> Option<Box<dyn Module>> {
-        match name {
-            "sleepy" => Some(Box::new(SleepyModule::from_context(context))),
-            _ => super::handle_module(name, context),
+    struct PanickyModule;
+
+    impl Module for PanickyModule {
+        fn name(&self) -> &str {
+            "panicky"
+        }
+        fn should_display(&self, _context: &Context, _cfg: &dyn ModuleConfig) -> bool {
+            true
+        }
+        fn render(&self, _context: &Context, _cfg: &dyn ModuleConfig) -> String {
+            panic!("render exploded")
         }
     }
 
+    struct PanickyFactory;
+
+    impl ModuleFactory for PanickyFactory {
+        fn name(&self) -> &'static str {
+            "panicky"
+        }
+        fn create(&self, _context: &Context) -> Box<dyn Module> {
+            Box::new(PanickyModule)
+        }
+        fn config<'a>(&self, _context: &'a Context) -> Option<&'a dyn ModuleConfig> {
+            Some(&EmptyConfig)
+        }
+    }
+
+    struct SleepyConfig {
+        timeout_ms: Option<u64>,
+        timeout_symbol: &'static str,
+    }
+
+    impl ModuleConfig for SleepyConfig {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+        fn timeout_ms(&self) -> Option<u64> {
+            self.timeout_ms
+        }
+        fn timeout_symbol(&self) -> &str {
+            self.timeout_symbol
+        }
+    }
+
+    static NO_OVERRIDE: SleepyConfig = SleepyConfig {
+        timeout_ms: None,
+        timeout_symbol: "",
+    };
+    static LONG_OVERRIDE: SleepyConfig = SleepyConfig {
+        timeout_ms: Some(2_000),
+        timeout_symbol: "",
+    };
+    static WITH_PLACEHOLDER: SleepyConfig = SleepyConfig {
+        timeout_ms: None,
+        timeout_symbol: "…",
+    };
+
+    /// Registers a sleepy module under `name` bound to a static config
+    struct SleepyFactory(&'static str, &'static SleepyConfig);
+
+    impl ModuleFactory for SleepyFactory {
+        fn name(&self) -> &'static str {
+            self.0
+        }
+        fn create(&self, _context: &Context) -> Box<dyn Module> {
+            Box::new(SleepyModule)
+        }
+        fn config<'a>(&self, _context: &'a Context) -> Option<&'a dyn ModuleConfig> {
+            Some(self.1)
+        }
+    }
+
+    fn sleepy_registry() -> Arc<Registry> {
+        let mut reg = Registry::with_defaults();
+        reg.register_factory(SleepyFactory("sleepy", &NO_OVERRIDE));
+        reg.register_factory(SleepyFactory("patient", &LONG_OVERRIDE));
+        reg.register_factory(SleepyFactory("ellipsis", &WITH_PLACEHOLDER));
+        reg.register_factory(PanickyFactory);
+        Arc::new(reg)
+    }
+
     fn make_context(cwd: &str, timeout_ms: u64) -> Context {
         let input = ClaudeInput {
             hook_event_name: None,
@@ -254,6 +514,7 @@ mod timeout_tests {
             }),
             version: Some("1.0.0".into()),
             output_style: None,
+            exit_status: None,
         };
         let cfg = Config {
             command_timeout: timeout_ms,
@@ -266,7 +527,155 @@ mod timeout_tests {
     fn sleepy_module_times_out_and_is_omitted() {
         let logger = DebugLogger::new(true);
         let ctx = make_context("/tmp", 50);
-        let out = render_module_with_timeout("sleepy", &ctx, &logger);
+        let out = render_module_with_registry(&sleepy_registry(), "sleepy", &ctx, &logger);
         assert!(out.is_none());
     }
+
+    #[test]
+    fn module_panic_maps_to_typed_error() {
+        let err =
+            catch_module_panic("panicky", || -> String { panic!("render exploded") }).unwrap_err();
+        match err {
+            CoreError::ModulePanic { name, message } => {
+                assert_eq!(name, "panicky");
+                assert_eq!(message, "render exploded");
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn panicking_module_is_omitted_and_others_still_render() {
+        let logger = DebugLogger::new(false);
+        let ctx = make_context("/tmp", 500);
+        let registry = sleepy_registry();
+        assert!(render_module_with_registry(&registry, "panicky", &ctx, &logger).is_none());
+        let dir = render_module_with_registry(&registry, "directory", &ctx, &logger);
+        assert!(dir.is_some_and(|s| s.contains("tmp")));
+    }
+
+    #[test]
+    fn timed_out_module_renders_timeout_symbol() {
+        let logger = DebugLogger::new(true);
+        let ctx = make_context("/tmp", 50);
+        let out = render_module_with_registry(&sleepy_registry(), "ellipsis", &ctx, &logger);
+        assert_eq!(out.as_deref(), Some("…"));
+    }
+
+    #[test]
+    fn module_timeout_override_outlasts_global_timeout() {
+        let logger = DebugLogger::new(true);
+        let ctx = make_context("/tmp", 50);
+        let out = render_module_with_registry(&sleepy_registry(), "patient", &ctx, &logger);
+        assert_eq!(out.as_deref(), Some("[SLEEP]"));
+    }
+
+    /// Counts module instances and calls into them
+    #[derive(Default)]
+    struct Calls {
+        created: std::sync::atomic::AtomicUsize,
+        should_display: std::sync::atomic::AtomicUsize,
+        render: std::sync::atomic::AtomicUsize,
+    }
+
+    struct CountingModule(Arc<Calls>, bool);
+
+    impl Module for CountingModule {
+        fn name(&self) -> &str {
+            "counting"
+        }
+        fn should_display(&self, _context: &Context, _cfg: &dyn ModuleConfig) -> bool {
+            self.0.should_display.fetch_add(1, Ordering::Relaxed);
+            self.1
+        }
+        fn render(&self, _context: &Context, _cfg: &dyn ModuleConfig) -> String {
+            self.0.render.fetch_add(1, Ordering::Relaxed);
+            "counted".to_string()
+        }
+    }
+
+    struct CountingFactory(Arc<Calls>, bool);
+
+    impl ModuleFactory for CountingFactory {
+        fn name(&self) -> &'static str {
+            "counting"
+        }
+        fn create(&self, _context: &Context) -> Box<dyn Module> {
+            self.0.created.fetch_add(1, Ordering::Relaxed);
+            Box::new(CountingModule(Arc::clone(&self.0), self.1))
+        }
+        fn config<'a>(&self, _context: &'a Context) -> Option<&'a dyn ModuleConfig> {
+            Some(&EmptyConfig)
+        }
+    }
+
+    #[rstest::rstest]
+    #[case::shown(true, Some("counted"), 1)]
+    #[case::hidden(false, None, 0)]
+    fn module_is_created_once_and_only_rendered_when_shown(
+        #[case] shown: bool,
+        #[case] expected: Option<&str>,
+        #[case] renders: usize,
+    ) {
+        let calls = Arc::new(Calls::default());
+        let mut reg = Registry::with_defaults();
+        reg.register_factory(CountingFactory(Arc::clone(&calls), shown));
+        let ctx = make_context("/tmp", 500);
+        let out =
+            render_module_with_registry(&Arc::new(reg), "counting", &ctx, &DebugLogger::new(false));
+
+        assert_eq!(out.as_deref(), expected);
+        assert_eq!(calls.created.load(Ordering::Relaxed), 1);
+        assert_eq!(calls.should_display.load(Ordering::Relaxed), 1);
+        assert_eq!(calls.render.load(Ordering::Relaxed), renders);
+    }
+
+    #[rstest::rstest]
+    #[case::set_var("env:CCS_TEST_WHEN_PRESENT", true)]
+    #[case::unset_var("env:CCS_TEST_WHEN_ABSENT", false)]
+    #[case::no_predicate("", true)]
+    fn when_env_predicate_gates_display(#[case] when: &str, #[case] shown: bool) {
+        let _guard = crate::test_env::env_lock();
+        // SAFETY: env mutation is serialized by `env_lock`
+        unsafe { std::env::set_var("CCS_TEST_WHEN_PRESENT", "1") };
+        let logger = DebugLogger::new(false);
+        let mut ctx = make_context("/tmp", 500);
+        ctx.config.claude_model.when = (!when.is_empty()).then(|| when.to_string());
+        let out = render_module_with_timeout("claude_model", &ctx, &logger);
+        assert_eq!(out.is_some(), shown, "{out:?}");
+    }
+
+    #[cfg(unix)]
+    #[rstest::rstest]
+    #[case::exit_zero("true", true)]
+    #[case::exit_nonzero("exit 3", false)]
+    #[case::runs_in_cwd("test -d \"$PWD\" && [ \"$(basename \"$PWD\")\" = tmp ]", true)]
+    fn when_command_predicate_uses_exit_code(#[case] when: &str, #[case] shown: bool) {
+        let mut ctx = make_context("/tmp", 500);
+        ctx.config.claude_model.when = Some(when.to_string());
+        let out = render_module_with_timeout("claude_model", &ctx, &DebugLogger::new(false));
+        assert_eq!(out.is_some(), shown, "{out:?}");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn when_command_is_killed_at_the_deadline() {
+        let tmp = tempfile::tempdir().unwrap();
+        let pid_file = tmp.path().join("pid");
+        let ctx = make_context(tmp.path().to_str().unwrap(), 500);
+        let start = Instant::now();
+        let expr = format!("echo $$ > {}; exec sleep 5", pid_file.display());
+        let shown = when_allows(&expr, &ctx, start + Duration::from_millis(100));
+        assert!(!shown);
+        assert!(start.elapsed() < Duration::from_secs(2));
+
+        // The shell (exec'd into `sleep`) is gone, not left running
+        let pid = std::fs::read_to_string(&pid_file).unwrap();
+        let alive = std::process::Command::new("kill")
+            .args(["-0", pid.trim()])
+            .stderr(std::process::Stdio::null())
+            .status()
+            .unwrap();
+        assert!(!alive.success(), "pid {pid} still running");
+    }
 }
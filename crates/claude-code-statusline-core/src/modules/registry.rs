@@ -4,7 +4,11 @@
 //! without hard-coded dispatcher matches. This enables pluggable modules
 //! and paves the way for external/extra modules via configuration.
 
-use super::{Module, ModuleConfig, claude_model::ClaudeModelModule, directory::DirectoryModule};
+use super::{
+    Module, ModuleConfig, cc_version::CcVersionModule, claude_model::ClaudeModelModule,
+    context_usage::ContextUsageModule, directory::DirectoryModule, localip::LocalIpModule,
+    session::SessionModule, status::StatusModule,
+};
 #[cfg(feature = "git")]
 use super::{git_branch::GitBranchModule, git_status::GitStatusModule};
 use crate::types::context::Context;
@@ -39,6 +43,12 @@ impl Registry {
         let mut reg = Self::new();
         reg.register_factory(DirectoryFactory);
         reg.register_factory(ClaudeModelFactory);
+        reg.register_factory(CcVersionFactory);
+        reg.register_factory(SessionFactory);
+        reg.register_factory(ClaudeSessionFactory);
+        reg.register_factory(LocalIpFactory);
+        reg.register_factory(StatusFactory);
+        reg.register_factory(ContextUsageFactory);
         #[cfg(feature = "git")]
         {
             reg.register_factory(GitBranchFactory);
@@ -109,6 +119,85 @@ impl ModuleFactory for ClaudeModelFactory {
     }
 }
 
+struct CcVersionFactory;
+impl ModuleFactory for CcVersionFactory {
+    fn name(&self) -> &'static str {
+        "cc_version"
+    }
+    fn create(&self, context: &Context) -> Box<dyn Module> {
+        Box::new(CcVersionModule::from_context(context))
+    }
+    fn config<'a>(&self, context: &'a Context) -> Option<&'a dyn ModuleConfig> {
+        Some(&context.config.cc_version)
+    }
+}
+
+struct SessionFactory;
+impl ModuleFactory for SessionFactory {
+    fn name(&self) -> &'static str {
+        "session"
+    }
+    fn create(&self, context: &Context) -> Box<dyn Module> {
+        Box::new(SessionModule::from_context(context))
+    }
+    fn config<'a>(&self, context: &'a Context) -> Option<&'a dyn ModuleConfig> {
+        Some(&context.config.session)
+    }
+}
+
+/// `$claude_session`: alias of the session module, configured by `[session]`
+struct ClaudeSessionFactory;
+impl ModuleFactory for ClaudeSessionFactory {
+    fn name(&self) -> &'static str {
+        "claude_session"
+    }
+    fn create(&self, context: &Context) -> Box<dyn Module> {
+        Box::new(SessionModule::from_context(context))
+    }
+    fn config<'a>(&self, context: &'a Context) -> Option<&'a dyn ModuleConfig> {
+        Some(&context.config.session)
+    }
+}
+
+struct LocalIpFactory;
+impl ModuleFactory for LocalIpFactory {
+    fn name(&self) -> &'static str {
+        "localip"
+    }
+    fn create(&self, context: &Context) -> Box<dyn Module> {
+        Box::new(LocalIpModule::from_context(context))
+    }
+    fn config<'a>(&self, context: &'a Context) -> Option<&'a dyn ModuleConfig> {
+        Some(&context.config.localip)
+    }
+}
+
+struct StatusFactory;
+impl ModuleFactory for StatusFactory {
+    fn name(&self) -> &'static str {
+        "status"
+    }
+    fn create(&self, context: &Context) -> Box<dyn Module> {
+        Box::new(StatusModule::from_context(context))
+    }
+    fn config<'a>(&self, context: &'a Context) -> Option<&'a dyn ModuleConfig> {
+        Some(&context.config.status)
+    }
+}
+
+struct ContextUsageFactory;
+impl ModuleFactory for ContextUsageFactory {
+    fn name(&self) -> &'static str {
+        "context_usage"
+    }
+    fn create(&self, context: &Context) -> Box<dyn Module> {
+        Box::new(ContextUsageModule::from_context(context))
+    }
+    fn config<'a>(&self, context: &'a Context) -> Option<&'a dyn ModuleConfig> {
+        Some(&context.config.context_usage)
+    }
+}
+
 #[cfg(feature = "git")]
 struct GitBranchFactory;
 #[cfg(feature = "git")]
@@ -152,6 +241,12 @@ mod tests {
         let names = reg.list();
         assert!(names.contains(&"directory"));
         assert!(names.contains(&"claude_model"));
+        assert!(names.contains(&"cc_version"));
+        assert!(names.contains(&"session"));
+        assert!(names.contains(&"claude_session"));
+        assert!(names.contains(&"localip"));
+        assert!(names.contains(&"status"));
+        assert!(names.contains(&"context_usage"));
         #[cfg(feature = "git")]
         {
             assert!(names.contains(&"git_branch"));
@@ -174,6 +269,7 @@ mod tests {
             workspace: None,
             version: None,
             output_style: None,
+            exit_status: None,
         };
         let ctx = Context::new(input, cfg);
         let reg = Registry::with_defaults();
@@ -182,4 +278,33 @@ mod tests {
         assert!(reg.config("directory", &ctx).is_some());
         assert!(reg.create("unknown", &ctx).is_none());
     }
+
+    #[test]
+    fn claude_session_is_an_alias_of_session() {
+        let mut cfg = Config::default();
+        cfg.session.format = "<$session_id>".into();
+        cfg.session.length = 4;
+        let input = ClaudeInput {
+            hook_event_name: None,
+            session_id: "5f2e9a41".into(),
+            transcript_path: None,
+            cwd: "/tmp".into(),
+            model: ModelInfo {
+                id: "claude-opus".into(),
+                display_name: "Opus".into(),
+            },
+            workspace: None,
+            version: None,
+            output_style: None,
+            exit_status: None,
+        };
+        let ctx = Context::new(input, cfg);
+        let reg = Registry::with_defaults();
+        let render = |name: &str| {
+            let module = reg.create(name, &ctx).expect("module");
+            module.render(&ctx, reg.config(name, &ctx).expect("config"))
+        };
+        assert_eq!(render("claude_session"), "<5f2e>");
+        assert_eq!(render("claude_session"), render("session"));
+    }
 }
@@ -0,0 +1,154 @@
+//! Session module for displaying a short Claude Code session id
+//!
+//! Useful when several sessions run side by side: the first few characters
+//! of the session id are usually enough to tell them apart.
+
+use super::{Module, ModuleConfig};
+use crate::types::context::Context;
+
+/// Module that displays a truncated session id
+///
+/// # Configuration
+///
+/// ```toml
+/// [session]
+/// format = "[$session_id]($style)"
+/// style = "italic yellow"
+/// length = 6
+/// disabled = false
+/// ```
+///
+/// # Display Rules
+///
+/// - Shows the first `length` characters of `session_id` (`0` keeps it whole)
+/// - Only displays when the session id is non-empty
+/// - Can be disabled via configuration
+pub struct SessionModule;
+
+impl SessionModule {
+    /// Create a new SessionModule instance
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Create from Context (kept for compatibility)
+    pub fn from_context(_context: &Context) -> Self {
+        Self::new()
+    }
+}
+
+impl Default for SessionModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// First `length` characters of `id`; `0` returns the id unchanged
+pub(crate) fn truncate_session_id(id: &str, length: usize) -> &str {
+    if length == 0 {
+        return id;
+    }
+    match id.char_indices().nth(length) {
+        Some((idx, _)) => &id[..idx],
+        None => id,
+    }
+}
+
+impl Module for SessionModule {
+    fn name(&self) -> &str {
+        "session"
+    }
+
+    fn should_display(&self, context: &Context, config: &dyn ModuleConfig) -> bool {
+        if let Some(cfg) = config
+            .as_any()
+            .downcast_ref::<crate::types::config::SessionConfig>()
+        {
+            if cfg.disabled {
+                return false;
+            }
+        }
+        !context.input.session_id.trim().is_empty()
+    }
+
+    fn render(&self, context: &Context, config: &dyn ModuleConfig) -> String {
+        let id = context.input.session_id.trim();
+
+        if let Some(cfg) = config
+            .as_any()
+            .downcast_ref::<crate::types::config::SessionConfig>()
+        {
+            use std::collections::HashMap;
+            let mut tokens = HashMap::new();
+            tokens.insert(
+                "session_id",
+                truncate_session_id(id, cfg.length).to_string(),
+            );
+            return crate::style::render_with_style_template_depth(
+                cfg.format(),
+                &tokens,
+                cfg.style(),
+                context.color_depth(),
+            );
+        }
+
+        id.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::types::claude::{ClaudeInput, ModelInfo};
+    use crate::types::context::Context;
+    use rstest::*;
+
+    fn context_with_session(session_id: &str) -> Context {
+        let input = ClaudeInput {
+            hook_event_name: None,
+            session_id: session_id.to_string(),
+            transcript_path: None,
+            cwd: "/test/dir".to_string(),
+            model: ModelInfo {
+                id: "claude-opus".to_string(),
+                display_name: "Opus".to_string(),
+            },
+            workspace: None,
+            version: None,
+            output_style: None,
+            exit_status: None,
+        };
+        Context::new(input, Config::default())
+    }
+
+    #[rstest]
+    #[case("abc123def456", 6, "abc123")]
+    #[case("abc", 6, "abc")]
+    #[case("abc123def456", 0, "abc123def456")]
+    #[case("セッション識別子", 3, "セッシ")]
+    fn truncates_by_characters(#[case] id: &str, #[case] length: usize, #[case] expected: &str) {
+        assert_eq!(truncate_session_id(id, length), expected);
+    }
+
+    #[rstest]
+    fn renders_truncated_id() {
+        let module = SessionModule::new();
+        let context = context_with_session("5f2e9a41-7c1d-4e0b");
+        assert!(module.should_display(&context, &context.config.session));
+        let rendered = module.render(&context, &context.config.session);
+        let plain = String::from_utf8(strip_ansi_escapes::strip(rendered)).unwrap();
+        assert_eq!(plain, "5f2e9a");
+    }
+
+    #[rstest]
+    fn hidden_when_empty_or_disabled() {
+        let module = SessionModule::new();
+        let context = context_with_session("  ");
+        assert!(!module.should_display(&context, &context.config.session));
+
+        let mut context = context_with_session("5f2e9a41");
+        context.config.session.disabled = true;
+        assert!(!module.should_display(&context, &context.config.session));
+    }
+}
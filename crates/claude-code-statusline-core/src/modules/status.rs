@@ -0,0 +1,145 @@
+//! Status module for surfacing a failing exit status
+//!
+//! Callers that wrap the status line can pass `exit_status` in the JSON
+//! input; this module highlights it whenever it is nonzero.
+
+use super::{Module, ModuleConfig};
+use crate::types::context::Context;
+
+/// Module that displays the last exit status when it indicates failure
+///
+/// # Configuration
+///
+/// ```toml
+/// [status]
+/// format = "[$symbol$status]($style)"
+/// style = "bold red"
+/// symbol = "✘ "
+/// disabled = false
+/// ```
+///
+/// # Display Rules
+///
+/// - Only displays when the input carries a nonzero `exit_status`
+/// - Can be disabled via configuration
+pub struct StatusModule;
+
+impl StatusModule {
+    /// Create a new StatusModule instance
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Create from Context (kept for compatibility)
+    pub fn from_context(_context: &Context) -> Self {
+        Self::new()
+    }
+}
+
+impl Default for StatusModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Module for StatusModule {
+    fn name(&self) -> &str {
+        "status"
+    }
+
+    fn should_display(&self, context: &Context, config: &dyn ModuleConfig) -> bool {
+        if let Some(cfg) = config
+            .as_any()
+            .downcast_ref::<crate::types::config::StatusConfig>()
+        {
+            if cfg.disabled {
+                return false;
+            }
+        }
+        context.input.exit_status.is_some_and(|code| code != 0)
+    }
+
+    fn render(&self, context: &Context, config: &dyn ModuleConfig) -> String {
+        let Some(code) = context.input.exit_status.filter(|code| *code != 0) else {
+            return String::new();
+        };
+
+        if let Some(cfg) = config
+            .as_any()
+            .downcast_ref::<crate::types::config::StatusConfig>()
+        {
+            use std::collections::HashMap;
+            let mut tokens = HashMap::new();
+            tokens.insert("status", code.to_string());
+            let mode = context.config.symbol_mode;
+            let symbol = crate::symbols::resolve("status", &cfg.symbol, mode);
+            tokens.insert("symbol", symbol.to_string());
+            return crate::style::render_with_style_template_depth(
+                cfg.format(),
+                &tokens,
+                cfg.style(),
+                context.color_depth(),
+            );
+        }
+
+        code.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::types::claude::{ClaudeInput, ModelInfo};
+    use crate::types::context::Context;
+    use rstest::*;
+
+    fn context_with_status(exit_status: Option<i32>) -> Context {
+        let input = ClaudeInput {
+            hook_event_name: None,
+            session_id: "test-session".to_string(),
+            transcript_path: None,
+            cwd: "/test/dir".to_string(),
+            model: ModelInfo {
+                id: "claude-opus".to_string(),
+                display_name: "Opus".to_string(),
+            },
+            workspace: None,
+            version: None,
+            output_style: None,
+            exit_status,
+        };
+        Context::new(input, Config::default())
+    }
+
+    #[rstest]
+    #[case(1, "✘ 1")]
+    #[case(-2, "✘ -2")]
+    #[case(127, "✘ 127")]
+    fn renders_nonzero_status(#[case] code: i32, #[case] expected: &str) {
+        let module = StatusModule::new();
+        let context = context_with_status(Some(code));
+        assert!(module.should_display(&context, &context.config.status));
+        let rendered = module.render(&context, &context.config.status);
+        let plain = String::from_utf8(strip_ansi_escapes::strip(rendered)).unwrap();
+        assert_eq!(plain, expected);
+    }
+
+    #[rstest]
+    #[case(None)]
+    #[case(Some(0))]
+    fn hidden_on_success_or_missing_status(#[case] exit_status: Option<i32>) {
+        let module = StatusModule::new();
+        let context = context_with_status(exit_status);
+        assert!(!module.should_display(&context, &context.config.status));
+        assert_eq!(module.render(&context, &context.config.status), "");
+    }
+
+    #[rstest]
+    fn respects_disabled_flag() {
+        let module = StatusModule::new();
+        let mut context = context_with_status(Some(1));
+        context.config.status.disabled = true;
+        assert!(!module.should_display(&context, &context.config.status));
+    }
+}
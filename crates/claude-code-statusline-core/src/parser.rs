@@ -13,6 +13,10 @@
 //! - `$git_branch` - Replaced with current git branch
 //!
 //! Example: `"$directory $git_branch $claude_model"`
+//!
+//! A braced form `${name}` is also accepted, optionally with a fallback
+//! value: `${git_branch:-no-git}` renders `no-git` when the module output is
+//! empty or absent.
 
 use crate::error::CoreError;
 use crate::types::claude::ClaudeInput;
@@ -46,6 +50,33 @@ pub fn parse_claude_input(json_str: &str) -> Result<ClaudeInput, CoreError> {
     Ok(serde_json::from_str(json_str)?)
 }
 
+/// Default cap on stdin size, see [`parse_claude_input_limited`]
+pub const DEFAULT_MAX_INPUT_BYTES: usize = 4 * 1024 * 1024;
+
+/// Same as [`parse_claude_input`], rejecting input longer than `max_len` bytes
+///
+/// Returns [`CoreError::InputTooLarge`] without parsing when the limit is
+/// exceeded.
+///
+/// # Examples
+///
+/// ```
+/// use claude_code_statusline_core::parser::parse_claude_input_limited;
+///
+/// let json = r#"{"session_id":"test","cwd":"/tmp","model":{"id":"claude","display_name":"Claude"}}"#;
+/// assert!(parse_claude_input_limited(json, 1024).is_ok());
+/// assert!(parse_claude_input_limited(json, 16).is_err());
+/// ```
+pub fn parse_claude_input_limited(
+    json_str: &str,
+    max_len: usize,
+) -> Result<ClaudeInput, CoreError> {
+    if json_str.len() > max_len {
+        return Err(CoreError::InputTooLarge { max: max_len });
+    }
+    parse_claude_input(json_str)
+}
+
 /// Parses format string and substitutes variables with module outputs
 ///
 /// Replaces `$<name>` tokens anywhere in the string (not only when
@@ -61,7 +92,9 @@ pub fn parse_claude_input(json_str: &str) -> Result<ClaudeInput, CoreError> {
 /// # Returns
 ///
 /// A string with all `$<name>` tokens replaced by their values while
-/// preserving all other characters (including spaces) verbatim.
+/// preserving all other characters (including spaces) verbatim. Only
+/// whitespace that directly precedes a removed or empty token at the end of
+/// the line is dropped.
 ///
 /// # Examples
 ///
@@ -84,53 +117,57 @@ pub fn parse_format(
     _context: &Context,
     module_outputs: &HashMap<String, String>,
 ) -> String {
-    // Scan the string and replace $<name> inline without altering
-    // any other characters. A valid name starts with [A-Za-z_]
-    // and continues with [A-Za-z0-9_]*.
-    let bytes = format.as_bytes();
+    // Token recognition and value resolution are shared with
+    // `style::render_with_style_template` so both paths agree on edge cases
+    // like `prefix$directory` or overlapping names (`$dir` vs `$directory`).
     let mut i = 0;
     let mut out = String::with_capacity(format.len());
-    while i < bytes.len() {
-        if bytes[i] == b'$' {
-            let start = i;
-            let j = i + 1;
-            // validate first identifier char
-            let mut k = j;
-            if k < bytes.len() {
-                let c = bytes[k] as char;
-                if c.is_ascii_alphabetic() || c == '_' {
-                    k += 1;
-                    // consume rest of identifier
-                    while k < bytes.len() {
-                        let c2 = bytes[k] as char;
-                        if c2.is_ascii_alphanumeric() || c2 == '_' {
-                            k += 1;
-                        } else {
-                            break;
-                        }
-                    }
-                    let name = &format[j..k];
-                    // Replace with module output (or empty string if missing)
-                    if let Some(val) = module_outputs.get(name) {
-                        out.push_str(val);
-                    }
-                    i = k;
-                    continue;
+    // Whitespace run `(start, end)` in `out` that directly precedes a removed
+    // or empty token. Cleared as soon as visible content follows.
+    let mut dangling: Option<(usize, usize)> = None;
+    let push_literal = |out: &mut String, dangling: &mut Option<_>, lit: &str| {
+        out.push_str(lit);
+        if !lit.trim().is_empty() {
+            *dangling = None;
+        }
+    };
+    while let Some(off) = format[i..].find('$') {
+        let at = i + off;
+        push_literal(&mut out, &mut dangling, &format[i..at]);
+        let Some(tok) = scan_token(format, at) else {
+            // Not a valid token — treat '$' literally
+            push_literal(&mut out, &mut dangling, "$");
+            i = at + 1;
+            continue;
+        };
+        let value = module_outputs.get(tok.name).map(String::as_str);
+        let resolved = resolve_token(&tok, value).map(|v| crate::style::pad_token(&tok, v));
+        match resolved.as_deref() {
+            Some(val) if !val.is_empty() => {
+                out.push_str(val);
+                if !val.trim_end().is_empty() {
+                    dangling = None;
+                }
+            }
+            // Missing or empty: the token is removed
+            _ => {
+                let ws_start = out.trim_end().len();
+                if ws_start < out.len() {
+                    dangling = Some((ws_start, out.len()));
                 }
             }
-            // Not a valid token — treat '$' literally
-            out.push('$');
-            i = start + 1;
-        } else {
-            out.push(bytes[i] as char);
-            i += 1;
         }
+        i = tok.end;
+    }
+    push_literal(&mut out, &mut dangling, &format[i..]);
+    // Avoid a dangling trailing space when tokens at the end are removed
+    // (e.g., "$directory $character"), but keep whitespace that is part of
+    // the literal format (e.g., a Powerline separator followed by a space).
+    // Interior whitespace is never altered to preserve precise layout.
+    if let Some((from, to)) = dangling {
+        out.replace_range(from..to, "");
     }
-    // Avoid a dangling trailing space when unknown tokens are removed
-    // at the end (e.g., "$directory $character"). Do not alter
-    // interior whitespace to preserve precise layout for Powerline-style
-    // compositions.
-    out.trim_end().to_string()
+    out
 }
 
 /// Extracts module names from a format string
@@ -155,48 +192,245 @@ pub fn parse_format(
 /// assert_eq!(modules, vec!["directory", "claude_model"]);
 /// ```
 pub fn extract_modules_from_format(format: &str) -> Vec<String> {
-    // Scan for `$<name>` anywhere in the string and return unique names
-    // in encounter order.
+    // Scan for `$<name>` / `${<name>...}` anywhere in the string and return
+    // unique names in encounter order.
     use std::collections::HashSet;
-    let bytes = format.as_bytes();
     let mut i = 0;
     let mut out: Vec<String> = Vec::new();
     let mut seen: HashSet<String> = HashSet::new();
-    while i < bytes.len() {
-        if bytes[i] == b'$' {
-            let j = i + 1;
-            let mut k = j;
-            if k < bytes.len() {
-                let c = bytes[k] as char;
-                if c.is_ascii_alphabetic() || c == '_' {
-                    k += 1;
-                    while k < bytes.len() {
-                        let c2 = bytes[k] as char;
-                        if c2.is_ascii_alphanumeric() || c2 == '_' {
-                            k += 1;
-                        } else {
-                            break;
-                        }
-                    }
-                    let name = &format[j..k];
-                    if seen.insert(name.to_string()) {
-                        out.push(name.to_string());
-                    }
-                    i = k;
-                    continue;
+    while let Some(off) = format[i..].find('$') {
+        let at = i + off;
+        match scan_token(format, at) {
+            Some(tok) => {
+                if seen.insert(tok.name.to_string()) {
+                    out.push(tok.name.to_string());
                 }
+                i = tok.end;
             }
+            None => i = at + 1,
         }
-        i += 1;
     }
     out
 }
 
+/// Whether `format` uses `$style` as a plain token, outside the `(...)`
+/// style annotation of a `[text](style)` group
+///
+/// ```
+/// use claude_code_statusline_core::parser::has_bare_style_token;
+///
+/// assert!(has_bare_style_token("$directory $style"));
+/// assert!(!has_bare_style_token("[$directory]($style)"));
+/// ```
+pub fn has_bare_style_token(format: &str) -> bool {
+    let mut i = 0;
+    while i < format.len() {
+        let rest = &format[i..];
+        if let Some(annotation) = rest.strip_prefix("](") {
+            match crate::style::annotation_end(annotation.as_bytes()) {
+                Some(end) => i += end + 3,
+                None => return false,
+            }
+            continue;
+        } else if let Some(tok) = scan_token(format, i) {
+            if tok.name == "style" {
+                return true;
+            }
+            i = tok.end;
+            continue;
+        }
+        i += rest.chars().next().map_or(1, char::len_utf8);
+    }
+    false
+}
+
+/// A variable token located in a format string
+///
+/// Covers the plain form `$name` as well as the braced forms `${name}` and
+/// `${name:-default}`. Any form may be followed by a padding directive such as `:<20`, `:>8` or `:^10`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct FormatToken<'a> {
+    /// Variable name (e.g., `git_branch`)
+    pub name: &'a str,
+    /// Fallback used when the value is empty or absent (`:-default`)
+    pub default: Option<&'a str>,
+    /// Whether the token used the `${...}` form
+    pub braced: bool,
+    /// Pad the substituted value to this many columns (`:<N`, `:>N`, `:^N`)
+    pub pad: Option<(Align, usize)>,
+    /// Byte index just past the end of the token (including any padding)
+    pub end: usize,
+}
+
+/// Alignment of a padded token value within its column width
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Align {
+    Left,
+    Right,
+    Center,
+}
+
+/// Scans a padding directive (`:<20`, `:>8`, `:^10`) at byte index `at`.
+/// Returns the alignment, width and the index just past the directive.
+fn scan_padding(format: &str, at: usize) -> Option<((Align, usize), usize)> {
+    let bytes = format.as_bytes();
+    if bytes.get(at) != Some(&b':') {
+        return None;
+    }
+    let align = match bytes.get(at + 1)? {
+        b'<' => Align::Left,
+        b'>' => Align::Right,
+        b'^' => Align::Center,
+        _ => return None,
+    };
+    let digits = bytes[at + 2..]
+        .iter()
+        .take_while(|c| c.is_ascii_digit())
+        .count();
+    let width: usize = format[at + 2..at + 2 + digits].parse().ok()?;
+    (width > 0).then_some(((align, width), at + 2 + digits))
+}
+
+/// Attach an optional padding directive following `tok`
+fn with_padding<'a>(mut tok: FormatToken<'a>, format: &str) -> FormatToken<'a> {
+    if let Some((pad, end)) = scan_padding(format, tok.end) {
+        tok.pad = Some(pad);
+        tok.end = end;
+    }
+    tok
+}
+
+/// Resolves the text substituted for `tok` given the rendered `value`.
+///
+/// Returns `None` when there is nothing to substitute (value absent and no
+/// `:-default`); callers decide whether that removes or preserves the token.
+/// A default replaces values without visible text (ANSI-only or whitespace).
+pub(crate) fn resolve_token<'a>(tok: &FormatToken<'a>, value: Option<&'a str>) -> Option<&'a str> {
+    match (value, tok.default) {
+        (Some(v), Some(default)) if !crate::style::has_visible_text(v) => Some(default),
+        (Some(v), _) => Some(v),
+        (None, default) => default,
+    }
+}
+
+/// Length of the identifier (`[A-Za-z_][A-Za-z0-9_]*`) starting at `start`
+fn identifier_len(bytes: &[u8], start: usize) -> usize {
+    match bytes.get(start) {
+        Some(c) if c.is_ascii_alphabetic() || *c == b'_' => {}
+        _ => return 0,
+    }
+    bytes[start..]
+        .iter()
+        .take_while(|c| c.is_ascii_alphanumeric() || **c == b'_')
+        .count()
+}
+
+/// Length of a token name: an identifier, optionally followed by a
+/// `#variant` suffix (e.g. `directory#short`)
+fn token_name_len(bytes: &[u8], start: usize) -> usize {
+    let len = identifier_len(bytes, start);
+    if len == 0 || bytes.get(start + len) != Some(&b'#') {
+        return len;
+    }
+    match identifier_len(bytes, start + len + 1) {
+        0 => len,
+        variant => len + 1 + variant,
+    }
+}
+
+/// Split a token name into its module and optional variant
+/// (`directory#short` → `("directory", Some("short"))`)
+pub fn split_variant(name: &str) -> (&str, Option<&str>) {
+    match name.split_once('#') {
+        Some((module, variant)) => (module, Some(variant)),
+        None => (name, None),
+    }
+}
+
+/// Scans a variable token beginning at byte index `start` (which must point
+/// at a `$`). Returns `None` when the `$` does not introduce a valid token,
+/// in which case callers treat it as a literal character.
+pub(crate) fn scan_token(format: &str, start: usize) -> Option<FormatToken<'_>> {
+    let bytes = format.as_bytes();
+    if bytes.get(start) != Some(&b'$') {
+        return None;
+    }
+
+    if bytes.get(start + 1) == Some(&b'{') {
+        let name_start = start + 2;
+        let name_end = name_start + token_name_len(bytes, name_start);
+        if name_end == name_start {
+            return None;
+        }
+        if bytes.get(name_end) == Some(&b'}') {
+            let tok = FormatToken {
+                name: &format[name_start..name_end],
+                default: None,
+                braced: true,
+                pad: None,
+                end: name_end + 1,
+            };
+            return Some(with_padding(tok, format));
+        }
+        if format[name_end..].starts_with(":-") {
+            let default_start = name_end + 2;
+            let close = default_start + format[default_start..].find('}')?;
+            let tok = FormatToken {
+                name: &format[name_start..name_end],
+                default: Some(&format[default_start..close]),
+                braced: true,
+                pad: None,
+                end: close + 1,
+            };
+            return Some(with_padding(tok, format));
+        }
+        return None;
+    }
+
+    let name_start = start + 1;
+    let name_end = name_start + token_name_len(bytes, name_start);
+    if name_end == name_start {
+        return None;
+    }
+    let tok = FormatToken {
+        name: &format[name_start..name_end],
+        default: None,
+        braced: false,
+        pad: None,
+        end: name_end,
+    };
+    Some(with_padding(tok, format))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::config::Config;
     use crate::types::claude::{ModelInfo, WorkspaceInfo};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::under_limit(200, true)]
+    #[case::exact_limit(0, true)]
+    #[case::over_limit(10, false)]
+    fn parse_claude_input_limited_enforces_max_len(#[case] slack: usize, #[case] ok: bool) {
+        let json =
+            r#"{"session_id":"s","cwd":"/tmp","model":{"id":"claude","display_name":"Claude"}}"#;
+        // `slack` bytes above the input length, or below it when rejecting
+        let max = if ok {
+            json.len() + slack
+        } else {
+            json.len() - slack
+        };
+        let result = parse_claude_input_limited(json, max);
+        assert_eq!(result.is_ok(), ok);
+        if !ok {
+            assert!(matches!(
+                result,
+                Err(CoreError::InputTooLarge { max: m }) if m == max
+            ));
+        }
+    }
 
     #[test]
     fn test_extract_modules_from_format() {
@@ -219,6 +453,76 @@ mod tests {
         assert_eq!(modules, Vec::<String>::new());
     }
 
+    #[test]
+    fn test_extract_modules_from_braced_tokens() {
+        let format = "${git_branch:-no-git} ${directory} $claude_model";
+        let modules = extract_modules_from_format(format);
+        assert_eq!(modules, vec!["git_branch", "directory", "claude_model"]);
+    }
+
+    #[rstest]
+    #[case("$name rest", Some(("name", None, false, 5)))]
+    #[case("${name} rest", Some(("name", None, true, 7)))]
+    #[case("${name:-fallback}", Some(("name", Some("fallback"), true, 17)))]
+    #[case("${name:-}", Some(("name", Some(""), true, 9)))]
+    #[case("${name:+:}x", None)]
+    #[case("${name:-unterminated", None)]
+    #[case("${name", None)]
+    #[case("$1", None)]
+    #[case("$", None)]
+    #[case("$directory#short x", Some(("directory#short", None, false, 16)))]
+    #[case("${directory#short:-}", Some(("directory#short", Some(""), true, 20)))]
+    #[case("$directory# x", Some(("directory", None, false, 10)))]
+    #[case("$directory#1", Some(("directory", None, false, 10)))]
+    fn test_scan_token(
+        #[case] input: &str,
+        #[case] expected: Option<(&str, Option<&str>, bool, usize)>,
+    ) {
+        let got = scan_token(input, 0).map(|t| (t.name, t.default, t.braced, t.end));
+        assert_eq!(got, expected);
+    }
+
+    #[rstest]
+    #[case("$directory $style", true)]
+    #[case("${style}", true)]
+    #[case("[$path]($style) $style", true)]
+    #[case("[$path]($style)", false)]
+    #[case("[$path](bold $style)", false)]
+    #[case("$styled", false)]
+    #[case("[→ $path](fg:#fff)", false)]
+    #[case("[$path](fg:gradient(#ff0000,#0000ff)) $style", true)]
+    fn bare_style_token_is_detected(#[case] format: &str, #[case] bare: bool) {
+        assert_eq!(has_bare_style_token(format), bare);
+    }
+
+    #[test]
+    fn test_extract_modules_keeps_variants_apart() {
+        let modules = extract_modules_from_format("$directory $directory#short ${directory#short}");
+        assert_eq!(modules, vec!["directory", "directory#short"]);
+        assert_eq!(
+            split_variant("directory#short"),
+            ("directory", Some("short"))
+        );
+        assert_eq!(split_variant("directory"), ("directory", None));
+    }
+
+    #[rstest]
+    #[case("$directory:<20 x", Some((Align::Left, 20)), 14)]
+    #[case("$directory:>8", Some((Align::Right, 8)), 13)]
+    #[case("${git_branch}:^10", Some((Align::Center, 10)), 17)]
+    #[case("${name:-none}:<5", Some((Align::Left, 5)), 16)]
+    #[case("$directory:<", None, 10)]
+    #[case("$directory:<0", None, 10)]
+    #[case("$directory: next", None, 10)]
+    fn test_scan_token_padding(
+        #[case] input: &str,
+        #[case] pad: Option<(Align, usize)>,
+        #[case] end: usize,
+    ) {
+        let tok = scan_token(input, 0).unwrap();
+        assert_eq!((tok.pad, tok.end), (pad, end));
+    }
+
     #[test]
     fn test_parse_format() {
         let input = ClaudeInput {
@@ -236,6 +540,7 @@ mod tests {
             }),
             version: Some("1.0.0".to_string()),
             output_style: None,
+            exit_status: None,
         };
 
         let config = Config::default();
@@ -266,6 +571,7 @@ mod tests {
             workspace: None,
             version: Some("1.0.0".to_string()),
             output_style: None,
+            exit_status: None,
         };
 
         let config = Config::default();
@@ -295,6 +601,7 @@ mod tests {
             workspace: None,
             version: Some("1.0.0".to_string()),
             output_style: None,
+            exit_status: None,
         };
 
         let config = Config::default();
@@ -316,6 +623,70 @@ mod tests {
         assert_eq!(result, "prefixlong suffix");
     }
 
+    #[rstest]
+    // Whitespace adjacent to a removed trailing token is dropped
+    #[case("$directory $character", "~/project")]
+    #[case("$directory $character $missing", "~/project")]
+    #[case("$directory $empty", "~/project")]
+    // Literal trailing whitespace in the format is preserved
+    #[case("$directory ", "~/project ")]
+    #[case("$directory \u{e0b0} ", "~/project \u{e0b0} ")]
+    #[case("$directory $character ", "~/project ")]
+    // Interior whitespace is never altered
+    #[case("$character  $directory", "  ~/project")]
+    fn test_parse_format_trailing_whitespace(#[case] format: &str, #[case] expected: &str) {
+        let input = ClaudeInput {
+            hook_event_name: None,
+            session_id: "test-123".to_string(),
+            transcript_path: None,
+            cwd: "/test/dir".to_string(),
+            model: ModelInfo {
+                id: "claude-opus".to_string(),
+                display_name: "Opus".to_string(),
+            },
+            workspace: None,
+            version: None,
+            output_style: None,
+            exit_status: None,
+        };
+        let context = Context::new(input, Config::default());
+
+        let mut module_outputs = HashMap::new();
+        module_outputs.insert("directory".to_string(), "~/project".to_string());
+        module_outputs.insert("empty".to_string(), String::new());
+
+        assert_eq!(parse_format(format, &context, &module_outputs), expected);
+    }
+
+    #[rstest]
+    #[case("$directory $dir")]
+    #[case("prefix$directory suffix")]
+    #[case("$git_branch $git")]
+    #[case("$git_branch$git")]
+    #[case("cost: $5 $directory")]
+    #[case("${git_branch:-none} ${missing:-fallback}")]
+    #[case("\u{e0b0} $directory \u{e0b0}")]
+    fn parse_format_matches_style_template_substitution(#[case] format: &str) {
+        let context = Context::new(
+            parse_claude_input(
+                r#"{"session_id":"s","cwd":"/tmp","model":{"id":"claude","display_name":"Claude"}}"#,
+            )
+            .unwrap(),
+            Config::default(),
+        );
+        let mut owned = HashMap::new();
+        owned.insert("dir".to_string(), "short".to_string());
+        owned.insert("directory".to_string(), "long".to_string());
+        owned.insert("git".to_string(), "G".to_string());
+        owned.insert("git_branch".to_string(), "BR".to_string());
+        let borrowed: HashMap<&str, String> =
+            owned.iter().map(|(k, v)| (k.as_str(), v.clone())).collect();
+
+        let via_parser = parse_format(format, &context, &owned);
+        let via_style = crate::style::render_with_style_template(format, &borrowed, "");
+        assert_eq!(via_parser, via_style);
+    }
+
     #[test]
     fn test_parse_valid_claude_input() {
         let json_str = r#"{
@@ -344,6 +715,25 @@ mod tests {
         assert_eq!(input.session_id, "test-session-123");
         assert_eq!(input.model.display_name, "Opus");
         assert_eq!(input.cwd, "/test/directory");
+        assert_eq!(input.exit_status, None);
+    }
+
+    #[rstest]
+    #[case(r#""exit_status": 1,"#, Some(1))]
+    #[case(r#""exit_status": 0,"#, Some(0))]
+    #[case("", None)]
+    fn test_parse_optional_exit_status(#[case] field: &str, #[case] expected: Option<i32>) {
+        let json_str = format!(
+            r#"{{
+            "session_id": "test-session-123",
+            {field}
+            "cwd": "/test/directory",
+            "model": {{ "id": "claude-opus-4-1", "display_name": "Opus" }}
+        }}"#
+        );
+
+        let input = parse_claude_input(&json_str).unwrap();
+        assert_eq!(input.exit_status, expected);
     }
 
     #[test]
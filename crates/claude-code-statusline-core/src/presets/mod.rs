@@ -0,0 +1,96 @@
+//! Built-in configuration presets
+//!
+//! A preset is an embedded TOML snippet selected with the top-level
+//! `preset = "<name>"` key. It is merged *under* the user's configuration,
+//! so any key the user sets (including `format`) wins over the preset.
+
+/// Available presets as `(name, toml)` pairs
+const PRESETS: &[(&str, &str)] = &[
+    ("default", include_str!("default.toml")),
+    ("minimal", include_str!("minimal.toml")),
+    ("powerline", include_str!("powerline.toml")),
+];
+
+/// Names of the built-in presets
+pub fn names() -> Vec<&'static str> {
+    PRESETS.iter().map(|(name, _)| *name).collect()
+}
+
+/// TOML source of the preset called `name`
+pub fn preset_toml(name: &str) -> Option<&'static str> {
+    PRESETS
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, toml)| *toml)
+}
+
+/// Recursively merge `overlay` into `base`; overlay values win and nested
+/// tables are merged key by key
+pub(crate) fn merge_tables(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(b)), toml::Value::Table(o)) => merge_tables(b, o),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("default")]
+    #[case("minimal")]
+    #[case("powerline")]
+    fn presets_parse_and_validate(#[case] name: &str) {
+        let cfg = Config::from_toml_str(&format!("preset = \"{name}\"")).unwrap();
+        cfg.validate().unwrap();
+        // Git tokens are only known with the `git` feature; styles must be clean
+        let warnings: Vec<String> = cfg
+            .collect_warnings()
+            .into_iter()
+            .filter(|w| cfg!(feature = "git") || !w.contains("$git_"))
+            .collect();
+        assert!(warnings.is_empty(), "{name}: {warnings:?}");
+        let standalone: Config = toml::from_str(preset_toml(name).unwrap()).unwrap();
+        assert_eq!(cfg.format, standalone.format);
+    }
+
+    #[test]
+    fn user_values_override_preset() {
+        let cfg = Config::from_toml_str(
+            r#"
+            preset = "minimal"
+            [directory]
+            style = "bold green"
+            "#,
+        )
+        .unwrap();
+        // Preset supplies format and untouched keys; user keys win
+        assert_eq!(
+            cfg.format,
+            "$directory${git_branch}${git_status} $claude_model"
+        );
+        assert_eq!(cfg.directory.truncation_length, 1);
+        assert_eq!(cfg.directory.style, "bold green");
+        assert_eq!(cfg.claude_model.style, "yellow");
+
+        let cfg =
+            Config::from_toml_str("preset = \"powerline\"\nformat = \"$claude_model\"").unwrap();
+        assert_eq!(cfg.format, "$claude_model");
+        assert_eq!(cfg.claude_model.format, "[$model]($style)");
+    }
+
+    #[test]
+    fn unknown_preset_is_a_validation_error() {
+        let cfg = Config::from_toml_str("preset = \"nope\"").unwrap();
+        assert_eq!(cfg.format, Config::default().format);
+        let err = cfg.validate().unwrap_err().to_string();
+        assert!(err.contains("unknown preset 'nope'"), "{err}");
+    }
+}
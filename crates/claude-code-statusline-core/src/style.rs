@@ -3,6 +3,223 @@
 //! This module provides functions for applying ANSI escape codes to
 //! terminal text, enabling colored and styled output in the status line.
 
+use serde::{Deserialize, Serialize};
+
+/// Color depth used when emitting RGB (`#rrggbb`) colors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum ColorDepth {
+    /// 24-bit `38;2;r;g;b` sequences
+    #[serde(rename = "truecolor")]
+    TrueColor,
+    /// Nearest xterm 256-color index
+    #[serde(rename = "256")]
+    Ansi256,
+    /// Nearest of the 16 base ANSI colors
+    #[serde(rename = "16")]
+    Ansi16,
+}
+
+/// Text transform applied to a module's rendered output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum Transform {
+    /// Uppercase every character
+    Upper,
+    /// Lowercase every character
+    Lower,
+    /// Remove leading and trailing whitespace
+    Trim,
+    /// Uppercase the first non-whitespace character
+    Capitalize,
+}
+
+impl Transform {
+    /// Apply the transform to the visible text of `s`, leaving escape
+    /// sequences (colors, hyperlinks) untouched
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use claude_code_statusline_core::style::Transform;
+    ///
+    /// assert_eq!(Transform::Upper.apply("\x1b[35mmain\x1b[0m"), "\x1b[35mMAIN\x1b[0m");
+    /// ```
+    pub fn apply(self, s: &str) -> String {
+        // (is_escape, text) runs in order
+        let bytes = s.as_bytes();
+        let mut runs: Vec<(bool, String)> = Vec::new();
+        let mut i = 0;
+        while let Some(off) = s[i..].find('\x1b') {
+            if off > 0 {
+                runs.push((false, s[i..i + off].to_string()));
+            }
+            i += off;
+            let len = escape_len(&bytes[i..]);
+            runs.push((true, s[i..i + len].to_string()));
+            i += len;
+        }
+        if i < s.len() {
+            runs.push((false, s[i..].to_string()));
+        }
+
+        match self {
+            Transform::Upper | Transform::Lower => {
+                for (_, text) in runs.iter_mut().filter(|(esc, _)| !esc) {
+                    *text = match self {
+                        Transform::Upper => text.to_uppercase(),
+                        _ => text.to_lowercase(),
+                    };
+                }
+            }
+            Transform::Trim => {
+                for (_, text) in runs.iter_mut().filter(|(esc, _)| !esc) {
+                    *text = text.trim_start().to_string();
+                    if !text.is_empty() {
+                        break;
+                    }
+                }
+                for (_, text) in runs.iter_mut().rev().filter(|(esc, _)| !esc) {
+                    *text = text.trim_end().to_string();
+                    if !text.is_empty() {
+                        break;
+                    }
+                }
+            }
+            Transform::Capitalize => {
+                for (_, text) in runs.iter_mut().filter(|(esc, _)| !esc) {
+                    if let Some((idx, c)) = text.char_indices().find(|(_, c)| !c.is_whitespace()) {
+                        let upper: String = c.to_uppercase().collect();
+                        text.replace_range(idx..idx + c.len_utf8(), &upper);
+                        break;
+                    }
+                }
+            }
+        }
+        runs.into_iter().map(|(_, text)| text).collect()
+    }
+}
+
+impl ColorDepth {
+    /// Color depth detected from the environment: truecolor when supported,
+    /// otherwise 256 colors
+    pub fn detect() -> Self {
+        if supports_truecolor() {
+            ColorDepth::TrueColor
+        } else {
+            ColorDepth::Ansi256
+        }
+    }
+}
+
+// Heuristics to decide if the terminal supports truecolor. This keeps
+// behavior consistent across environments where 24-bit colors are not
+// fully supported and avoids foreground/background mismatch when a host
+// silently downgrades one channel differently from the other.
+fn supports_truecolor() -> bool {
+    // Explicit override for tests or user preference: `1` forces truecolor
+    // on, `0` forces it off, anything else falls through to detection
+    match std::env::var("CCS_TRUECOLOR").as_deref() {
+        Ok("1") => return true,
+        Ok("0") => return false,
+        _ => {}
+    }
+    if let Ok(v) = std::env::var("COLORTERM") {
+        let v = v.to_lowercase();
+        if v.contains("truecolor") || v.contains("24bit") {
+            return true;
+        }
+    }
+    if let Ok(t) = std::env::var("TERM") {
+        let t = t.to_lowercase();
+        if t.contains("direct") || t.contains("truecolor") {
+            return true;
+        }
+    }
+    false
+}
+
+// Convert an RGB color to the nearest ANSI 256-color index.
+// Algorithm: prefer xterm 6x6x6 color cube (16..231) and fall back to
+// grayscale ramp (232..255) when r≈g≈b. This mirrors common mappers.
+fn rgb_to_ansi256(r: u8, g: u8, b: u8) -> u8 {
+    // If it's close to gray, map to grayscale range for better fidelity
+    let rg = r as i32 - g as i32;
+    let rb = r as i32 - b as i32;
+    let gb = g as i32 - b as i32;
+    let is_grayish = rg.abs() < 10 && rb.abs() < 10 && gb.abs() < 10;
+    if is_grayish {
+        // 24 grays, 8..238 step ~10
+        let gray = ((r as u16 + g as u16 + b as u16) / 3) as u8;
+        if gray < 8 {
+            return 16; // nearest to black
+        }
+        if gray > 238 {
+            return 231; // nearest to white from color cube
+        }
+        return 232 + ((gray as u16 - 8) / 10) as u8;
+    }
+    // Quantize each channel to 0..5 then map into 6x6x6 cube
+    let to_6 = |v: u8| -> u8 { ((v as u16 * 5 + 127) / 255) as u8 };
+    let r6 = to_6(r);
+    let g6 = to_6(g);
+    let b6 = to_6(b);
+    16 + 36 * r6 + 6 * g6 + b6
+}
+
+// xterm's default palette for the 16 base colors (0..=7 normal, 8..=15 bright)
+const ANSI16_PALETTE: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+// Convert an RGB color to the nearest of the 16 base ANSI colors (0..=15)
+// by squared Euclidean distance against the xterm palette.
+fn rgb_to_ansi16(r: u8, g: u8, b: u8) -> u8 {
+    let dist = |&(pr, pg, pb): &(u8, u8, u8)| {
+        let dr = r as i32 - pr as i32;
+        let dg = g as i32 - pg as i32;
+        let db = b as i32 - pb as i32;
+        dr * dr + dg * dg + db * db
+    };
+    (0..16u8)
+        .min_by_key(|&i| dist(&ANSI16_PALETTE[i as usize]))
+        .unwrap_or(0)
+}
+
+// SGR parameters for an RGB color; `base` is 38 (foreground) or 48 (background)
+fn rgb_to_sgr(r: u8, g: u8, b: u8, base: u8, depth: ColorDepth) -> String {
+    match depth {
+        ColorDepth::TrueColor => format!("{base};2;{r};{g};{b}"),
+        ColorDepth::Ansi256 => format!("{base};5;{}", rgb_to_ansi256(r, g, b)),
+        ColorDepth::Ansi16 => {
+            // 38 -> 30/90, 48 -> 40/100
+            let idx = rgb_to_ansi16(r, g, b);
+            let normal = base - 8;
+            if idx < 8 {
+                (normal + idx).to_string()
+            } else {
+                (normal + 60 + idx - 8).to_string()
+            }
+        }
+    }
+}
+
 /// Applies ANSI styling to text for terminal display
 ///
 /// Takes a text string and a style specification, returning the text
@@ -41,208 +258,340 @@
 /// - Unknown tokens are silently ignored
 /// - If no valid tokens are found, returns the original text
 /// - Multiple styles can be combined (e.g., "bold red underline")
+/// - RGB colors follow the detected [`ColorDepth`] (see [`ColorDepth::detect`])
 pub fn apply_style(text: &str, style: &str) -> String {
-    #[derive(Clone, Copy)]
-    enum ColorSpec {
-        NamedNormal(u8), // 30..=37 (FG) / 40..=47 (BG) base offset will be applied
-        NamedBright(u8), // 90..=97 / 100..=107 (store 0..=7)
-        Index(u8),       // 0..=255
-        Rgb(u8, u8, u8), // truecolor
-        NoneSet,         // explicit none
-    }
-
-    fn parse_named(name: &str) -> Option<u8> {
-        match name {
-            "black" => Some(0),
-            "red" => Some(1),
-            "green" => Some(2),
-            "yellow" => Some(3),
-            "blue" => Some(4),
-            "magenta" => Some(5),
-            "cyan" => Some(6),
-            "white" => Some(7),
-            _ => None,
-        }
+    apply_style_with_depth(text, style, ColorDepth::detect())
+}
+
+/// A color in a style string, for either channel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    /// `black`..`white` as `0..=7` (SGR 30–37 / 40–47)
+    Named(u8),
+    /// `bright-<name>` as `0..=7` (SGR 90–97 / 100–107)
+    Bright(u8),
+    /// xterm 256-color index (`0..=255`)
+    Index(u8),
+    /// `#rrggbb`
+    Rgb(u8, u8, u8),
+    /// `none`: reset the channel to the terminal default
+    Reset,
+    /// `prev_fg`: foreground of the previous style group
+    PrevFg,
+    /// `prev_bg`: background of the previous style group
+    PrevBg,
+    /// `gradient(#rrggbb,#rrggbb)`: foreground interpolated from the first
+    /// to the second color across the visible characters
+    Gradient((u8, u8, u8), (u8, u8, u8)),
+}
+
+/// Structured form of a style string such as `"bold fg:#bf5700 bg:238"`
+///
+/// Later tokens win for each channel, as in the renderer.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParsedStyle {
+    pub bold: bool,
+    pub dimmed: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+}
+
+/// Error returned by [`parse_style`]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum StyleError {
+    /// A token that is neither an attribute nor a color
+    #[error("unknown style token '{0}'")]
+    UnknownToken(String),
+}
+
+fn parse_named(name: &str) -> Option<u8> {
+    match name {
+        "black" => Some(0),
+        "red" => Some(1),
+        "green" => Some(2),
+        "yellow" => Some(3),
+        "blue" => Some(4),
+        "magenta" => Some(5),
+        "cyan" => Some(6),
+        "white" => Some(7),
+        _ => None,
     }
+}
 
-    // Heuristics to decide if the terminal supports truecolor. This keeps
-    // behavior consistent across environments where 24-bit colors are not
-    // fully supported and avoids foreground/background mismatch when a host
-    // silently downgrades one channel differently from the other.
-    fn supports_truecolor() -> bool {
-        // Explicit override for tests or user preference
-        if std::env::var("CCS_TRUECOLOR")
-            .map(|v| v == "1")
-            .unwrap_or(false)
-        {
-            return true;
-        }
-        if let Ok(v) = std::env::var("COLORTERM") {
-            let v = v.to_lowercase();
-            if v.contains("truecolor") || v.contains("24bit") {
-                return true;
-            }
-        }
-        if let Ok(t) = std::env::var("TERM") {
-            let t = t.to_lowercase();
-            if t.contains("direct") || t.contains("truecolor") {
-                return true;
-            }
-        }
-        false
-    }
-
-    // Convert an RGB color to the nearest ANSI 256-color index.
-    // Algorithm: prefer xterm 6x6x6 color cube (16..231) and fall back to
-    // grayscale ramp (232..255) when r≈g≈b. This mirrors common mappers.
-    fn rgb_to_ansi256(r: u8, g: u8, b: u8) -> u8 {
-        // If it's close to gray, map to grayscale range for better fidelity
-        let rg = r as i32 - g as i32;
-        let rb = r as i32 - b as i32;
-        let gb = g as i32 - b as i32;
-        let is_grayish = rg.abs() < 10 && rb.abs() < 10 && gb.abs() < 10;
-        if is_grayish {
-            // 24 grays, 8..238 step ~10
-            let gray = ((r as u16 + g as u16 + b as u16) / 3) as u8;
-            if gray < 8 {
-                return 16; // nearest to black
-            }
-            if gray > 238 {
-                return 231; // nearest to white from color cube
+fn parse_hex(spec: &str) -> Option<(u8, u8, u8)> {
+    let hex = spec.strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
+    let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
+    let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
+    Some((r, g, b))
+}
+
+fn parse_color_spec(spec: &str) -> Option<Color> {
+    let s = spec.to_lowercase();
+    match s.as_str() {
+        "none" => return Some(Color::Reset),
+        "prev_fg" => return Some(Color::PrevFg),
+        "prev_bg" => return Some(Color::PrevBg),
+        _ => {}
+    }
+    if let Some(args) = s
+        .strip_prefix("gradient(")
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        let (start, end) = args.split_once(',')?;
+        return Some(Color::Gradient(parse_hex(start)?, parse_hex(end)?));
+    }
+    if let Some((r, g, b)) = parse_hex(&s) {
+        return Some(Color::Rgb(r, g, b));
+    }
+    if s.chars().all(|c| c.is_ascii_digit()) {
+        if let Ok(n) = s.parse::<u16>() {
+            if n <= 255 {
+                return Some(Color::Index(n as u8));
             }
-            return 232 + ((gray as u16 - 8) / 10) as u8;
-        }
-        // Quantize each channel to 0..5 then map into 6x6x6 cube
-        let to_6 = |v: u8| -> u8 { ((v as u16 * 5 + 127) / 255) as u8 };
-        let r6 = to_6(r);
-        let g6 = to_6(g);
-        let b6 = to_6(b);
-        16 + 36 * r6 + 6 * g6 + b6
-    }
-
-    fn parse_color_spec(spec: &str) -> Option<ColorSpec> {
-        let s = spec.to_lowercase();
-        if s == "none" {
-            return Some(ColorSpec::NoneSet);
         }
-        if let Some(hex) = s.strip_prefix('#') {
-            if hex.len() == 6 {
-                let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
-                let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
-                let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
-                return Some(ColorSpec::Rgb(r, g, b));
-            }
+    }
+    if let Some(n) = s.strip_prefix("bright-") {
+        if let Some(idx) = parse_named(n) {
+            return Some(Color::Bright(idx));
         }
-        if s.chars().all(|c| c.is_ascii_digit()) {
-            if let Ok(n) = s.parse::<u16>() {
-                if n <= 255 {
-                    return Some(ColorSpec::Index(n as u8));
+    }
+    if let Some(idx) = parse_named(&s) {
+        return Some(Color::Named(idx));
+    }
+    None
+}
+
+/// Parse `style`, passing each unrecognized token to `on_unknown`
+fn parse_style_with(style: &str, mut on_unknown: impl FnMut(&str)) -> ParsedStyle {
+    let mut parsed = ParsedStyle::default();
+    for token in style.split_whitespace() {
+        let t = token.to_lowercase();
+        match t.as_str() {
+            "bold" => parsed.bold = true,
+            "dimmed" => parsed.dimmed = true,
+            "italic" => parsed.italic = true,
+            "underline" => parsed.underline = true,
+            _ => {
+                let (channel, color) = if let Some(rest) = t.strip_prefix("fg:") {
+                    (&mut parsed.fg, parse_color_spec(rest))
+                } else if let Some(rest) = t.strip_prefix("bg:") {
+                    // Gradients only apply to the foreground
+                    let color =
+                        parse_color_spec(rest).filter(|c| !matches!(c, Color::Gradient(..)));
+                    (&mut parsed.bg, color)
+                } else {
+                    // Bare color spec is treated as foreground
+                    (&mut parsed.fg, parse_color_spec(&t))
+                };
+                match color {
+                    Some(color) => *channel = Some(color),
+                    None => on_unknown(token),
                 }
             }
         }
-        if let Some(n) = s.strip_prefix("bright-") {
-            if let Some(idx) = parse_named(n) {
-                return Some(ColorSpec::NamedBright(idx));
-            }
-        }
-        if let Some(idx) = parse_named(&s) {
-            return Some(ColorSpec::NamedNormal(idx));
-        }
-        None
     }
+    parsed
+}
 
-    // Modifiers
-    let mut bold = false;
-    let mut italic = false;
-    let mut underline = false;
+/// Parse a style string the same way the renderer does
+///
+/// # Examples
+///
+/// ```
+/// use claude_code_statusline_core::style::{parse_style, Color, StyleError};
+///
+/// let style = parse_style("bold fg:#bf5700 bg:prev_fg").unwrap();
+/// assert!(style.bold);
+/// assert_eq!(style.fg, Some(Color::Rgb(0xbf, 0x57, 0x00)));
+/// assert_eq!(style.bg, Some(Color::PrevFg));
+///
+/// assert_eq!(
+///     parse_style("bold blink"),
+///     Err(StyleError::UnknownToken("blink".into()))
+/// );
+/// ```
+pub fn parse_style(style: &str) -> Result<ParsedStyle, StyleError> {
+    let mut unknown = None;
+    let parsed = parse_style_with(style, |token| {
+        unknown.get_or_insert_with(|| token.to_string());
+    });
+    match unknown {
+        Some(token) => Err(StyleError::UnknownToken(token)),
+        None => Ok(parsed),
+    }
+}
 
-    // Color channels: last one wins
-    let mut fg: Option<ColorSpec> = None;
-    let mut bg: Option<ColorSpec> = None;
+/// SGR parameter for `color` on the channel whose base is 30 (fg) or 40 (bg)
+///
+/// Unresolved `prev_*` references produce nothing.
+fn color_sgr(color: Color, base: u8, depth: ColorDepth) -> Option<String> {
+    Some(match color {
+        Color::Named(idx) => (base + idx).to_string(),
+        Color::Bright(idx) => (base + 60 + idx).to_string(),
+        Color::Index(n) => format!("{};5;{n}", base + 8),
+        Color::Rgb(r, g, b) => rgb_to_sgr(r, g, b, base + 8, depth),
+        Color::Reset => (base + 9).to_string(),
+        Color::PrevFg | Color::PrevBg => return None,
+        // Without truecolor a gradient is drawn in its start color
+        Color::Gradient((r, g, b), _) => rgb_to_sgr(r, g, b, base + 8, depth),
+    })
+}
 
-    for token in style.split_whitespace() {
-        let t = token.to_lowercase();
-        match t.as_str() {
-            "bold" => {
-                bold = true;
-                continue;
-            }
-            "italic" => {
-                italic = true;
-                continue;
-            }
-            "underline" => {
-                underline = true;
-                continue;
-            }
-            _ => {}
+/// Color each visible character of `text` along the gradient `start..=end`
+///
+/// `codes` are the remaining SGR parameters of the style (attributes and
+/// background) and are repeated with every character's foreground.
+/// Escape sequences already in `text` are copied through, and zero-width
+/// characters (combining marks, joiners) keep the color of the character
+/// they attach to.
+fn gradient_text(text: &str, codes: &[String], start: (u8, u8, u8), end: (u8, u8, u8)) -> String {
+    use unicode_width::UnicodeWidthChar;
+
+    let steps = strip_ansi(text)
+        .chars()
+        .filter(|c| c.width().unwrap_or(0) > 0)
+        .count();
+    let lerp = |a: u8, b: u8, i: usize| -> u8 {
+        if steps <= 1 {
+            return a;
         }
+        let (a, b) = (a as i32, b as i32);
+        (a + (b - a) * i as i32 / (steps as i32 - 1)) as u8
+    };
 
-        if let Some(rest) = t.strip_prefix("fg:") {
-            fg = parse_color_spec(rest);
+    let bytes = text.as_bytes();
+    let mut out = String::with_capacity(text.len() * 20);
+    let mut step = 0;
+    let mut i = 0;
+    while i < text.len() {
+        if bytes[i] == 0x1b {
+            let len = escape_len(&bytes[i..]);
+            out.push_str(&text[i..i + len]);
+            i += len;
             continue;
         }
-        if let Some(rest) = t.strip_prefix("bg:") {
-            bg = parse_color_spec(rest);
-            continue;
+        let c = text[i..].chars().next().unwrap_or_default();
+        if c.width().unwrap_or(0) > 0 {
+            let (r, g, b) = (
+                lerp(start.0, end.0, step),
+                lerp(start.1, end.1, step),
+                lerp(start.2, end.2, step),
+            );
+            step += 1;
+            out.push_str("\x1b[");
+            for code in codes {
+                out.push_str(code);
+                out.push(';');
+            }
+            out.push_str(&format!("38;2;{r};{g};{b}m"));
         }
+        out.push(c);
+        i += c.len_utf8();
+    }
+    out.push_str("\x1b[0m");
+    out
+}
 
-        // Bare color spec is treated as foreground
-        if let Some(cs) = parse_color_spec(&t) {
-            fg = Some(cs);
-        } else {
-            // Unknown token: ignore
+/// Channel colors of the previous style group, for `prev_fg`/`prev_bg`
+#[derive(Default)]
+struct PrevColors {
+    fg: Option<String>,
+    bg: Option<String>,
+}
+
+impl PrevColors {
+    /// Replace `prev_fg`/`prev_bg` in `style` and remember its channels
+    ///
+    /// Either name works on either channel (`fg:prev_bg`, `bg:prev_fg`, ...)
+    /// and takes the named channel of the previous group; a bare name sets
+    /// the foreground like any bare color. References to a channel the
+    /// previous group didn't set are dropped.
+    fn resolve(&mut self, style: &str) -> String {
+        let mut fg = None;
+        let mut bg = None;
+        let mut resolved: Vec<String> = Vec::new();
+        for token in style.split_whitespace() {
+            let lower = token.to_lowercase();
+            let (prefix, spec) = match lower.split_once(':') {
+                Some((p @ ("fg" | "bg"), rest)) => (Some(p), rest),
+                _ => (None, lower.as_str()),
+            };
+            let spec = match spec {
+                "prev_fg" => self.fg.clone(),
+                "prev_bg" => self.bg.clone(),
+                other => match parse_color_spec(other) {
+                    // Later groups see a gradient as its end color
+                    Some(Color::Gradient(_, (r, g, b))) if prefix != Some("bg") => {
+                        fg = Some(format!("#{r:02x}{g:02x}{b:02x}"));
+                        resolved.push(format!("fg:{other}"));
+                        continue;
+                    }
+                    Some(_) => Some(other.to_string()),
+                    None => {
+                        resolved.push(token.to_string());
+                        continue;
+                    }
+                },
+            };
+            let Some(spec) = spec else { continue };
+            if prefix == Some("bg") {
+                bg = Some(spec.clone());
+                resolved.push(format!("bg:{spec}"));
+            } else {
+                fg = Some(spec.clone());
+                resolved.push(format!("fg:{spec}"));
+            }
         }
+        self.fg = fg;
+        self.bg = bg;
+        resolved.join(" ")
     }
+}
+
+fn apply_style_with_depth(text: &str, style: &str, depth: ColorDepth) -> String {
+    // Unknown tokens are ignored here; `parse_style` reports them
+    let parsed = parse_style_with(style, |_| {});
 
     let mut codes: Vec<String> = Vec::with_capacity(5);
-    if bold {
+    if parsed.bold {
         codes.push("1".to_string());
     }
-    if italic {
+    if parsed.dimmed {
+        codes.push("2".to_string());
+    }
+    if parsed.italic {
         codes.push("3".to_string());
     }
-    if underline {
+    if parsed.underline {
         codes.push("4".to_string());
     }
-
-    if let Some(c) = fg {
-        match c {
-            ColorSpec::NamedNormal(idx) => codes.push((30 + idx).to_string()),
-            ColorSpec::NamedBright(idx) => codes.push((90 + idx).to_string()),
-            ColorSpec::Index(n) => codes.push(format!("38;5;{n}")),
-            ColorSpec::Rgb(r, g, b) => {
-                if supports_truecolor() {
-                    codes.push(format!("38;2;{r};{g};{b}"));
-                } else {
-                    let n = rgb_to_ansi256(r, g, b);
-                    codes.push(format!("38;5;{n}"));
-                }
-            }
-            ColorSpec::NoneSet => {}
+    let bg = parsed.bg.and_then(|c| color_sgr(c, 40, depth));
+    if let Some(Color::Gradient(start, end)) = parsed.fg {
+        if depth == ColorDepth::TrueColor && has_visible_text(text) {
+            codes.extend(bg);
+            return gradient_text(text, &codes, start, end);
         }
     }
-    if let Some(c) = bg {
-        match c {
-            ColorSpec::NamedNormal(idx) => codes.push((40 + idx).to_string()),
-            ColorSpec::NamedBright(idx) => codes.push((100 + idx).to_string()),
-            ColorSpec::Index(n) => codes.push(format!("48;5;{n}")),
-            ColorSpec::Rgb(r, g, b) => {
-                if supports_truecolor() {
-                    codes.push(format!("48;2;{r};{g};{b}"));
-                } else {
-                    let n = rgb_to_ansi256(r, g, b);
-                    codes.push(format!("48;5;{n}"));
-                }
-            }
-            ColorSpec::NoneSet => {}
-        }
+    if let Some(c) = parsed.fg {
+        codes.extend(color_sgr(c, 30, depth));
     }
+    codes.extend(bg);
 
     if codes.is_empty() {
         return text.to_string();
     }
     let sgr = codes.join(";");
+    // A style that only resets channels (`fg:none`/`bg:none`) must not end
+    // with a full reset, which would also drop an enclosing background
+    if codes.iter().all(|c| c == "39" || c == "49") {
+        return format!("\x1b[{sgr}m{text}");
+    }
     format!("\x1b[{sgr}m{text}\x1b[0m")
 }
 
@@ -251,27 +600,41 @@ pub fn apply_style(text: &str, style: &str) -> String {
 /// annotations: `[$content]($style)`.
 ///
 /// - Variables inside the bracket content are substituted first.
+/// - `${name:-default}` substitutes `default` when the value for `name` is
+///   empty (no visible text) or absent; `${name}` renders absent values as
+///   an empty string.
+/// - Plain `$name` tokens without a value are left verbatim.
+/// - `$name:<20`, `$name:>20` and `$name:^20` pad the value to 20 visible
+///   columns (left, right or center aligned); ANSI escapes don't count and
+///   longer values are left unchanged.
 /// - The style inside parentheses can be a literal (e.g. "bold yellow") or
 ///   `$style` which resolves to `default_style`.
+/// - `prev_fg` / `prev_bg` in a style (bare, or after `fg:` / `bg:`) take the
+///   foreground / background of the previous group, e.g. powerline
+///   separators `[](fg:prev_bg bg:blue)`.
 /// - If there is no bracket-style annotation, the variables are substituted and
 ///   returned as-is.
+/// - RGB colors follow the detected [`ColorDepth`]; use
+///   [`render_with_style_template_depth`] to pick one.
 pub fn render_with_style_template(
     format: &str,
     tokens: &std::collections::HashMap<&str, String>,
     default_style: &str,
 ) -> String {
-    // First, replace known tokens except "$style" using deterministic,
-    // longest-key-first ordering to avoid overlaps (e.g., $git vs $git_branch).
-    let mut replaced = String::from(format);
-    let mut keys: Vec<&str> = tokens.keys().copied().filter(|k| *k != "style").collect();
-    // Sort by descending length so longer tokens are substituted first
-    keys.sort_by_key(|k| std::cmp::Reverse(k.len()));
-    for k in keys {
-        if let Some(v) = tokens.get(k) {
-            let needle = format!("${k}");
-            replaced = replaced.replace(&needle, v);
-        }
-    }
+    render_with_style_template_depth(format, tokens, default_style, ColorDepth::detect())
+}
+
+/// Like [`render_with_style_template`], emitting RGB colors at `depth`
+pub fn render_with_style_template_depth(
+    format: &str,
+    tokens: &std::collections::HashMap<&str, String>,
+    default_style: &str,
+    depth: ColorDepth,
+) -> String {
+    // First, replace known tokens except "$style" in a single scan so that
+    // overlapping names (e.g., $git vs $git_branch) resolve by full identifier
+    // and substituted values are never re-scanned for tokens.
+    let replaced = substitute_tokens(format, tokens);
 
     // Robust pass to process [text](style) while ignoring ANSI escape
     // sequences already present in the string (e.g., from substituted
@@ -283,25 +646,15 @@ pub fn render_with_style_template(
     let mut out = String::with_capacity(len + 16);
     // Start index of the current literal chunk to be copied as-is
     let mut seg_start = 0usize;
+    let mut prev = PrevColors::default();
 
     while i < len {
         let b = bytes[i];
         if b == 0x1b {
-            // ESC: copy SGR/CSI sequence verbatim
+            // ESC: copy the whole CSI/OSC sequence verbatim
             let start = i;
-            i += 1; // Skip ESC
-            if i < len && bytes[i] == b'[' {
-                i += 1;
-                while i < len {
-                    let bb = bytes[i];
-                    if (0x40..=0x7E).contains(&bb) {
-                        i += 1; // include terminator
-                        break;
-                    }
-                    i += 1;
-                }
-            }
-            // flush preceding literal then CSI
+            i += escape_len(&bytes[i..]);
+            // flush preceding literal then the sequence
             if seg_start < start {
                 out.push_str(&replaced[seg_start..start]);
             }
@@ -316,17 +669,18 @@ pub fn render_with_style_template(
             if seg_start < i {
                 out.push_str(&replaced[seg_start..i]);
             }
+            // Brackets inside escape sequences (e.g. OSC `\x1b]`) don't count
             let mut j = i + 1;
             while j < len && bytes[j] != b']' {
-                j += 1;
+                j += if bytes[j] == 0x1b {
+                    escape_len(&bytes[j..])
+                } else {
+                    1
+                };
             }
             if j < len && j + 1 < len && bytes[j + 1] == b'(' {
-                // Find right parenthesis
-                let mut k = j + 2;
-                while k < len && bytes[k] != b')' {
-                    k += 1;
-                }
-                if k < len {
+                // Find the matching right parenthesis
+                if let Some(k) = annotation_end(&bytes[j + 2..]).map(|end| j + 2 + end) {
                     let inner = &replaced[i + 1..j];
                     let style_spec = &replaced[j + 2..k];
                     let style_to_use = if style_spec == "$style" {
@@ -334,7 +688,11 @@ pub fn render_with_style_template(
                     } else {
                         style_spec
                     };
-                    out.push_str(&apply_style(inner, style_to_use));
+                    out.push_str(&apply_style_with_depth(
+                        inner,
+                        &prev.resolve(style_to_use),
+                        depth,
+                    ));
                     i = k + 1;
                     seg_start = i;
                     continue;
@@ -358,9 +716,187 @@ pub fn render_with_style_template(
     out
 }
 
+/// Replace `$name`, `${name}` and `${name:-default}` tokens using `tokens`.
+///
+/// `$style` is intentionally preserved so the bracket pass can resolve it.
+fn substitute_tokens(format: &str, tokens: &std::collections::HashMap<&str, String>) -> String {
+    let mut out = String::with_capacity(format.len());
+    let mut i = 0;
+    while let Some(off) = format[i..].find('$') {
+        let at = i + off;
+        out.push_str(&format[i..at]);
+        match crate::parser::scan_token(format, at) {
+            Some(tok) if tok.name != "style" => {
+                let value = tokens.get(tok.name).map(String::as_str);
+                match crate::parser::resolve_token(&tok, value) {
+                    Some(v) => out.push_str(&pad_token(&tok, v)),
+                    // Hidden braced tokens still occupy their padded slot
+                    None if tok.braced => out.push_str(&pad_token(&tok, "")),
+                    None => out.push_str(&format[at..tok.end]),
+                }
+                i = tok.end;
+            }
+            _ => {
+                out.push('$');
+                i = at + 1;
+            }
+        }
+    }
+    out.push_str(&format[i..]);
+    out
+}
+
+/// Apply the token's padding directive (if any) to its substituted value
+pub(crate) fn pad_token<'a>(
+    tok: &crate::parser::FormatToken<'_>,
+    value: &'a str,
+) -> std::borrow::Cow<'a, str> {
+    use crate::parser::Align;
+    let Some((align, width)) = tok.pad else {
+        return value.into();
+    };
+    let visible = visible_width(value);
+    if visible >= width {
+        return value.into();
+    }
+    let fill = width - visible;
+    let (left, right) = match align {
+        Align::Left => (0, fill),
+        Align::Right => (fill, 0),
+        Align::Center => (fill / 2, fill - fill / 2),
+    };
+    format!("{}{value}{}", " ".repeat(left), " ".repeat(right)).into()
+}
+
+/// Offset of the `)` closing a style annotation in `bytes`, which starts
+/// just past the opening `(`
+///
+/// Nested parentheses (e.g. `fg:gradient(#ff0000,#0000ff)`) stay inside the
+/// annotation and escape sequences are skipped; `None` when unclosed.
+pub(crate) fn annotation_end(bytes: &[u8]) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut k = 0;
+    while k < bytes.len() {
+        match bytes[k] {
+            0x1b => {
+                k += escape_len(&bytes[k..]);
+                continue;
+            }
+            b'(' => depth += 1,
+            b')' if depth == 0 => return Some(k),
+            b')' => depth -= 1,
+            _ => {}
+        }
+        k += 1;
+    }
+    None
+}
+
+/// Length in bytes of the escape sequence at the start of `bytes` (an ESC)
+///
+/// - CSI `ESC [ … final` (final byte in `0x40..=0x7e`, e.g. SGR `m`)
+/// - OSC `ESC ] … BEL` or `ESC ] … ESC \` (e.g. OSC 8 hyperlinks)
+/// - any other `ESC x` pair (`x` ASCII)
+///
+/// An unterminated sequence extends to the end of `bytes`; the result
+/// always ends on a UTF-8 character boundary.
+fn escape_len(bytes: &[u8]) -> usize {
+    match bytes.get(1) {
+        Some(b'[') => bytes[2..]
+            .iter()
+            .position(|b| (0x40..=0x7e).contains(b))
+            .map_or(bytes.len(), |p| p + 3),
+        Some(b']') => {
+            let mut p = 2;
+            while p < bytes.len() {
+                match bytes[p] {
+                    0x07 => return p + 1,
+                    0x1b if bytes.get(p + 1) == Some(&b'\\') => return p + 2,
+                    _ => p += 1,
+                }
+            }
+            bytes.len()
+        }
+        Some(b) if b.is_ascii() => 2,
+        // Lone ESC (or one followed by a multi-byte character)
+        _ => 1,
+    }
+}
+
+/// Remove escape sequences (SGR such as `\x1b[1;31m`, other CSI, OSC) from `s`
+///
+/// # Examples
+///
+/// ```
+/// use claude_code_statusline_core::style::strip_ansi;
+///
+/// assert_eq!(strip_ansi("\x1b[1;31mmain\x1b[0m"), "main");
+/// ```
+pub fn strip_ansi(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = String::with_capacity(s.len());
+    let mut i = 0;
+    while let Some(off) = s[i..].find('\x1b') {
+        out.push_str(&s[i..i + off]);
+        i += off;
+        i += escape_len(&bytes[i..]);
+    }
+    out.push_str(&s[i..]);
+    out
+}
+
+/// Display width of `s` in terminal columns, ignoring escape sequences
+///
+/// Wide characters (CJK, most emoji) count as two columns.
+///
+/// # Examples
+///
+/// ```
+/// use claude_code_statusline_core::style::visible_width;
+///
+/// assert_eq!(visible_width("\x1b[36m日本\x1b[0m"), 4);
+/// ```
+pub fn visible_width(s: &str) -> usize {
+    unicode_width::UnicodeWidthStr::width(strip_ansi(s).as_str())
+}
+
+/// Whether `s` contains any non-whitespace text outside of escape sequences
+pub(crate) fn has_visible_text(s: &str) -> bool {
+    strip_ansi(s).chars().any(|c| !c.is_whitespace())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_env::env_lock;
+    use rstest::rstest;
+
+    /// Set (or remove with `None`) env vars, returning the previous values
+    fn set_env(vars: &[(&'static str, Option<&str>)]) -> Vec<(&'static str, Option<String>)> {
+        vars.iter()
+            .map(|(k, v)| {
+                let prev = std::env::var(k).ok();
+                unsafe {
+                    match v {
+                        Some(v) => std::env::set_var(k, v),
+                        None => std::env::remove_var(k),
+                    }
+                }
+                (*k, prev)
+            })
+            .collect()
+    }
+
+    fn restore_env(prev: Vec<(&'static str, Option<String>)>) {
+        for (k, v) in prev {
+            unsafe {
+                match v {
+                    Some(v) => std::env::set_var(k, v),
+                    None => std::env::remove_var(k),
+                }
+            }
+        }
+    }
 
     #[test]
     fn applies_bold_yellow() {
@@ -477,6 +1013,156 @@ mod tests {
         assert!(!out.contains("_branch"));
     }
 
+    #[test]
+    fn default_token_uses_present_value() {
+        use std::collections::HashMap;
+        let mut tokens = HashMap::new();
+        tokens.insert("git_branch", String::from("main"));
+        let out = render_with_style_template("${git_branch:-no-git}", &tokens, "");
+        assert_eq!(out, "main");
+    }
+
+    #[test]
+    fn default_token_replaces_empty_value() {
+        use std::collections::HashMap;
+        let mut tokens = HashMap::new();
+        tokens.insert("git_branch", String::new());
+        tokens.insert("git_status", String::from("\u{1b}[1m\u{1b}[0m"));
+        let out =
+            render_with_style_template("${git_branch:-no-git} ${git_status:-clean}", &tokens, "");
+        assert_eq!(out, "no-git clean");
+    }
+
+    #[test]
+    fn default_token_replaces_missing_value() {
+        use std::collections::HashMap;
+        let tokens: HashMap<&str, String> = HashMap::new();
+        let out = render_with_style_template("[${git_branch:-no-git}](bold)", &tokens, "");
+        let plain = String::from_utf8(strip_ansi_escapes::strip(out)).unwrap();
+        assert_eq!(plain, "no-git");
+        // Braced token without a default renders absent values as empty
+        assert_eq!(render_with_style_template("a${x}b", &tokens, ""), "ab");
+        // Plain tokens without a value are preserved verbatim
+        assert_eq!(render_with_style_template("a $x b", &tokens, ""), "a $x b");
+    }
+
+    #[test]
+    fn substituted_values_are_not_rescanned() {
+        use std::collections::HashMap;
+        let mut tokens = HashMap::new();
+        tokens.insert("all_status", String::from("$1"));
+        tokens.insert("model", String::from("Opus"));
+        let out = render_with_style_template("$all_status $model", &tokens, "");
+        assert_eq!(out, "$1 Opus");
+    }
+
+    #[rstest]
+    #[case::plain("main", "main", 4)]
+    #[case::sgr("\x1b[1;31mmain\x1b[0m", "main", 4)]
+    #[case::truecolor("\x1b[38;2;1;2;3mA\x1b[0mB", "AB", 2)]
+    #[case::cjk("\x1b[36m日本語\x1b[0m", "日本語", 6)]
+    #[case::emoji("🌿 main", "🌿 main", 7)]
+    #[case::emoji_styled("\x1b[1m🦀\x1b[0m!", "🦀!", 3)]
+    #[case::empty("\x1b[0m", "", 0)]
+    fn strip_and_measure_ignore_escapes(
+        #[case] input: &str,
+        #[case] stripped: &str,
+        #[case] width: usize,
+    ) {
+        assert_eq!(strip_ansi(input), stripped);
+        assert_eq!(visible_width(input), width);
+    }
+
+    #[rstest]
+    #[case::bel("\x1b]8;;https://example.com/a]b\x07")]
+    #[case::st("\x1b]8;;https://example.com/a]b\x1b\\")]
+    fn osc_hyperlink_passes_through_groups(#[case] open: &str) {
+        let close = open.replace("https://example.com/a]b", "");
+        let link = format!("{open}docs{close}");
+        let mut tokens = std::collections::HashMap::new();
+        tokens.insert("link", link.clone());
+
+        // Inside a style group: the link is wrapped in SGR, bytes untouched
+        let out = render_with_style_template("[$link](bold) [x]", &tokens, "");
+        assert_eq!(out, format!("\x1b[1m{link}\x1b[0m [x]"));
+        // Outside a group: passed through verbatim
+        let out = render_with_style_template("<$link>", &tokens, "");
+        assert_eq!(out, format!("<{link}>"));
+
+        assert_eq!(strip_ansi(&link), "docs");
+        assert_eq!(visible_width(&link), 4);
+    }
+
+    #[test]
+    fn non_sgr_csi_and_unterminated_escapes_are_stripped() {
+        assert_eq!(strip_ansi("a\x1b[2Kb\x1b[1Ac"), "abc");
+        assert_eq!(strip_ansi("ok\x1b]8;;never-closed"), "ok");
+        assert_eq!(strip_ansi("ok\x1b[31"), "ok");
+        assert_eq!(strip_ansi("\x1b日本"), "日本");
+        let tokens = std::collections::HashMap::new();
+        assert_eq!(
+            render_with_style_template("[\x1b日](bold)", &tokens, ""),
+            "\x1b[1m\x1b日\x1b[0m"
+        );
+    }
+
+    #[rstest]
+    #[case::fg_prev_fg("fg:prev_fg", "\x1b[31mB\x1b[0m")]
+    #[case::fg_prev_bg("fg:prev_bg", "\x1b[34mB\x1b[0m")]
+    #[case::bg_prev_fg("bg:prev_fg", "\x1b[41mB\x1b[0m")]
+    #[case::bg_prev_bg("bg:prev_bg", "\x1b[44mB\x1b[0m")]
+    #[case::bare_prev_fg("prev_fg", "\x1b[31mB\x1b[0m")]
+    #[case::bare_prev_bg("prev_bg", "\x1b[34mB\x1b[0m")]
+    #[case::swapped("fg:prev_bg bg:prev_fg bold", "\x1b[1;34;41mB\x1b[0m")]
+    fn prev_colors_map_to_target_channel(#[case] style: &str, #[case] expected: &str) {
+        let tokens = std::collections::HashMap::new();
+        let format = format!("[A](fg:red bg:blue)[B]({style})");
+        let out = render_with_style_template(&format, &tokens, "");
+        assert_eq!(out, format!("\x1b[31;44mA\x1b[0m{expected}"));
+    }
+
+    #[test]
+    fn prev_colors_follow_the_immediately_previous_group() {
+        let tokens = std::collections::HashMap::new();
+        // No previous group: the reference is dropped
+        assert_eq!(
+            render_with_style_template("[B](bg:prev_bg)", &tokens, ""),
+            "B"
+        );
+        // Each group sees only the one before it
+        let out = render_with_style_template(
+            "[A](bg:blue)[B](fg:prev_bg bg:red)[C](fg:prev_bg)",
+            &tokens,
+            "",
+        );
+        assert_eq!(out, "\x1b[44mA\x1b[0m\x1b[34;41mB\x1b[0m\x1b[31mC\x1b[0m");
+    }
+
+    #[rstest]
+    #[case::empty("", ParsedStyle::default())]
+    #[case::attrs_and_bare_fg("bold italic yellow", ParsedStyle { bold: true, italic: true, fg: Some(Color::Named(3)), ..Default::default() })]
+    #[case::channels("fg:bright-blue bg:238", ParsedStyle { fg: Some(Color::Bright(4)), bg: Some(Color::Index(238)), ..Default::default() })]
+    #[case::hex_and_reset("underline fg:#BF5700 bg:none", ParsedStyle { underline: true, fg: Some(Color::Rgb(0xbf, 0x57, 0)), bg: Some(Color::Reset), ..Default::default() })]
+    #[case::prev("fg:prev_bg bg:prev_fg", ParsedStyle { fg: Some(Color::PrevBg), bg: Some(Color::PrevFg), ..Default::default() })]
+    #[case::dimmed("dimmed fg:none", ParsedStyle { dimmed: true, fg: Some(Color::Reset), ..Default::default() })]
+    #[case::last_wins("red fg:green", ParsedStyle { fg: Some(Color::Named(2)), ..Default::default() })]
+    fn parse_style_accepts_valid_specs(#[case] style: &str, #[case] expected: ParsedStyle) {
+        assert_eq!(parse_style(style), Ok(expected));
+    }
+
+    #[rstest]
+    #[case::unknown_attr("bold blink", "blink")]
+    #[case::unknown_name("purple", "purple")]
+    #[case::index_out_of_range("fg:256", "fg:256")]
+    #[case::short_hex("bg:#abc", "bg:#abc")]
+    #[case::first_reported("fg:nope bg:nada", "fg:nope")]
+    fn parse_style_rejects_unknown_tokens(#[case] style: &str, #[case] token: &str) {
+        assert_eq!(
+            parse_style(style),
+            Err(StyleError::UnknownToken(token.to_string()))
+        );
+    }
+
     #[test]
     fn style_none_handling() {
         let s = apply_style("X", "fg:none italic");
@@ -484,13 +1170,174 @@ mod tests {
         assert!(!s.contains("38;"));
     }
 
+    #[rstest]
+    #[case::fg("fg:none", "\x1b[39mB")]
+    #[case::bg("bg:none", "\x1b[49mB")]
+    #[case::both("fg:none bg:none", "\x1b[39;49mB")]
+    fn none_resets_only_its_channel(#[case] style: &str, #[case] expected: &str) {
+        assert_eq!(apply_style("B", style), expected);
+    }
+
+    #[test]
+    fn fg_none_segment_keeps_enclosing_background() {
+        let tokens = std::collections::HashMap::new();
+        let bg = "\x1b[48;2;17;34;51m";
+        let format = format!("{bg}A[B](fg:none)C");
+        let out = render_with_style_template(&format, &tokens, "");
+        assert_eq!(out, format!("{bg}A\x1b[39mBC"));
+        // Nothing between the background and `C` clears it
+        let c = out.rfind('C').unwrap();
+        assert!(!out[..c].contains("\x1b[0m") && !out[..c].contains("\x1b[49m"));
+    }
+
+    #[rstest]
+    #[case::left("$path:<8|", "~/a", "~/a     |")]
+    #[case::right("$path:>8|", "~/a", "     ~/a|")]
+    #[case::center("<$path:^8>", "~/a", "<  ~/a   >")]
+    #[case::overflow_unchanged("$path:<2", "~/abc", "~/abc")]
+    #[case::wide_chars("$path:<6|", "日本", "日本  |")]
+    fn padding_aligns_visible_text(#[case] format: &str, #[case] value: &str, #[case] plain: &str) {
+        let mut tokens = std::collections::HashMap::new();
+        tokens.insert("path", value.to_string());
+        let out = render_with_style_template(format, &tokens, "");
+        let stripped = String::from_utf8(strip_ansi_escapes::strip(&out)).unwrap();
+        assert_eq!(stripped, plain);
+    }
+
+    #[test]
+    fn padding_ignores_ansi_in_styled_values() {
+        let mut tokens = std::collections::HashMap::new();
+        tokens.insert("directory", apply_style("repo", "bold red"));
+        tokens.insert("model", apply_style("Opus", "fg:#ff8800"));
+        let out = render_with_style_template("$directory:<8|$model:>6|", &tokens, "");
+        // Padding goes outside the escapes and is based on visible width only
+        assert!(out.starts_with("\x1b[1;31mrepo\x1b[0m    |"));
+        assert!(out.contains("|  \x1b["));
+        let stripped = String::from_utf8(strip_ansi_escapes::strip(&out)).unwrap();
+        assert_eq!(stripped, "repo    |  Opus|");
+    }
+
+    #[test]
+    fn padding_reserves_slot_for_hidden_braced_token() {
+        let tokens = std::collections::HashMap::new();
+        let out = render_with_style_template("${git_branch}:<6|", &tokens, "");
+        assert_eq!(out, "      |");
+    }
+
+    #[rstest]
+    #[case::black(0x00, 0x00, 0x00, 0)]
+    #[case::dark_red(0xc0, 0x10, 0x10, 1)]
+    #[case::green(0x20, 0xc0, 0x30, 2)]
+    #[case::navy_blue(0x10, 0x10, 0xe0, 4)]
+    #[case::light_gray(0xd0, 0xd0, 0xd0, 7)]
+    #[case::mid_gray(0x80, 0x80, 0x80, 8)]
+    #[case::pure_red(0xff, 0x00, 0x00, 9)]
+    #[case::orange_yellow(0xff, 0xe0, 0x20, 11)]
+    #[case::white(0xff, 0xff, 0xff, 15)]
+    fn rgb_maps_to_nearest_ansi16(#[case] r: u8, #[case] g: u8, #[case] b: u8, #[case] idx: u8) {
+        assert_eq!(rgb_to_ansi16(r, g, b), idx);
+    }
+
+    #[rstest]
+    #[case::truecolor(ColorDepth::TrueColor, "38;2;255;0;0", "48;2;0;0;238")]
+    #[case::ansi256(ColorDepth::Ansi256, "38;5;196", "48;5;21")]
+    #[case::ansi16(ColorDepth::Ansi16, "91", "44")]
+    fn rgb_honors_color_depth(#[case] depth: ColorDepth, #[case] fg: &str, #[case] bg: &str) {
+        let s = apply_style_with_depth("X", "fg:#ff0000 bg:#0000ee", depth);
+        assert_eq!(s, format!("\x1b[{fg};{bg}mX\x1b[0m"));
+    }
+
+    #[rstest]
+    #[case::truecolor(ColorDepth::TrueColor, "38;2;255;0;0")]
+    #[case::ansi16(ColorDepth::Ansi16, "91")]
+    fn template_depth_is_per_call(#[case] depth: ColorDepth, #[case] fg: &str) {
+        let tokens = std::collections::HashMap::new();
+        let s = render_with_style_template_depth("[X](#ff0000)", &tokens, "", depth);
+        assert_eq!(s, format!("\x1b[{fg}mX\x1b[0m"));
+    }
+
+    #[test]
+    fn gradient_colors_each_character_in_truecolor() {
+        let s = apply_style_with_depth(
+            "abcd",
+            "bold fg:gradient(#ff0000,#0000ff)",
+            ColorDepth::TrueColor,
+        );
+        assert_eq!(strip_ansi(&s), "abcd");
+        let fgs: Vec<&str> = s
+            .split("\x1b[1;")
+            .skip(1)
+            .map(|seq| seq.split('m').next().unwrap())
+            .collect();
+        assert_eq!(
+            fgs,
+            [
+                "38;2;255;0;0",
+                "38;2;170;0;85",
+                "38;2;85;0;170",
+                "38;2;0;0;255"
+            ]
+        );
+        assert!(s.ends_with("d\x1b[0m"), "{s:?}");
+    }
+
+    #[test]
+    fn gradient_falls_back_to_start_color_without_truecolor() {
+        let s = apply_style_with_depth("abcd", "fg:gradient(#ff0000,#0000ff)", ColorDepth::Ansi256);
+        assert_eq!(s, "\x1b[38;5;196mabcd\x1b[0m");
+        assert_eq!(
+            parse_style("fg:gradient(#ff0000,#0000ff)").unwrap().fg,
+            Some(Color::Gradient((255, 0, 0), (0, 0, 255)))
+        );
+        assert!(parse_style("bg:gradient(#ff0000,#0000ff)").is_err());
+        assert!(parse_style("fg:gradient(#ff0000)").is_err());
+    }
+
+    #[test]
+    fn color_depth_deserializes_from_strings() {
+        #[derive(serde::Deserialize)]
+        struct T {
+            depth: ColorDepth,
+        }
+        for (s, d) in [
+            ("truecolor", ColorDepth::TrueColor),
+            ("256", ColorDepth::Ansi256),
+            ("16", ColorDepth::Ansi16),
+        ] {
+            let t: T = toml::from_str(&format!("depth = \"{s}\"")).unwrap();
+            assert_eq!(t.depth, d);
+        }
+    }
+
+    #[rstest]
+    #[case::forced_on(Some("1"), Some(""), Some("xterm-256color"), true)]
+    #[case::forced_off_despite_colorterm(Some("0"), Some("truecolor"), Some("xterm-direct"), false)]
+    #[case::auto_from_colorterm(None, Some("truecolor"), Some("xterm"), true)]
+    #[case::auto_from_term(None, Some(""), Some("xterm-direct"), true)]
+    #[case::auto_without_signals(None, Some(""), Some("xterm-256color"), false)]
+    #[case::unrecognized_value_is_auto(Some("yes"), Some("24bit"), None, true)]
+    fn ccs_truecolor_is_three_state(
+        #[case] ccs: Option<&str>,
+        #[case] colorterm: Option<&str>,
+        #[case] term: Option<&str>,
+        #[case] expected: bool,
+    ) {
+        let _g = env_lock();
+        let prev = set_env(&[
+            ("CCS_TRUECOLOR", ccs),
+            ("COLORTERM", colorterm),
+            ("TERM", term),
+        ]);
+        let detected = supports_truecolor();
+        restore_env(prev);
+        assert_eq!(detected, expected);
+    }
+
     #[test]
     fn rgb_foreground_background_downgrade_is_consistent() {
         // Ensure that when truecolor is not detected, the same RGB hex
         // maps to the same ANSI-256 index for both fg and bg.
-        use std::sync::{Mutex, OnceLock};
-        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
-        let _g = LOCK.get_or_init(|| Mutex::new(())).lock().unwrap();
+        let _g = env_lock();
         // Force non-truecolor environment
         unsafe {
             std::env::remove_var("CCS_TRUECOLOR");
@@ -519,4 +1366,19 @@ mod tests {
             assert!(fg.contains("38;2;") && bg.contains("48;2;"));
         }
     }
+
+    #[rstest]
+    #[case(Transform::Upper, "\x1b[1;35m  MAIN \x1b[0m\x1b[31m↑1 \x1b[0m")]
+    #[case(Transform::Lower, "\x1b[1;35m  main \x1b[0m\x1b[31m↑1 \x1b[0m")]
+    #[case(Transform::Trim, "\x1b[1;35mMain \x1b[0m\x1b[31m↑1\x1b[0m")]
+    #[case(Transform::Capitalize, "\x1b[1;35m  Main \x1b[0m\x1b[31m↑1 \x1b[0m")]
+    fn transforms_touch_only_visible_text(#[case] transform: Transform, #[case] expected: &str) {
+        let styled = "\x1b[1;35m  Main \x1b[0m\x1b[31m↑1 \x1b[0m";
+        let styled = if transform == Transform::Capitalize {
+            styled.replace("Main", "main")
+        } else {
+            styled.to_string()
+        };
+        assert_eq!(transform.apply(&styled), expected);
+    }
 }
@@ -0,0 +1,167 @@
+//! Glyph sets for the `symbol_mode` setting
+//!
+//! Modules look up their default symbols here by logical name so one
+//! top-level switch can move the whole line between Unicode, Nerd Font
+//! and plain ASCII glyphs.
+//!
+//! A symbol is only swapped while it still holds its Unicode default;
+//! values customized in the module's config table are kept as-is.
+
+use serde::{Deserialize, Serialize};
+
+/// Glyph set used for module symbols
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum SymbolMode {
+    /// Unicode symbols (the module defaults)
+    #[default]
+    Unicode,
+    /// Nerd Font / Powerline glyphs
+    NerdFont,
+    /// ASCII-only fallbacks for limited terminals and fonts
+    Ascii,
+}
+
+/// Logical symbol name → glyphs as `[unicode, nerdfont, ascii]`
+const SYMBOLS: &[(&str, [&str; 3])] = &[
+    ("git_branch", ["🌿", "\u{f418}", "branch:"]),
+    ("git_branch.truncation_symbol", ["…", "…", "..."]),
+    ("git_status.ahead", ["⇡", "⇡", "^"]),
+    ("git_status.behind", ["⇣", "⇣", "v"]),
+    ("git_status.diverged", ["⇕", "⇕", "<>"]),
+    ("git_status.deleted", ["✘", "✘", "x"]),
+    ("git_status.renamed", ["»", "»", ">"]),
+    ("git_status.ignored", ["◌", "◌", "i"]),
+    ("git_status.changed", ["±", "±", "~"]),
+    ("status", ["✘ ", "\u{f00d} ", "x "]),
+    ("context_usage.bar_filled", ["█", "█", "#"]),
+    ("context_usage.bar_empty", ["░", "░", "-"]),
+    ("claude_model", ["", "\u{f544} ", ""]),
+];
+
+/// Glyph for the logical symbol `name` in `mode`, if the name is known
+pub fn glyph(name: &str, mode: SymbolMode) -> Option<&'static str> {
+    SYMBOLS
+        .iter()
+        .find(|(key, _)| *key == name)
+        .map(|(_, set)| set[mode as usize])
+}
+
+/// Resolve a configured symbol for `mode`
+///
+/// Returns the mode's glyph when `configured` is still the Unicode default
+/// of `name`, otherwise `configured` unchanged.
+pub fn resolve<'a>(name: &str, configured: &'a str, mode: SymbolMode) -> &'a str {
+    match glyph(name, SymbolMode::Unicode) {
+        Some(default) if default == configured => glyph(name, mode).unwrap_or(configured),
+        _ => configured,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(SymbolMode::Unicode, "🌿")]
+    #[case(SymbolMode::NerdFont, "\u{f418}")]
+    #[case(SymbolMode::Ascii, "branch:")]
+    fn default_symbol_follows_mode(#[case] mode: SymbolMode, #[case] expected: &str) {
+        assert_eq!(resolve("git_branch", "🌿", mode), expected);
+    }
+
+    #[test]
+    fn empty_unicode_default_gains_a_nerdfont_glyph() {
+        assert_eq!(resolve("claude_model", "", SymbolMode::Ascii), "");
+        assert_eq!(
+            resolve("claude_model", "", SymbolMode::NerdFont),
+            "\u{f544} "
+        );
+        assert_eq!(resolve("claude_model", "<", SymbolMode::NerdFont), "<");
+    }
+
+    #[test]
+    fn customized_and_unknown_symbols_are_kept() {
+        assert_eq!(resolve("git_branch", "*", SymbolMode::Ascii), "*");
+        assert_eq!(resolve("nope", "◆", SymbolMode::Ascii), "◆");
+    }
+
+    #[test]
+    fn ascii_glyphs_are_ascii() {
+        for (name, _) in SYMBOLS {
+            let g = glyph(name, SymbolMode::Ascii).unwrap();
+            assert!(g.is_ascii(), "{name}: {g:?}");
+        }
+    }
+
+    #[test]
+    fn mode_parses_from_toml() {
+        #[derive(Deserialize)]
+        struct T {
+            symbol_mode: SymbolMode,
+        }
+        for (s, mode) in [
+            ("unicode", SymbolMode::Unicode),
+            ("nerdfont", SymbolMode::NerdFont),
+            ("ascii", SymbolMode::Ascii),
+        ] {
+            let t: T = toml::from_str(&format!("symbol_mode = \"{s}\"")).unwrap();
+            assert_eq!(t.symbol_mode, mode);
+        }
+    }
+
+    #[cfg(feature = "git")]
+    #[test]
+    fn ascii_mode_never_emits_non_ascii() {
+        use crate::{Config, Engine, parse_claude_input};
+        use git2::{Repository, Signature};
+
+        // Repository on `main` with a deleted file and one commit ahead of origin/main
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().join("repo");
+        std::fs::create_dir_all(&root).unwrap();
+        let repo = Repository::init(&root).unwrap();
+        let sig = Signature::now("Tester", "tester@example.com").unwrap();
+        std::fs::write(root.join("README.md"), "init\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new("README.md")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let base = repo
+            .commit(Some("refs/heads/main"), &sig, &sig, "initial", &tree, &[])
+            .unwrap();
+        repo.set_head("refs/heads/main").unwrap();
+        repo.reference("refs/remotes/origin/main", base, true, "test")
+            .unwrap();
+        let parent = repo.find_commit(base).unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "second", &tree, &[&parent])
+            .unwrap();
+        std::fs::remove_file(root.join("README.md")).unwrap();
+        index
+            .remove_path(std::path::Path::new("README.md"))
+            .unwrap();
+        index.write().unwrap();
+
+        let mut config = Config {
+            format: "$directory $git_branch $git_status $claude_model $status".to_string(),
+            symbol_mode: SymbolMode::Ascii,
+            ..Default::default()
+        };
+        config.git_branch.truncation_length = 3;
+        config.git_status.compare_to = Some("origin/main".to_string());
+        config.git_status.format = "[$all_status$ahead_behind]($style)".to_string();
+        let json = format!(
+            r#"{{"session_id":"s","cwd":{cwd:?},"model":{{"id":"claude-opus","display_name":"Opus"}},"exit_status":1}}"#,
+            cwd = root.to_str().unwrap()
+        );
+        let input = parse_claude_input(&json).unwrap();
+        let out = Engine::new(config).render(&input).unwrap();
+        let plain = String::from_utf8(strip_ansi_escapes::strip(&out)).unwrap();
+
+        assert!(out.is_ascii(), "{plain}");
+        assert!(plain.contains("branch: mai... x1+1^1 "), "{plain}");
+        assert!(plain.ends_with("x 1"), "{plain}");
+    }
+}
@@ -0,0 +1,14 @@
+//! Shared lock for tests that touch process environment variables
+
+use std::sync::{Mutex, MutexGuard, OnceLock};
+
+/// Serialize tests that set or read environment variables such as `HOME`
+///
+/// The environment is process-wide, so every module's tests must share
+/// this one lock. A lock poisoned by a failed test stays usable.
+pub(crate) fn env_lock() -> MutexGuard<'static, ()> {
+    static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+    LOCK.get_or_init(|| Mutex::new(()))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+}
@@ -2,12 +2,65 @@
 //!
 //! This module provides functions for running operations with time limits,
 //! ensuring that slow operations don't block the status line generation.
+//!
+//! A timed-out worker cannot be cancelled; it keeps running in the
+//! background until its closure returns. To keep repeated slow frames from
+//! piling up threads, the number of live workers is capped at
+//! [`MAX_BACKGROUND_WORKERS`]. When the cap is reached new work is refused
+//! with [`CoreError::WorkerLimit`] instead of spawning another thread.
 
 use crate::error::CoreError;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::mpsc;
 use std::thread;
 use std::time::Duration;
 
+/// Upper bound on worker threads alive at once (including timed-out ones)
+pub const MAX_BACKGROUND_WORKERS: usize = 64;
+
+/// Name given to worker threads (visible in debuggers and `ps -T`)
+const WORKER_THREAD_NAME: &str = "ccs-timeout-worker";
+
+static GLOBAL_SLOTS: WorkerSlots = WorkerSlots::new(MAX_BACKGROUND_WORKERS);
+
+/// Counter of live workers with a fixed capacity
+struct WorkerSlots {
+    active: AtomicUsize,
+    cap: usize,
+}
+
+impl WorkerSlots {
+    const fn new(cap: usize) -> Self {
+        Self {
+            active: AtomicUsize::new(0),
+            cap,
+        }
+    }
+
+    fn try_acquire(&'static self) -> Option<SlotGuard> {
+        self.active
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                (n < self.cap).then_some(n + 1)
+            })
+            .ok()
+            .map(|_| SlotGuard(self))
+    }
+}
+
+/// Releases its slot when the worker finishes (or fails to start)
+struct SlotGuard(&'static WorkerSlots);
+
+impl Drop for SlotGuard {
+    fn drop(&mut self) {
+        self.0.active.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// Number of worker threads currently alive, including timed-out ones
+pub fn active_workers() -> usize {
+    GLOBAL_SLOTS.active.load(Ordering::Acquire)
+}
+
 /// Executes a function with a timeout constraint
 ///
 /// Spawns the function in a separate thread and waits for completion
@@ -48,22 +101,42 @@ use std::time::Duration;
 /// - Uses channels for thread communication
 /// - Catches panics and converts them to errors
 /// - Thread is detached after timeout (may continue running)
+/// - At most [`MAX_BACKGROUND_WORKERS`] workers live at once; beyond that
+///   `Err(CoreError::WorkerLimit)` is returned without running `f`
 pub fn run_with_timeout<F, T>(dur: Duration, f: F) -> Result<Option<T>, CoreError>
 where
     F: Send + 'static + FnOnce() -> Result<T, CoreError>,
     T: Send + 'static,
 {
+    run_with_slots(&GLOBAL_SLOTS, dur, f)
+}
+
+fn run_with_slots<F, T>(
+    slots: &'static WorkerSlots,
+    dur: Duration,
+    f: F,
+) -> Result<Option<T>, CoreError>
+where
+    F: Send + 'static + FnOnce() -> Result<T, CoreError>,
+    T: Send + 'static,
+{
+    let guard = slots
+        .try_acquire()
+        .ok_or(CoreError::WorkerLimit(slots.cap))?;
     let (tx, rx) = mpsc::channel();
 
-    thread::spawn(move || {
-        let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(f));
-        // Map panic into typed error; send result through channel if possible
-        let _ = match res {
-            Ok(Ok(val)) => tx.send(Ok(val)),
-            Ok(Err(err)) => tx.send(Err(err)),
-            Err(_) => tx.send(Err(CoreError::TaskPanic)),
-        };
-    });
+    thread::Builder::new()
+        .name(WORKER_THREAD_NAME.to_string())
+        .spawn(move || {
+            let _guard = guard;
+            let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(f));
+            // Map panic into typed error; send result through channel if possible
+            let _ = match res {
+                Ok(Ok(val)) => tx.send(Ok(val)),
+                Ok(Err(err)) => tx.send(Err(err)),
+                Err(_) => tx.send(Err(CoreError::TaskPanic)),
+            };
+        })?;
 
     match rx.recv_timeout(dur) {
         Ok(Ok(v)) => Ok(Some(v)),
@@ -97,6 +170,48 @@ mod tests {
         assert_eq!(out, None);
     }
 
+    #[test]
+    fn worker_threads_are_named() {
+        let name = run_with_timeout(Duration::from_millis(200), || {
+            Ok::<_, CoreError>(thread::current().name().map(str::to_string))
+        })
+        .unwrap()
+        .flatten();
+        assert_eq!(name.as_deref(), Some(WORKER_THREAD_NAME));
+    }
+
+    #[test]
+    fn repeated_timeouts_do_not_grow_threads_unbounded() {
+        // Private slots so this stress test can't starve other tests
+        static SLOTS: WorkerSlots = WorkerSlots::new(8);
+
+        let mut refused = 0;
+        for _ in 0..100 {
+            match run_with_slots(&SLOTS, Duration::from_millis(1), || {
+                std::thread::sleep(Duration::from_millis(100));
+                Ok::<_, CoreError>(())
+            }) {
+                Ok(None) => {}
+                Err(CoreError::WorkerLimit(8)) => refused += 1,
+                other => panic!("unexpected result: {other:?}"),
+            }
+            assert!(SLOTS.active.load(Ordering::Acquire) <= 8);
+        }
+        assert!(refused > 0, "cap should refuse work while workers linger");
+
+        // Lingering workers drain and free their slots
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while SLOTS.active.load(Ordering::Acquire) > 0 {
+            assert!(
+                std::time::Instant::now() < deadline,
+                "workers never drained"
+            );
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        let out = run_with_slots(&SLOTS, Duration::from_millis(200), || Ok::<_, CoreError>(1));
+        assert_eq!(out.unwrap(), Some(1));
+    }
+
     #[test]
     fn propagates_error() {
         let err = run_with_timeout(Duration::from_millis(100), || {
@@ -0,0 +1,138 @@
+//! Reading Claude Code session transcripts
+//!
+//! The input's `transcript_path` points at a JSONL file with one entry per
+//! line. Assistant entries carry the API `usage` block of their response,
+//! which is what token-based modules read.
+
+use serde::Deserialize;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Bytes read from the end of a transcript when looking for the latest usage
+///
+/// Transcripts grow to many megabytes; the latest response is near the end,
+/// so only this tail is read and parsed.
+pub const TAIL_BYTES: u64 = 256 * 1024;
+
+/// Token counts reported for one assistant response
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub struct TokenUsage {
+    #[serde(default)]
+    pub input_tokens: u64,
+    #[serde(default)]
+    pub cache_creation_input_tokens: u64,
+    #[serde(default)]
+    pub cache_read_input_tokens: u64,
+    #[serde(default)]
+    pub output_tokens: u64,
+}
+
+impl TokenUsage {
+    /// Tokens the request occupied in the context window (prompt side,
+    /// cached or not)
+    pub fn context_tokens(&self) -> u64 {
+        self.input_tokens + self.cache_creation_input_tokens + self.cache_read_input_tokens
+    }
+}
+
+#[derive(Deserialize)]
+struct Entry {
+    #[serde(default, rename = "isSidechain")]
+    is_sidechain: bool,
+    message: Option<Message>,
+}
+
+#[derive(Deserialize)]
+struct Message {
+    usage: Option<TokenUsage>,
+}
+
+/// Usage of the most recent main-thread response in the transcript at `path`
+///
+/// Only the last [`TAIL_BYTES`] of the file are read, scanning lines from
+/// the end. Sub-agent (sidechain) entries and lines that fail to decode or
+/// parse are skipped. Returns `None` when the file can't be read or its
+/// tail has no usage.
+pub fn last_usage(path: &Path) -> Option<TokenUsage> {
+    let mut file = std::fs::File::open(path).ok()?;
+    let start = file.metadata().ok()?.len().saturating_sub(TAIL_BYTES);
+    file.seek(SeekFrom::Start(start)).ok()?;
+    let mut tail = Vec::new();
+    file.read_to_end(&mut tail).ok()?;
+    // Starting mid-file: the first line is partial
+    let tail = if start > 0 {
+        let newline = tail.iter().position(|&b| b == b'\n')?;
+        &tail[newline + 1..]
+    } else {
+        &tail[..]
+    };
+    tail.rsplit(|&b| b == b'\n')
+        // Cheap pre-filter: most lines (user turns, tool output) have no usage
+        .filter(|line| line.windows(7).any(|w| w == b"\"usage\""))
+        .filter_map(|line| serde_json::from_slice::<Entry>(line).ok())
+        .filter(|entry| !entry.is_sidechain)
+        .find_map(|entry| entry.message?.usage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn last_main_thread_usage_wins() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("t.jsonl");
+        std::fs::write(
+            &path,
+            [
+                r#"{"type":"user","message":{"role":"user","content":"hi"}}"#,
+                r#"{"type":"assistant","message":{"usage":{"input_tokens":10,"output_tokens":5}}}"#,
+                "not json \"usage\"",
+                r#"{"type":"assistant","message":{"usage":{"input_tokens":3,"cache_creation_input_tokens":100,"cache_read_input_tokens":900,"output_tokens":7}}}"#,
+                r#"{"type":"assistant","isSidechain":true,"message":{"usage":{"input_tokens":99999}}}"#,
+                r#"{"type":"user","message":{"role":"user","content":"next"}}"#,
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+
+        let usage = last_usage(&path).unwrap();
+        assert_eq!(usage.context_tokens(), 1003);
+        assert_eq!(usage.output_tokens, 7);
+    }
+
+    #[test]
+    fn only_the_tail_is_read_and_bad_lines_are_skipped() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("t.jsonl");
+        let usage = |tokens: u64| {
+            format!(r#"{{"type":"assistant","message":{{"usage":{{"input_tokens":{tokens}}}}}}}"#)
+        };
+        let mut contents = usage(1).into_bytes();
+        // Pushes the first entry out of the tail window
+        contents.extend_from_slice(b"\n");
+        contents.extend_from_slice(&vec![b'x'; TAIL_BYTES as usize]);
+        contents.extend_from_slice(b"\n");
+        contents.extend_from_slice(usage(2).as_bytes());
+        // Undecodable and unparsable lines after the latest usage
+        contents.extend_from_slice(b"\n\xff\xfe \"usage\"\n{\"usage\": \n");
+        std::fs::write(&path, &contents).unwrap();
+        assert_eq!(last_usage(&path).unwrap().input_tokens, 2);
+
+        // The latest usage lies before the tail: nothing is found
+        let mut stale = usage(1).into_bytes();
+        stale.extend_from_slice(b"\n");
+        stale.extend_from_slice(&vec![b'x'; TAIL_BYTES as usize]);
+        std::fs::write(&path, &stale).unwrap();
+        assert_eq!(last_usage(&path), None);
+    }
+
+    #[test]
+    fn missing_or_usage_free_transcript_is_none() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert_eq!(last_usage(&tmp.path().join("missing.jsonl")), None);
+        let path = tmp.path().join("t.jsonl");
+        std::fs::write(&path, r#"{"type":"user","message":{"content":"hi"}}"#).unwrap();
+        assert_eq!(last_usage(&path), None);
+    }
+}
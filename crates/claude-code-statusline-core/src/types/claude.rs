@@ -29,21 +29,63 @@ use serde::{Deserialize, Serialize};
 ///     }
 /// }
 /// ```
+///
+/// Only `session_id`, `cwd` and `model` are required. The remaining fields
+/// are optional context: a value of an unexpected shape reads as `None`
+/// instead of failing the whole line, so schema drift on the Claude Code
+/// side degrades gracefully.
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct ClaudeInput {
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        deserialize_with = "lenient",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub hook_event_name: Option<String>,
     pub session_id: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        deserialize_with = "lenient",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub transcript_path: Option<String>,
     pub cwd: String,
     pub model: ModelInfo,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        deserialize_with = "lenient",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub workspace: Option<WorkspaceInfo>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        deserialize_with = "lenient",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub version: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        deserialize_with = "lenient",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub output_style: Option<OutputStyle>,
+    /// Exit status of the last command, when the caller provides one
+    #[serde(
+        default,
+        deserialize_with = "lenient",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub exit_status: Option<i32>,
+}
+
+/// Deserialize an optional field, treating a value of the wrong shape as absent
+fn lenient<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: serde::de::DeserializeOwned,
+{
+    let value = serde_json::Value::deserialize(deserializer)?;
+    Ok(serde_json::from_value(value).ok())
 }
 
 /// Information about the current Claude model
@@ -78,3 +120,74 @@ pub struct OutputStyle {
     /// Name of the output style
     pub name: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    const BASE: &str =
+        r#""session_id":"s","cwd":"/tmp","model":{"id":"claude-opus","display_name":"Opus"}"#;
+
+    #[rstest]
+    #[case::output_style_string(r#""output_style":"default""#)]
+    #[case::output_style_array(r#""output_style":[{"name":"x"}]"#)]
+    #[case::output_style_missing_name(r#""output_style":{"label":"x"}"#)]
+    #[case::version_number(r#""version":1.5"#)]
+    #[case::workspace_string(r#""workspace":"/tmp""#)]
+    #[case::exit_status_string(r#""exit_status":"1""#)]
+    #[case::transcript_object(r#""transcript_path":{"path":"/t"}"#)]
+    fn unexpected_shapes_of_optional_fields_read_as_none(#[case] field: &str) {
+        let json = format!("{{{BASE},{field},\"new_field\":{{\"a\":1}}}}");
+        let input: ClaudeInput = serde_json::from_str(&json).unwrap();
+        assert_eq!(input.cwd, "/tmp");
+        assert_eq!(input.model.display_name, "Opus");
+        assert!(input.output_style.is_none());
+        assert!(input.version.is_none());
+        assert!(input.workspace.is_none());
+        assert!(input.exit_status.is_none());
+        assert!(input.transcript_path.is_none());
+    }
+
+    #[test]
+    fn well_formed_optional_fields_still_parse() {
+        let json = format!(
+            r#"{{{BASE},"output_style":{{"name":"Explanatory"}},"version":"1.0.80","exit_status":2}}"#
+        );
+        let input: ClaudeInput = serde_json::from_str(&json).unwrap();
+        assert_eq!(input.output_style.unwrap().name, "Explanatory");
+        assert_eq!(input.version.as_deref(), Some("1.0.80"));
+        assert_eq!(input.exit_status, Some(2));
+    }
+
+    #[test]
+    fn sample_input_round_trips_through_serialization() {
+        let json = r#"{
+            "hook_event_name": "Status",
+            "session_id": "abc123",
+            "transcript_path": "/tmp/t.jsonl",
+            "cwd": "/home/user/project",
+            "model": {"id": "claude-opus-4-1", "display_name": "Opus 4.1"},
+            "workspace": {"current_dir": "/home/user/project/src", "project_dir": "/home/user/project"},
+            "version": "1.0.80",
+            "output_style": {"name": "default"},
+            "exit_status": 1
+        }"#;
+        let input: ClaudeInput = serde_json::from_str(json).unwrap();
+        let expected: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(serde_json::to_value(&input).unwrap(), expected);
+
+        // Absent optional fields stay absent instead of becoming `null`
+        let minimal = format!("{{{BASE}}}");
+        let input: ClaudeInput = serde_json::from_str(&minimal).unwrap();
+        assert_eq!(serde_json::to_string(&input).unwrap(), minimal);
+    }
+
+    #[rstest]
+    #[case::model_missing(r#"{"session_id":"s","cwd":"/tmp"}"#)]
+    #[case::model_wrong_shape(r#"{"session_id":"s","cwd":"/tmp","model":"opus"}"#)]
+    #[case::cwd_wrong_shape(r#"{"session_id":"s","cwd":1,"model":{"id":"a","display_name":"A"}}"#)]
+    fn required_fields_still_fail(#[case] json: &str) {
+        assert!(serde_json::from_str::<ClaudeInput>(json).is_err());
+    }
+}
@@ -10,10 +10,11 @@
 //! uses TOML format. All fields are optional and will use defaults
 //! if not specified.
 
-use crate::error::CoreError;
+use crate::error::{CoreError, ErrorCategory};
 use crate::modules::ModuleConfig;
 use serde::{Deserialize, Serialize};
 use std::any::Any;
+use std::collections::HashMap;
 
 /// Main configuration structure
 ///
@@ -32,6 +33,7 @@ use std::any::Any;
 /// truncation_length = 5
 /// ```
 #[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Config {
     #[serde(default = "default_format")]
     pub format: String,
@@ -42,6 +44,50 @@ pub struct Config {
     #[serde(default = "default_debug")]
     pub debug: bool,
 
+    /// Maximum stdin size in bytes; larger input gets the invalid-JSON fallback
+    #[serde(default = "default_max_input_bytes")]
+    pub max_input_bytes: usize,
+
+    /// End the printed status line (and fallback lines) with `\n`
+    #[serde(default)]
+    pub trailing_newline: bool,
+
+    /// Shown instead of a line with no visible text (e.g. every module hidden);
+    /// accepts `[text](style)` groups. Empty keeps the blank line
+    #[serde(default)]
+    pub empty_placeholder: String,
+
+    /// Built-in preset merged under this config (`default`, `minimal`, `powerline`)
+    #[serde(default)]
+    pub preset: Option<String>,
+
+    /// Merge the nearest `.claude-statusline.toml` (from the current directory
+    /// up to the repository root) over this config; only read from the user config.
+    /// Project files can only set presentation keys (no `when` or timeouts)
+    #[serde(default)]
+    pub project_config: bool,
+
+    /// Color depth for RGB colors: `"truecolor"`, `"256"` or `"16"`
+    /// (auto-detected from `COLORTERM`/`TERM` when unset)
+    #[serde(default)]
+    pub color_depth: Option<crate::style::ColorDepth>,
+
+    /// Glyph set for default module symbols: `"unicode"`, `"nerdfont"` or `"ascii"`
+    #[serde(default)]
+    pub symbol_mode: crate::symbols::SymbolMode,
+
+    /// How git modules read the repository: `"auto"`, `"libgit2"` or `"cli"`
+    #[serde(default)]
+    pub git_backend: GitBackend,
+
+    /// Resolve top-level `$style` to an accent chosen by model tier
+    #[serde(default)]
+    pub accent_from_model: bool,
+
+    /// Accent styles keyed by a substring of the model id (e.g., `opus`)
+    #[serde(default = "default_accents", serialize_with = "serialize_sorted")]
+    pub accents: HashMap<String, String>,
+
     #[serde(default)]
     pub directory: DirectoryConfig,
 
@@ -54,17 +100,125 @@ pub struct Config {
     #[serde(default)]
     pub git_status: GitStatusConfig,
 
+    #[serde(default)]
+    pub cc_version: CcVersionConfig,
+
+    #[serde(default)]
+    pub session: SessionConfig,
+
+    #[serde(default)]
+    pub localip: LocalIpConfig,
+
+    #[serde(default)]
+    pub status: StatusConfig,
+
+    #[serde(default)]
+    pub context_usage: ContextUsageConfig,
+
+    /// Overrides for the fallback lines printed when rendering fails
+    #[serde(default)]
+    pub messages: MessagesConfig,
+
+    /// Module variants referenced as `$module#variant`, keyed `module#variant`
+    ///
+    /// Holds the override tables of sections like `[directory.short]`; filled
+    /// in by [`Config::from_toml_str`].
+    #[serde(skip)]
+    pub variants: HashMap<String, toml::Table>,
+
     /// Unrecognized/extra top-level tables (e.g., third-party modules)
     /// Captures unknown sections like `[my_custom_module]` without losing them.
     #[serde(flatten)]
     #[serde(default)]
+    #[cfg_attr(
+        feature = "schema",
+        schemars(with = "std::collections::BTreeMap<String, serde_json::Value>")
+    )]
     pub extra_modules: toml::value::Table,
 }
 
+/// Backend used by the git modules to read the repository
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum GitBackend {
+    /// libgit2, falling back to the `git` command where a module supports it
+    #[default]
+    Auto,
+    /// libgit2 only, never spawning `git`
+    Libgit2,
+    /// The `git` command only, for modules that support it
+    Cli,
+}
+
+impl GitBackend {
+    /// Whether the repository is read through libgit2
+    pub fn uses_libgit2(self) -> bool {
+        self != GitBackend::Cli
+    }
+
+    /// Whether the `git` command may be spawned
+    pub fn uses_cli(self) -> bool {
+        self != GitBackend::Libgit2
+    }
+}
+
+/// Custom fallback messages
+///
+/// Each field overrides the matching constant in [`crate::messages`]. They
+/// apply only once the config has loaded, so a config that fails to load
+/// always falls back to the built-in message.
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct MessagesConfig {
+    /// Shown when stdin is empty
+    #[serde(default)]
+    pub fallback_empty: Option<String>,
+    /// Shown when stdin is not valid Claude Code JSON
+    #[serde(default)]
+    pub fallback_invalid_json: Option<String>,
+    /// Shown when the config loads but fails validation
+    #[serde(default)]
+    pub fallback_invalid_config: Option<String>,
+}
+
+impl MessagesConfig {
+    /// Fallback for empty input
+    pub fn empty_input(&self) -> &str {
+        self.fallback_empty
+            .as_deref()
+            .unwrap_or(crate::messages::MSG_FAILED_EMPTY_INPUT)
+    }
+
+    /// Fallback for unparsable input
+    pub fn invalid_json(&self) -> &str {
+        self.fallback_invalid_json
+            .as_deref()
+            .unwrap_or(crate::messages::MSG_FAILED_INVALID_JSON)
+    }
+
+    /// Fallback for a config that fails validation
+    pub fn invalid_config(&self) -> &str {
+        self.fallback_invalid_config
+            .as_deref()
+            .unwrap_or(crate::messages::MSG_FAILED_INVALID_CONFIG)
+    }
+
+    /// Fallback for `err`: [`CoreError::user_message`] with these overrides
+    pub fn for_error(&self, err: &CoreError) -> &str {
+        match err.category() {
+            ErrorCategory::InvalidInput => self.invalid_json(),
+            ErrorCategory::InvalidConfig => self.invalid_config(),
+            ErrorCategory::Render => err.user_message(),
+        }
+    }
+}
+
 /// Configuration for the directory module
 ///
 /// Controls how the current directory is displayed in the status line.
 #[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct DirectoryConfig {
     #[serde(default = "default_directory_format")]
     pub format: String,
@@ -78,10 +232,73 @@ pub struct DirectoryConfig {
     #[serde(default = "default_directory_truncate_to_repo")]
     pub truncate_to_repo: bool,
 
+    /// Outside repositories, also keep only the last `truncation_length`
+    /// components (the full home-abbreviated path is shown otherwise)
+    #[serde(default)]
+    pub truncate_outside_repo: bool,
+
     /// Symbol to indicate truncated paths (e.g., "…/")
     #[serde(default = "default_directory_truncation_symbol")]
     pub truncation_symbol: String,
 
+    /// Resolve symlinks in the working directory before git discovery and truncation
+    #[serde(default)]
+    pub resolve_symlinks: bool,
+
+    /// In a linked git worktree, show the main repository's name instead of the worktree's
+    #[serde(default)]
+    pub worktree_show_main: bool,
+
+    /// Label the repository with the `name` from its root `Cargo.toml`,
+    /// `package.json` or `pyproject.toml` instead of the directory name
+    #[serde(default)]
+    pub use_project_name: bool,
+
+    /// Path prefix → label replacements (longest prefix wins), e.g.
+    /// `"~/work" = "💼"`; in a repository only a match of the repo root applies
+    #[serde(default)]
+    pub substitutions: HashMap<String, String>,
+
+    /// At the repository root, prefix the repo name with its parent directory
+    #[serde(default)]
+    pub root_shows_parent: bool,
+
+    /// Outside repositories, show the shell's logical path (`PWD`, keeping
+    /// symlink names) when it resolves to the working directory
+    #[serde(default)]
+    pub use_logical_path: bool,
+
+    /// Abbreviate every component but the last to this many characters
+    /// (fish `prompt_pwd` style) instead of truncating; `0` disables it
+    #[serde(default)]
+    pub fish_style_pwd_dir_length: usize,
+
+    /// Patterns (regular expressions; invalid ones match literally) whose
+    /// matches in the displayed path are replaced by `redact_mask`
+    #[serde(default)]
+    pub redact: Vec<String>,
+
+    /// Replacement for `redact` matches
+    #[serde(default = "default_directory_redact_mask")]
+    pub redact_mask: String,
+
+    /// Per-module timeout override in milliseconds (falls back to `command_timeout`)
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+
+    /// Placeholder shown when the module times out (empty hides the module)
+    #[serde(default)]
+    pub timeout_symbol: String,
+
+    /// Display predicate: `env:VAR` (shown while `VAR` is set) or a shell
+    /// command (shown when it exits 0)
+    #[serde(default)]
+    pub when: Option<String>,
+
+    /// Post-processing of the rendered output: `upper`, `lower`, `trim` or `capitalize`
+    #[serde(default)]
+    pub transform: Option<crate::style::Transform>,
+
     #[serde(default = "default_disabled")]
     pub disabled: bool,
 }
@@ -90,6 +307,7 @@ pub struct DirectoryConfig {
 ///
 /// Controls how the Claude model information is displayed.
 #[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ClaudeModelConfig {
     #[serde(default = "default_claude_model_format")]
     pub format: String,
@@ -100,6 +318,43 @@ pub struct ClaudeModelConfig {
     #[serde(default = "default_claude_model_symbol")]
     pub symbol: String,
 
+    /// Short labels keyed by model id or display name
+    /// (e.g., `"claude-3-5-sonnet" = "S3.5"`)
+    #[serde(default, serialize_with = "serialize_sorted")]
+    pub aliases: HashMap<String, String>,
+
+    /// Per-family symbols keyed by a substring of the model id
+    /// (e.g., `opus = "◆"`); falls back to `symbol`
+    #[serde(default, serialize_with = "serialize_sorted")]
+    pub symbols: HashMap<String, String>,
+
+    /// Suffixes removed from the end of the display name before compaction
+    /// (e.g., `" (New)"`, `"-20241022"`)
+    #[serde(default)]
+    pub strip_suffixes: Vec<String>,
+
+    /// Remove parenthesized parts like `(New)` from the display name and
+    /// rewrite legacy `Claude 3.5 Sonnet` names as `Sonnet 3.5`
+    #[serde(default)]
+    pub strip_parenthetical: bool,
+
+    /// Per-module timeout override in milliseconds (falls back to `command_timeout`)
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+
+    /// Placeholder shown when the module times out (empty hides the module)
+    #[serde(default)]
+    pub timeout_symbol: String,
+
+    /// Display predicate: `env:VAR` (shown while `VAR` is set) or a shell
+    /// command (shown when it exits 0)
+    #[serde(default)]
+    pub when: Option<String>,
+
+    /// Post-processing of the rendered output: `upper`, `lower`, `trim` or `capitalize`
+    #[serde(default)]
+    pub transform: Option<crate::style::Transform>,
+
     #[serde(default = "default_disabled")]
     pub disabled: bool,
 }
@@ -109,11 +364,28 @@ impl Default for Config {
         Config {
             format: default_format(),
             command_timeout: default_command_timeout(),
+            max_input_bytes: default_max_input_bytes(),
+            trailing_newline: false,
+            empty_placeholder: String::new(),
             debug: default_debug(),
+            preset: None,
+            project_config: false,
+            color_depth: None,
+            symbol_mode: Default::default(),
+            git_backend: GitBackend::default(),
+            accent_from_model: false,
+            accents: default_accents(),
             directory: DirectoryConfig::default(),
             claude_model: ClaudeModelConfig::default(),
             git_branch: GitBranchConfig::default(),
             git_status: GitStatusConfig::default(),
+            cc_version: CcVersionConfig::default(),
+            session: SessionConfig::default(),
+            localip: LocalIpConfig::default(),
+            status: StatusConfig::default(),
+            context_usage: ContextUsageConfig::default(),
+            messages: MessagesConfig::default(),
+            variants: HashMap::new(),
             extra_modules: toml::value::Table::new(),
         }
     }
@@ -126,7 +398,21 @@ impl Default for DirectoryConfig {
             style: default_directory_style(),
             truncation_length: default_directory_truncation_length(),
             truncate_to_repo: default_directory_truncate_to_repo(),
+            truncate_outside_repo: false,
             truncation_symbol: default_directory_truncation_symbol(),
+            resolve_symlinks: false,
+            worktree_show_main: false,
+            use_project_name: false,
+            substitutions: HashMap::new(),
+            root_shows_parent: false,
+            use_logical_path: false,
+            fish_style_pwd_dir_length: 0,
+            redact: Vec::new(),
+            redact_mask: default_directory_redact_mask(),
+            timeout_ms: None,
+            timeout_symbol: String::new(),
+            when: None,
+            transform: None,
             disabled: default_disabled(),
         }
     }
@@ -138,12 +424,21 @@ impl Default for ClaudeModelConfig {
             format: default_claude_model_format(),
             style: default_claude_model_style(),
             symbol: default_claude_model_symbol(),
+            aliases: HashMap::new(),
+            symbols: HashMap::new(),
+            strip_suffixes: Vec::new(),
+            strip_parenthetical: false,
+            timeout_ms: None,
+            timeout_symbol: String::new(),
+            when: None,
+            transform: None,
             disabled: default_disabled(),
         }
     }
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct GitBranchConfig {
     #[serde(default = "default_git_branch_format")]
     pub format: String,
@@ -154,6 +449,50 @@ pub struct GitBranchConfig {
     #[serde(default = "default_git_branch_symbol")]
     pub symbol: String,
 
+    /// `[pattern, symbol]` pairs tried in order against the branch name;
+    /// the first match replaces `symbol`. `*` in a pattern matches any run
+    /// of characters (e.g. `["feature/*", "🌱"]`)
+    #[serde(default)]
+    pub symbols: Vec<(String, String)>,
+
+    /// Maximum branch name length in characters (0 disables truncation)
+    #[serde(default)]
+    pub truncation_length: usize,
+
+    /// Appended to truncated branch names
+    #[serde(default = "default_git_branch_truncation_symbol")]
+    pub truncation_symbol: String,
+
+    /// Prefixes removed from the branch name (first match wins)
+    #[serde(default)]
+    pub strip_prefixes: Vec<String>,
+
+    /// On a detached HEAD, show a tag pointing at the commit instead of the SHA
+    #[serde(default)]
+    pub prefer_tag: bool,
+
+    /// Fill `$ahead_behind` with the commits ahead of/behind the upstream,
+    /// using the `git_status` symbols
+    #[serde(default)]
+    pub show_ahead_behind: bool,
+
+    /// Per-module timeout override in milliseconds (falls back to `command_timeout`)
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+
+    /// Placeholder shown when the module times out (empty hides the module)
+    #[serde(default)]
+    pub timeout_symbol: String,
+
+    /// Display predicate: `env:VAR` (shown while `VAR` is set) or a shell
+    /// command (shown when it exits 0)
+    #[serde(default)]
+    pub when: Option<String>,
+
+    /// Post-processing of the rendered output: `upper`, `lower`, `trim` or `capitalize`
+    #[serde(default)]
+    pub transform: Option<crate::style::Transform>,
+
     #[serde(default = "default_disabled")]
     pub disabled: bool,
 }
@@ -164,12 +503,23 @@ impl Default for GitBranchConfig {
             format: default_git_branch_format(),
             style: default_git_branch_style(),
             symbol: default_git_branch_symbol(),
+            symbols: Vec::new(),
+            truncation_length: 0,
+            truncation_symbol: default_git_branch_truncation_symbol(),
+            strip_prefixes: Vec::new(),
+            prefer_tag: false,
+            show_ahead_behind: false,
+            timeout_ms: None,
+            timeout_symbol: String::new(),
+            when: None,
+            transform: None,
             disabled: default_disabled(),
         }
     }
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct GitStatusSymbolsConfig {
     #[serde(default = "default_git_status_symbol_conflicted")]
     pub conflicted: String,
@@ -193,6 +543,15 @@ pub struct GitStatusSymbolsConfig {
     pub behind: String,
     #[serde(default = "default_git_status_symbol_diverged")]
     pub diverged: String,
+    /// Ignored files, counted only with `show_ignored = true`
+    #[serde(default = "default_git_status_symbol_ignored")]
+    pub ignored: String,
+    /// Shown via `$clean` when there is nothing to report (empty hides the module)
+    #[serde(default)]
+    pub clean: String,
+    /// Prefix of the `$changed` total
+    #[serde(default = "default_git_status_symbol_changed")]
+    pub changed: String,
 }
 
 impl Default for GitStatusSymbolsConfig {
@@ -209,11 +568,15 @@ impl Default for GitStatusSymbolsConfig {
             ahead: default_git_status_symbol_ahead(),
             behind: default_git_status_symbol_behind(),
             diverged: default_git_status_symbol_diverged(),
+            ignored: default_git_status_symbol_ignored(),
+            clean: String::new(),
+            changed: default_git_status_symbol_changed(),
         }
     }
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct GitStatusConfig {
     #[serde(default = "default_git_status_format")]
     pub format: String,
@@ -224,6 +587,44 @@ pub struct GitStatusConfig {
     #[serde(default)]
     pub symbols: GitStatusSymbolsConfig,
 
+    /// Ref used for ahead/behind when the branch has no upstream (e.g., `origin/main`)
+    #[serde(default)]
+    pub compare_to: Option<String>,
+
+    /// Detect staged renames so moved files count as renamed, not deleted + added
+    #[serde(default = "default_detect_renames")]
+    pub detect_renames: bool,
+
+    /// Count ignored files for `$ignored` (slow in repositories with large
+    /// ignored trees such as build output)
+    #[serde(default)]
+    pub show_ignored: bool,
+
+    /// Show a single `$changed` total (e.g. `±3`) in `$all_status` instead of
+    /// per-category symbols
+    ///
+    /// The total counts changed files, not category hits: a file that is
+    /// both staged and modified (or a staged rename) counts once.
+    #[serde(default)]
+    pub summary: bool,
+
+    /// Per-module timeout override in milliseconds (falls back to `command_timeout`)
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+
+    /// Placeholder shown when the module times out (empty hides the module)
+    #[serde(default)]
+    pub timeout_symbol: String,
+
+    /// Display predicate: `env:VAR` (shown while `VAR` is set) or a shell
+    /// command (shown when it exits 0)
+    #[serde(default)]
+    pub when: Option<String>,
+
+    /// Post-processing of the rendered output: `upper`, `lower`, `trim` or `capitalize`
+    #[serde(default)]
+    pub transform: Option<crate::style::Transform>,
+
     #[serde(default = "default_disabled")]
     pub disabled: bool,
 }
@@ -234,83 +635,481 @@ impl Default for GitStatusConfig {
             format: default_git_status_format(),
             style: default_git_status_style(),
             symbols: GitStatusSymbolsConfig::default(),
+            compare_to: None,
+            detect_renames: default_detect_renames(),
+            show_ignored: false,
+            summary: false,
+            timeout_ms: None,
+            timeout_symbol: String::new(),
+            when: None,
+            transform: None,
             disabled: default_disabled(),
         }
     }
 }
 
-// Default value functions
-fn default_format() -> String {
-    "$directory $claude_model".to_string()
-}
+/// Configuration for the cc_version module
+///
+/// Controls how the Claude Code client version is displayed.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct CcVersionConfig {
+    #[serde(default = "default_cc_version_format")]
+    pub format: String,
 
-fn default_command_timeout() -> u64 {
-    500
-}
+    #[serde(default = "default_cc_version_style")]
+    pub style: String,
 
-fn default_debug() -> bool {
-    false
-}
+    #[serde(default = "default_cc_version_symbol")]
+    pub symbol: String,
 
-fn default_disabled() -> bool {
-    false
-}
+    /// Per-module timeout override in milliseconds (falls back to `command_timeout`)
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
 
-// Directory module defaults
-fn default_directory_format() -> String {
-    "[$path]($style)".to_string()
-}
+    /// Placeholder shown when the module times out (empty hides the module)
+    #[serde(default)]
+    pub timeout_symbol: String,
 
-fn default_directory_style() -> String {
-    "bold cyan".to_string()
-}
+    /// Display predicate: `env:VAR` (shown while `VAR` is set) or a shell
+    /// command (shown when it exits 0)
+    #[serde(default)]
+    pub when: Option<String>,
 
-fn default_directory_truncation_length() -> usize {
-    3
-}
+    /// Post-processing of the rendered output: `upper`, `lower`, `trim` or `capitalize`
+    #[serde(default)]
+    pub transform: Option<crate::style::Transform>,
 
-fn default_directory_truncate_to_repo() -> bool {
-    true
+    #[serde(default = "default_disabled")]
+    pub disabled: bool,
 }
 
-fn default_directory_truncation_symbol() -> String {
-    "".to_string()
+impl Default for CcVersionConfig {
+    fn default() -> Self {
+        CcVersionConfig {
+            format: default_cc_version_format(),
+            style: default_cc_version_style(),
+            symbol: default_cc_version_symbol(),
+            timeout_ms: None,
+            timeout_symbol: String::new(),
+            when: None,
+            transform: None,
+            disabled: default_disabled(),
+        }
+    }
 }
 
-// Claude Model module defaults
-fn default_claude_model_format() -> String {
-    "[$symbol$model]($style)".to_string()
-}
+/// Configuration for the session module
+///
+/// Controls how the (truncated) session id is displayed.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SessionConfig {
+    #[serde(default = "default_session_format")]
+    pub format: String,
 
-fn default_claude_model_style() -> String {
-    "bold yellow".to_string()
-}
+    #[serde(default = "default_session_style")]
+    pub style: String,
 
-fn default_claude_model_symbol() -> String {
-    "".to_string()
+    /// Number of leading characters to keep (0 shows the full id)
+    #[serde(default = "default_session_length")]
+    pub length: usize,
+
+    /// Per-module timeout override in milliseconds (falls back to `command_timeout`)
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+
+    /// Placeholder shown when the module times out (empty hides the module)
+    #[serde(default)]
+    pub timeout_symbol: String,
+
+    /// Display predicate: `env:VAR` (shown while `VAR` is set) or a shell
+    /// command (shown when it exits 0)
+    #[serde(default)]
+    pub when: Option<String>,
+
+    /// Post-processing of the rendered output: `upper`, `lower`, `trim` or `capitalize`
+    #[serde(default)]
+    pub transform: Option<crate::style::Transform>,
+
+    #[serde(default = "default_disabled")]
+    pub disabled: bool,
 }
 
-// Git Branch module defaults
-fn default_git_branch_format() -> String {
-    "[$symbol $branch]($style)".to_string()
+impl Default for SessionConfig {
+    fn default() -> Self {
+        SessionConfig {
+            format: default_session_format(),
+            style: default_session_style(),
+            length: default_session_length(),
+            timeout_ms: None,
+            timeout_symbol: String::new(),
+            when: None,
+            transform: None,
+            disabled: default_disabled(),
+        }
+    }
 }
 
-fn default_git_branch_style() -> String {
-    "bold green".to_string()
+/// Configuration for the localip module
+///
+/// Controls when and how the primary LAN address is displayed.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct LocalIpConfig {
+    #[serde(default = "default_localip_format")]
+    pub format: String,
+
+    #[serde(default = "default_localip_style")]
+    pub style: String,
+
+    /// Only display inside SSH sessions
+    #[serde(default = "default_localip_ssh_only")]
+    pub ssh_only: bool,
+
+    /// Per-module timeout override in milliseconds (falls back to `command_timeout`)
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+
+    /// Placeholder shown when the module times out (empty hides the module)
+    #[serde(default)]
+    pub timeout_symbol: String,
+
+    /// Display predicate: `env:VAR` (shown while `VAR` is set) or a shell
+    /// command (shown when it exits 0)
+    #[serde(default)]
+    pub when: Option<String>,
+
+    /// Post-processing of the rendered output: `upper`, `lower`, `trim` or `capitalize`
+    #[serde(default)]
+    pub transform: Option<crate::style::Transform>,
+
+    #[serde(default = "default_disabled")]
+    pub disabled: bool,
 }
 
-fn default_git_branch_symbol() -> String {
-    "🌿".to_string()
+impl Default for LocalIpConfig {
+    fn default() -> Self {
+        LocalIpConfig {
+            format: default_localip_format(),
+            style: default_localip_style(),
+            ssh_only: default_localip_ssh_only(),
+            timeout_ms: None,
+            timeout_symbol: String::new(),
+            when: None,
+            transform: None,
+            disabled: default_disabled(),
+        }
+    }
 }
 
-// Git Status module defaults (Starship 準拠の最小形)
-fn default_git_status_format() -> String {
-    // ([[$all_status$ahead_behind]]($style) )
-    "([[$all_status$ahead_behind]]($style) )".to_string()
+/// Configuration for the status module
+///
+/// Controls how a nonzero `exit_status` from the input is displayed.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct StatusConfig {
+    #[serde(default = "default_status_format")]
+    pub format: String,
+
+    #[serde(default = "default_status_style")]
+    pub style: String,
+
+    #[serde(default = "default_status_symbol")]
+    pub symbol: String,
+
+    /// Per-module timeout override in milliseconds (falls back to `command_timeout`)
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+
+    /// Placeholder shown when the module times out (empty hides the module)
+    #[serde(default)]
+    pub timeout_symbol: String,
+
+    /// Display predicate: `env:VAR` (shown while `VAR` is set) or a shell
+    /// command (shown when it exits 0)
+    #[serde(default)]
+    pub when: Option<String>,
+
+    /// Post-processing of the rendered output: `upper`, `lower`, `trim` or `capitalize`
+    #[serde(default)]
+    pub transform: Option<crate::style::Transform>,
+
+    #[serde(default = "default_disabled")]
+    pub disabled: bool,
 }
 
-fn default_git_status_style() -> String {
-    "bold red".to_string()
+impl Default for StatusConfig {
+    fn default() -> Self {
+        StatusConfig {
+            format: default_status_format(),
+            style: default_status_style(),
+            symbol: default_status_symbol(),
+            timeout_ms: None,
+            timeout_symbol: String::new(),
+            when: None,
+            transform: None,
+            disabled: default_disabled(),
+        }
+    }
+}
+
+/// Configuration for the context_usage module
+///
+/// Controls the gauge of how much of the model's context window the
+/// session's latest request used, read from the transcript.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ContextUsageConfig {
+    #[serde(default = "default_context_usage_format")]
+    pub format: String,
+
+    #[serde(default = "default_context_usage_style")]
+    pub style: String,
+
+    /// Context window size in tokens for models without a `max_tokens` entry
+    #[serde(default = "default_context_usage_max_tokens")]
+    pub default_max_tokens: u64,
+
+    /// Context window size keyed by a substring of the model id (longest match wins)
+    #[serde(default, serialize_with = "serialize_sorted")]
+    pub max_tokens: HashMap<String, u64>,
+
+    /// Number of cells in `$bar`
+    #[serde(default = "default_context_usage_bar_width")]
+    pub bar_width: usize,
+
+    /// Cell for the used part of `$bar`
+    #[serde(default = "default_context_usage_bar_filled")]
+    pub bar_filled: String,
+
+    /// Cell for the remaining part of `$bar`
+    #[serde(default = "default_context_usage_bar_empty")]
+    pub bar_empty: String,
+
+    /// Per-module timeout override in milliseconds (falls back to `command_timeout`)
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+
+    /// Placeholder shown when the module times out (empty hides the module)
+    #[serde(default)]
+    pub timeout_symbol: String,
+
+    /// Display predicate: `env:VAR` (shown while `VAR` is set) or a shell
+    /// command (shown when it exits 0)
+    #[serde(default)]
+    pub when: Option<String>,
+
+    /// Post-processing of the rendered output: `upper`, `lower`, `trim` or `capitalize`
+    #[serde(default)]
+    pub transform: Option<crate::style::Transform>,
+
+    #[serde(default = "default_disabled")]
+    pub disabled: bool,
+}
+
+impl Default for ContextUsageConfig {
+    fn default() -> Self {
+        ContextUsageConfig {
+            format: default_context_usage_format(),
+            style: default_context_usage_style(),
+            default_max_tokens: default_context_usage_max_tokens(),
+            max_tokens: HashMap::new(),
+            bar_width: default_context_usage_bar_width(),
+            bar_filled: default_context_usage_bar_filled(),
+            bar_empty: default_context_usage_bar_empty(),
+            timeout_ms: None,
+            timeout_symbol: String::new(),
+            when: None,
+            transform: None,
+            disabled: default_disabled(),
+        }
+    }
+}
+
+// Default value functions
+fn default_format() -> String {
+    "$directory $claude_model".to_string()
+}
+
+fn default_command_timeout() -> u64 {
+    500
+}
+
+fn default_max_input_bytes() -> usize {
+    crate::parser::DEFAULT_MAX_INPUT_BYTES
+}
+
+fn default_debug() -> bool {
+    false
+}
+
+fn default_accents() -> HashMap<String, String> {
+    [("opus", "magenta"), ("sonnet", "blue"), ("haiku", "green")]
+        .into_iter()
+        .map(|(tier, style)| (tier.to_string(), style.to_string()))
+        .collect()
+}
+
+/// Serialize a map with sorted keys so `config --default` and round trips are stable
+fn serialize_sorted<S, V>(map: &HashMap<String, V>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+    V: Serialize,
+{
+    map.iter()
+        .collect::<std::collections::BTreeMap<_, _>>()
+        .serialize(serializer)
+}
+
+fn default_disabled() -> bool {
+    false
+}
+
+// Directory module defaults
+fn default_directory_format() -> String {
+    "[$path]($style)".to_string()
+}
+
+fn default_directory_style() -> String {
+    "bold cyan".to_string()
+}
+
+fn default_directory_truncation_length() -> usize {
+    3
+}
+
+fn default_directory_truncate_to_repo() -> bool {
+    true
+}
+
+fn default_directory_truncation_symbol() -> String {
+    "".to_string()
+}
+
+fn default_directory_redact_mask() -> String {
+    "***".to_string()
+}
+
+// Claude Model module defaults
+fn default_claude_model_format() -> String {
+    "[$symbol$model]($style)".to_string()
+}
+
+fn default_claude_model_style() -> String {
+    "bold yellow".to_string()
+}
+
+fn default_claude_model_symbol() -> String {
+    "".to_string()
+}
+
+// Git Branch module defaults
+fn default_git_branch_format() -> String {
+    "[$symbol $branch]($style)".to_string()
+}
+
+fn default_git_branch_style() -> String {
+    "bold green".to_string()
+}
+
+fn default_git_branch_symbol() -> String {
+    "🌿".to_string()
+}
+
+fn default_git_branch_truncation_symbol() -> String {
+    "…".to_string()
+}
+
+// Claude Code version module defaults
+fn default_cc_version_format() -> String {
+    "[$symbol$version]($style)".to_string()
+}
+
+fn default_cc_version_style() -> String {
+    "bold white".to_string()
+}
+
+fn default_cc_version_symbol() -> String {
+    "v".to_string()
+}
+
+// Session module defaults
+fn default_session_format() -> String {
+    "[$session_id]($style)".to_string()
+}
+
+fn default_session_style() -> String {
+    "italic yellow".to_string()
+}
+
+fn default_session_length() -> usize {
+    6
+}
+
+// Local IP module defaults
+fn default_localip_format() -> String {
+    "[$localipv4]($style)".to_string()
+}
+
+fn default_localip_style() -> String {
+    "bold yellow".to_string()
+}
+
+fn default_localip_ssh_only() -> bool {
+    true
+}
+
+// Status module defaults
+fn default_status_format() -> String {
+    "[$symbol$status]($style)".to_string()
+}
+
+fn default_status_style() -> String {
+    "bold red".to_string()
+}
+
+fn default_status_symbol() -> String {
+    "✘ ".to_string()
+}
+
+// Context usage module defaults
+fn default_context_usage_format() -> String {
+    "[$bar $percentage%]($style)".to_string()
+}
+
+fn default_context_usage_style() -> String {
+    "bold blue".to_string()
+}
+
+fn default_context_usage_max_tokens() -> u64 {
+    200_000
+}
+
+fn default_context_usage_bar_width() -> usize {
+    10
+}
+
+fn default_context_usage_bar_filled() -> String {
+    "█".to_string()
+}
+
+fn default_context_usage_bar_empty() -> String {
+    "░".to_string()
+}
+
+// Git Status module defaults (Starship 準拠の最小形)
+fn default_git_status_format() -> String {
+    // ([[$all_status$ahead_behind$clean]]($style) )
+    "([[$all_status$ahead_behind$clean]]($style) )".to_string()
+}
+
+fn default_git_status_style() -> String {
+    "bold red".to_string()
+}
+
+fn default_detect_renames() -> bool {
+    true
 }
 
 fn default_git_status_symbol_conflicted() -> String {
@@ -337,6 +1136,9 @@ fn default_git_status_symbol_staged() -> String {
 fn default_git_status_symbol_untracked() -> String {
     "?".to_string()
 }
+fn default_git_status_symbol_ignored() -> String {
+    "◌".to_string()
+}
 fn default_git_status_symbol_ahead() -> String {
     "⇡".to_string()
 }
@@ -346,6 +1148,9 @@ fn default_git_status_symbol_behind() -> String {
 fn default_git_status_symbol_diverged() -> String {
     "⇕".to_string()
 }
+fn default_git_status_symbol_changed() -> String {
+    "±".to_string()
+}
 
 // ModuleConfig implementations
 impl ModuleConfig for DirectoryConfig {
@@ -360,6 +1165,26 @@ impl ModuleConfig for DirectoryConfig {
     fn style(&self) -> &str {
         &self.style
     }
+
+    fn timeout_ms(&self) -> Option<u64> {
+        self.timeout_ms
+    }
+
+    fn timeout_symbol(&self) -> &str {
+        &self.timeout_symbol
+    }
+
+    fn when(&self) -> Option<&str> {
+        self.when.as_deref()
+    }
+
+    fn transform(&self) -> Option<crate::style::Transform> {
+        self.transform
+    }
+
+    fn disabled(&self) -> bool {
+        self.disabled
+    }
 }
 
 impl ModuleConfig for ClaudeModelConfig {
@@ -374,6 +1199,26 @@ impl ModuleConfig for ClaudeModelConfig {
     fn style(&self) -> &str {
         &self.style
     }
+
+    fn timeout_ms(&self) -> Option<u64> {
+        self.timeout_ms
+    }
+
+    fn timeout_symbol(&self) -> &str {
+        &self.timeout_symbol
+    }
+
+    fn when(&self) -> Option<&str> {
+        self.when.as_deref()
+    }
+
+    fn transform(&self) -> Option<crate::style::Transform> {
+        self.transform
+    }
+
+    fn disabled(&self) -> bool {
+        self.disabled
+    }
 }
 
 impl ModuleConfig for GitBranchConfig {
@@ -388,6 +1233,26 @@ impl ModuleConfig for GitBranchConfig {
     fn style(&self) -> &str {
         &self.style
     }
+
+    fn timeout_ms(&self) -> Option<u64> {
+        self.timeout_ms
+    }
+
+    fn timeout_symbol(&self) -> &str {
+        &self.timeout_symbol
+    }
+
+    fn when(&self) -> Option<&str> {
+        self.when.as_deref()
+    }
+
+    fn transform(&self) -> Option<crate::style::Transform> {
+        self.transform
+    }
+
+    fn disabled(&self) -> bool {
+        self.disabled
+    }
 }
 
 impl ModuleConfig for GitStatusConfig {
@@ -402,99 +1267,335 @@ impl ModuleConfig for GitStatusConfig {
     fn style(&self) -> &str {
         &self.style
     }
+
+    fn timeout_ms(&self) -> Option<u64> {
+        self.timeout_ms
+    }
+
+    fn timeout_symbol(&self) -> &str {
+        &self.timeout_symbol
+    }
+
+    fn when(&self) -> Option<&str> {
+        self.when.as_deref()
+    }
+
+    fn transform(&self) -> Option<crate::style::Transform> {
+        self.transform
+    }
+
+    fn disabled(&self) -> bool {
+        self.disabled
+    }
+}
+
+impl ModuleConfig for CcVersionConfig {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn format(&self) -> &str {
+        &self.format
+    }
+
+    fn style(&self) -> &str {
+        &self.style
+    }
+
+    fn timeout_ms(&self) -> Option<u64> {
+        self.timeout_ms
+    }
+
+    fn timeout_symbol(&self) -> &str {
+        &self.timeout_symbol
+    }
+
+    fn when(&self) -> Option<&str> {
+        self.when.as_deref()
+    }
+
+    fn transform(&self) -> Option<crate::style::Transform> {
+        self.transform
+    }
+
+    fn disabled(&self) -> bool {
+        self.disabled
+    }
+}
+
+impl ModuleConfig for SessionConfig {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn format(&self) -> &str {
+        &self.format
+    }
+
+    fn style(&self) -> &str {
+        &self.style
+    }
+
+    fn timeout_ms(&self) -> Option<u64> {
+        self.timeout_ms
+    }
+
+    fn timeout_symbol(&self) -> &str {
+        &self.timeout_symbol
+    }
+
+    fn when(&self) -> Option<&str> {
+        self.when.as_deref()
+    }
+
+    fn transform(&self) -> Option<crate::style::Transform> {
+        self.transform
+    }
+
+    fn disabled(&self) -> bool {
+        self.disabled
+    }
+}
+
+impl ModuleConfig for LocalIpConfig {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn format(&self) -> &str {
+        &self.format
+    }
+
+    fn style(&self) -> &str {
+        &self.style
+    }
+
+    fn timeout_ms(&self) -> Option<u64> {
+        self.timeout_ms
+    }
+
+    fn timeout_symbol(&self) -> &str {
+        &self.timeout_symbol
+    }
+
+    fn when(&self) -> Option<&str> {
+        self.when.as_deref()
+    }
+
+    fn transform(&self) -> Option<crate::style::Transform> {
+        self.transform
+    }
+
+    fn disabled(&self) -> bool {
+        self.disabled
+    }
+}
+
+impl ModuleConfig for StatusConfig {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn format(&self) -> &str {
+        &self.format
+    }
+
+    fn style(&self) -> &str {
+        &self.style
+    }
+
+    fn timeout_ms(&self) -> Option<u64> {
+        self.timeout_ms
+    }
+
+    fn timeout_symbol(&self) -> &str {
+        &self.timeout_symbol
+    }
+
+    fn when(&self) -> Option<&str> {
+        self.when.as_deref()
+    }
+
+    fn transform(&self) -> Option<crate::style::Transform> {
+        self.transform
+    }
+
+    fn disabled(&self) -> bool {
+        self.disabled
+    }
+}
+
+impl ModuleConfig for ContextUsageConfig {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn format(&self) -> &str {
+        &self.format
+    }
+
+    fn style(&self) -> &str {
+        &self.style
+    }
+
+    fn timeout_ms(&self) -> Option<u64> {
+        self.timeout_ms
+    }
+
+    fn timeout_symbol(&self) -> &str {
+        &self.timeout_symbol
+    }
+
+    fn when(&self) -> Option<&str> {
+        self.when.as_deref()
+    }
+
+    fn transform(&self) -> Option<crate::style::Transform> {
+        self.transform
+    }
+
+    fn disabled(&self) -> bool {
+        self.disabled
+    }
 }
 
 impl Config {
     /// Validate configuration values. Returns an error for clearly invalid values.
     pub fn validate(&self) -> Result<(), CoreError> {
-        // Milliseconds; enforce sane bounds (50ms ..= 600_000ms)
-        if self.command_timeout < 50 || self.command_timeout > 600_000 {
-            return Err(CoreError::InvalidConfig(format!(
-                "command_timeout out of range (50..=600000): {}",
-                self.command_timeout
-            )));
+        check_timeout_range("command_timeout", self.command_timeout)?;
+        if self.max_input_bytes == 0 {
+            return Err(CoreError::InvalidConfig(
+                "max_input_bytes must be greater than 0".to_string(),
+            ));
+        }
+        if let Some(name) = &self.preset {
+            if crate::presets::preset_toml(name).is_none() {
+                return Err(CoreError::InvalidConfig(format!(
+                    "unknown preset '{name}' (available: {})",
+                    crate::presets::names().join(", ")
+                )));
+            }
+        }
+        let overrides = [
+            ("directory", self.directory.timeout_ms),
+            ("claude_model", self.claude_model.timeout_ms),
+            ("git_branch", self.git_branch.timeout_ms),
+            ("git_status", self.git_status.timeout_ms),
+            ("cc_version", self.cc_version.timeout_ms),
+            ("session", self.session.timeout_ms),
+            ("localip", self.localip.timeout_ms),
+            ("status", self.status.timeout_ms),
+            ("context_usage", self.context_usage.timeout_ms),
+        ];
+        for (name, timeout) in overrides {
+            if let Some(ms) = timeout {
+                check_timeout_range(&format!("{name}.timeout_ms"), ms)?;
+            }
         }
         Ok(())
     }
 
+    /// Override `command_timeout` with a millisecond value from the
+    /// environment (see [`crate::config::TIMEOUT_ENV`])
+    ///
+    /// The value must be a number within the same bounds [`Config::validate`]
+    /// enforces; otherwise the config is left unchanged and an error returned.
+    pub fn apply_timeout_override(&mut self, value: &str) -> Result<(), CoreError> {
+        let ms: u64 = value.trim().parse().map_err(|_| {
+            CoreError::InvalidConfig(format!(
+                "{} is not a number of milliseconds: '{value}'",
+                crate::config::TIMEOUT_ENV
+            ))
+        })?;
+        check_timeout_range(crate::config::TIMEOUT_ENV, ms)?;
+        self.command_timeout = ms;
+        Ok(())
+    }
+
     /// Collect non-fatal warnings about style/format configuration.
     /// Unknown style tokens or unknown variables in format strings should not
     /// break the program, but we surface them as warnings.
+    ///
+    /// Format tokens are checked against the built-in module registry; use
+    /// [`Config::collect_warnings_with_registry`] when custom modules are
+    /// registered.
     pub fn collect_warnings(&self) -> Vec<String> {
-        let mut warnings = Vec::new();
-        fn is_named(name: &str) -> bool {
-            matches!(
-                name,
-                "black" | "red" | "green" | "yellow" | "blue" | "magenta" | "cyan" | "white"
-            )
-        }
+        self.collect_warnings_with_registry(&crate::modules::Registry::with_defaults())
+    }
 
-        fn valid_color_spec(spec: &str) -> bool {
-            let s = spec.to_lowercase();
-            if s == "none" {
-                return true;
-            }
-            if let Some(hex) = s.strip_prefix('#') {
-                if hex.len() == 6
-                    && u8::from_str_radix(&hex[0..2], 16).is_ok()
-                    && u8::from_str_radix(&hex[2..4], 16).is_ok()
-                    && u8::from_str_radix(&hex[4..6], 16).is_ok()
-                {
-                    return true;
-                }
-            }
-            if s.chars().all(|c| c.is_ascii_digit()) {
-                if let Ok(n) = s.parse::<u16>() {
-                    if n <= 255 {
-                        return true;
-                    }
-                }
-            }
-            if let Some(n) = s.strip_prefix("bright-") {
-                return is_named(n);
-            }
-            is_named(&s)
-        }
+    /// Same as [`Config::collect_warnings`], treating every module in
+    /// `registry` (plus `character`) as a known format token.
+    pub fn collect_warnings_with_registry(
+        &self,
+        registry: &crate::modules::Registry,
+    ) -> Vec<String> {
+        let mut warnings = Vec::new();
 
+        // Same parser as the renderer, one token at a time so that every
+        // unknown token is reported
         let check_style = |name: &str, style: &str, warnings: &mut Vec<String>| {
             for tok in style.split_whitespace() {
-                let t = tok.to_lowercase();
-                match t.as_str() {
-                    "bold" | "italic" | "underline" => continue,
-                    _ => {}
-                }
-                if let Some(rest) = t.strip_prefix("fg:") {
-                    if !valid_color_spec(rest) {
-                        warnings.push(crate::messages::warn_unknown_style_token(name, tok));
-                    }
-                    continue;
-                }
-                if let Some(rest) = t.strip_prefix("bg:") {
-                    if !valid_color_spec(rest) {
-                        warnings.push(crate::messages::warn_unknown_style_token(name, tok));
-                    }
-                    continue;
-                }
-                // Bare color spec -> treat as foreground
-                if !valid_color_spec(&t) {
+                if crate::style::parse_style(tok).is_err() {
                     warnings.push(crate::messages::warn_unknown_style_token(name, tok));
                 }
             }
         };
 
+        for (tier, style) in &self.accents {
+            check_style(&format!("accents.{tier}"), style, &mut warnings);
+        }
         check_style("directory", &self.directory.style, &mut warnings);
+        for pattern in &self.directory.redact {
+            if regex::Regex::new(pattern).is_err() {
+                warnings.push(crate::messages::warn_redact_pattern_literal(pattern));
+            }
+        }
         check_style("claude_model", &self.claude_model.style, &mut warnings);
         check_style("git_branch", &self.git_branch.style, &mut warnings);
         check_style("git_status", &self.git_status.style, &mut warnings);
+        check_style("cc_version", &self.cc_version.style, &mut warnings);
+        check_style("session", &self.session.style, &mut warnings);
+        check_style("status", &self.status.style, &mut warnings);
+        check_style("localip", &self.localip.style, &mut warnings);
+        check_style("context_usage", &self.context_usage.style, &mut warnings);
+
+        // `$style` outside `[text](...)` renders nothing (or the style text)
+        let formats = [
+            ("format", self.format.as_str()),
+            ("directory.format", self.directory.format.as_str()),
+            ("claude_model.format", self.claude_model.format.as_str()),
+            ("git_branch.format", self.git_branch.format.as_str()),
+            ("git_status.format", self.git_status.format.as_str()),
+            ("cc_version.format", self.cc_version.format.as_str()),
+            ("session.format", self.session.format.as_str()),
+            ("localip.format", self.localip.format.as_str()),
+            ("status.format", self.status.format.as_str()),
+            ("context_usage.format", self.context_usage.format.as_str()),
+        ];
+        for (location, format) in formats {
+            if crate::parser::has_bare_style_token(format) {
+                warnings.push(crate::messages::warn_bare_style_token(location));
+            }
+        }
 
-        // Unknown $tokens in top-level format
-        for part in self.format.split_whitespace() {
-            if let Some(tok) = part.strip_prefix('$') {
-                match tok {
-                    "directory" | "claude_model" | "git_branch" | "git_status"
-                    | "claude_session" | "character" => {}
-                    other => warnings.push(crate::messages::warn_unknown_format_token(other)),
-                }
+        // Unknown $tokens in top-level format (plain, braced, or with defaults).
+        // Git modules are only registered with the `git` feature; their tokens
+        // stay known so that builds without it don't warn about the defaults.
+        let mut known = registry.list();
+        known.extend(["git_branch", "git_status"]);
+        for tok in crate::parser::extract_modules_from_format(&self.format) {
+            if tok == "style" {
+                continue;
+            }
+            let (module, variant) = crate::parser::split_variant(&tok);
+            let known_variant = variant.is_none_or(|variant| {
+                self.variants.contains_key(&tok)
+                    || self
+                        .extra_module_table(module)
+                        .is_some_and(|t| t.get(variant).is_some_and(toml::Value::is_table))
+            });
+            if tok != "character" && !(known.contains(&module) && known_variant) {
+                warnings.push(crate::messages::warn_unknown_format_token(&tok));
             }
         }
 
@@ -508,6 +1609,25 @@ impl Config {
             _ => None,
         }
     }
+
+    /// JSON Schema for the configuration file (pretty-printed)
+    ///
+    /// Includes field descriptions and defaults for editor completion.
+    #[cfg(feature = "schema")]
+    pub fn json_schema() -> String {
+        let schema = schemars::schema_for!(Config);
+        serde_json::to_string_pretty(&schema).unwrap_or_default()
+    }
+}
+
+/// Timeouts are milliseconds within 50..=600000
+fn check_timeout_range(name: &str, ms: u64) -> Result<(), CoreError> {
+    if !(50..=600_000).contains(&ms) {
+        return Err(CoreError::InvalidConfig(format!(
+            "{name} out of range (50..=600000): {ms}"
+        )));
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -529,6 +1649,31 @@ mod validation_tests {
         assert!(cfg.validate().is_err());
     }
 
+    #[test]
+    fn module_timeout_override_bounds() {
+        let mut cfg = Config::default();
+        cfg.git_status.timeout_ms = Some(10);
+        let err = cfg.validate().unwrap_err();
+        assert!(format!("{err}").contains("git_status.timeout_ms"));
+        cfg.git_status.timeout_ms = Some(5_000);
+        assert!(cfg.validate().is_ok());
+        cfg.git_status.timeout_ms = Some(600_001);
+        assert!(cfg.validate().is_err());
+    }
+
+    #[rstest::rstest]
+    #[case::valid("1000", Some(1000))]
+    #[case::trimmed(" 2000\n", Some(2000))]
+    #[case::too_small("10", None)]
+    #[case::too_large("600001", None)]
+    #[case::not_a_number("fast", None)]
+    fn timeout_override_respects_bounds(#[case] value: &str, #[case] expected: Option<u64>) {
+        let mut cfg = Config::default();
+        let result = cfg.apply_timeout_override(value);
+        assert_eq!(result.is_ok(), expected.is_some());
+        assert_eq!(cfg.command_timeout, expected.unwrap_or(500));
+    }
+
     #[test]
     fn warns_on_unknown_style_tokens() {
         let mut cfg = Config::default();
@@ -550,6 +1695,107 @@ mod validation_tests {
         );
     }
 
+    #[test]
+    fn warns_on_bare_style_token() {
+        let mut cfg = Config::from_toml_str("format = \"$directory $style\"").unwrap();
+        assert_eq!(
+            cfg.collect_warnings(),
+            vec![crate::messages::warn_bare_style_token("format")]
+        );
+
+        cfg.format = "[$directory]($style)".to_string();
+        cfg.git_status.format = "$all_status $style".to_string();
+        assert_eq!(
+            cfg.collect_warnings(),
+            vec![crate::messages::warn_bare_style_token("git_status.format")]
+        );
+    }
+
+    #[test]
+    fn warns_on_invalid_redact_regex() {
+        let mut cfg = Config::default();
+        cfg.directory.redact = vec!["alice".to_string(), "a(b".to_string()];
+        assert_eq!(
+            cfg.collect_warnings(),
+            vec!["Invalid regex in directory.redact: 'a(b' (matched literally)".to_string()]
+        );
+    }
+
+    #[test]
+    fn warns_on_variant_without_table() {
+        let cfg = Config::from_toml_str(
+            "format = \"$directory#short $directory#nope\"\n[directory.short]\nstyle = \"red\"\n",
+        )
+        .unwrap();
+        let ws = cfg.collect_warnings();
+        assert_eq!(
+            ws,
+            vec!["Unknown format token: '$directory#nope'".to_string()]
+        );
+    }
+
+    #[test]
+    fn default_config_has_no_warnings() {
+        // Also holds without the `git` feature, where the git modules are
+        // not registered but appear in the default format
+        let ws = Config::default().collect_warnings();
+        assert!(ws.is_empty(), "unexpected warnings: {ws:?}");
+    }
+
+    #[test]
+    fn braced_default_tokens_are_recognized() {
+        let cfg = Config {
+            format: "${git_branch:-no-git} ${directory}".to_string(),
+            ..Default::default()
+        };
+        let ws = cfg.collect_warnings();
+        assert!(ws.is_empty(), "unexpected warnings: {ws:?}");
+    }
+
+    #[test]
+    fn registered_module_tokens_are_recognized() {
+        let cfg = Config {
+            format: "$directory $cc_version $session $claude_session".to_string(),
+            ..Default::default()
+        };
+        let ws = cfg.collect_warnings();
+        assert!(ws.is_empty(), "unexpected warnings: {ws:?}");
+    }
+
+    #[test]
+    fn custom_registry_modules_are_recognized() {
+        use crate::modules::{EmptyConfig, Module, ModuleFactory, Registry};
+        use crate::types::context::Context;
+
+        struct MyFactory;
+        impl ModuleFactory for MyFactory {
+            fn name(&self) -> &'static str {
+                "my_module"
+            }
+            fn create(&self, _context: &Context) -> Box<dyn Module> {
+                unimplemented!("not rendered in this test")
+            }
+            fn config<'a>(&self, _context: &'a Context) -> Option<&'a dyn ModuleConfig> {
+                Some(&EmptyConfig)
+            }
+        }
+
+        let cfg = Config {
+            format: "$directory $my_module $character".to_string(),
+            ..Default::default()
+        };
+        assert!(
+            cfg.collect_warnings()
+                .iter()
+                .any(|w| w.contains("'$my_module'"))
+        );
+
+        let mut registry = Registry::with_defaults();
+        registry.register_factory(MyFactory);
+        let ws = cfg.collect_warnings_with_registry(&registry);
+        assert!(ws.is_empty(), "unexpected warnings: {ws:?}");
+    }
+
     #[test]
     fn git_branch_default_format_uses_symbol_token() {
         // The default git_branch format should include $symbol so that
@@ -589,4 +1835,51 @@ mod validation_tests {
         assert!(ws.iter().any(|w| w.contains("fg:300")));
         assert!(ws.iter().any(|w| w.contains("sparkle")));
     }
+
+    #[rstest::rstest]
+    #[case::prev_bg("fg:prev_bg bg:blue")]
+    #[case::bare_prev("prev_fg")]
+    #[case::dimmed("dimmed italic")]
+    #[case::reset("fg:none bg:none")]
+    fn styles_accepted_by_renderer_do_not_warn(#[case] style: &str) {
+        let mut cfg = Config::default();
+        cfg.git_branch.style = style.to_string();
+        assert!(cfg.collect_warnings().is_empty(), "{style}");
+        assert!(crate::style::parse_style(style).is_ok());
+    }
+
+    #[rstest::rstest]
+    #[case::blink("bold blink", "blink")]
+    #[case::prev_typo("fg:prev_bgg", "fg:prev_bgg")]
+    fn styles_rejected_by_renderer_warn(#[case] style: &str, #[case] token: &str) {
+        let mut cfg = Config::default();
+        cfg.git_branch.style = style.to_string();
+        let ws = cfg.collect_warnings();
+        assert_eq!(ws.len(), 1, "{ws:?}");
+        assert!(ws[0].contains(token), "{ws:?}");
+        assert!(crate::style::parse_style(style).is_err());
+    }
+}
+
+#[cfg(all(test, feature = "schema"))]
+mod schema_tests {
+    use super::*;
+
+    #[test]
+    fn schema_describes_modules_with_defaults() {
+        let schema: serde_json::Value = serde_json::from_str(&Config::json_schema()).unwrap();
+        let props = &schema["properties"];
+        assert_eq!(props["command_timeout"]["default"], 500);
+        assert!(props["directory"].is_object());
+        assert!(props["git_branch"].is_object());
+
+        let defs = &schema["$defs"];
+        let directory = &defs["DirectoryConfig"]["properties"];
+        assert_eq!(directory["truncation_length"]["default"], 3);
+        assert!(
+            directory["truncation_symbol"]["description"]
+                .as_str()
+                .is_some_and(|d| d.contains("truncated"))
+        );
+    }
 }
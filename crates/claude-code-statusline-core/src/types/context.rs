@@ -6,18 +6,17 @@
 
 use crate::config::Config;
 use crate::types::claude::ClaudeInput;
-use std::collections::HashSet;
+#[cfg(feature = "git")]
+use crate::types::git::{GitSnapshot, GitStatusCounts};
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
-use std::sync::OnceLock;
-#[cfg(test)]
-use std::sync::atomic::{AtomicUsize, Ordering};
 #[cfg(feature = "git")]
-use std::sync::{Mutex, MutexGuard};
-
+use std::sync::MutexGuard;
 #[cfg(test)]
-static REPO_DISCOVER_COUNT: AtomicUsize = AtomicUsize::new(0);
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
 
 /// Central context structure that holds all runtime data and configuration
 ///
@@ -29,7 +28,11 @@ static REPO_DISCOVER_COUNT: AtomicUsize = AtomicUsize::new(0);
 /// # Memoization
 ///
 /// - Git repository discovery is cached using OnceLock
-/// - Directory contents scanning is cached using OnceLock
+/// - Directory contents scanning is cached using OnceLock and shared by
+///   clones, so modules rendered from cloned contexts scan only once
+/// - `find_up` marker lookups are cached per marker name and shared by clones
+/// - The git snapshot (HEAD, upstream, ahead/behind, status counts) is
+///   computed at most once per frame and shared by clones
 /// - Both operations are thread-safe and only executed once
 pub struct Context {
     /// Raw input from Claude Code
@@ -53,13 +56,52 @@ pub struct Context {
 
     /// Memoized directory contents for current working directory
     #[allow(dead_code)]
-    dir_contents: OnceLock<Result<DirContents, io::Error>>,
+    dir_contents: Arc<OnceLock<Result<DirContents, io::Error>>>,
+
+    /// Memoized repository root (shared by clones)
+    repo_root: Arc<OnceLock<Option<PathBuf>>>,
+
+    /// Memoized `find_up` results keyed by marker name (shared by clones)
+    found_up: Arc<Mutex<HashMap<String, Option<PathBuf>>>>,
+
+    /// Memoized git snapshot (shared by clones)
+    #[cfg(feature = "git")]
+    git_snapshot: Arc<OnceLock<Option<GitSnapshot>>>,
+
+    /// Number of times the git status counts were computed (shared by clones)
+    #[cfg(all(test, feature = "git"))]
+    git_status_reads: Arc<AtomicUsize>,
+
+    /// Number of repository discoveries by this context and its clones
+    #[cfg(all(test, feature = "git"))]
+    repo_discovers: Arc<AtomicUsize>,
+
+    /// Number of directory scans by this context and its clones
+    #[cfg(test)]
+    dir_scans: Arc<AtomicUsize>,
+
+    /// Number of `find_up` ancestor walks by this context and its clones
+    #[cfg(test)]
+    find_up_walks: Arc<AtomicUsize>,
 }
 
 impl Context {
     /// Create a new Context from ClaudeInput and Config
     pub fn new(input: ClaudeInput, config: Config) -> Self {
-        let current_dir = PathBuf::from(&input.cwd);
+        // Prefer an absolute path: fall back to workspace.current_dir when cwd is relative
+        let cwd = PathBuf::from(&input.cwd);
+        let current_dir = match input.workspace.as_ref() {
+            Some(ws) if !cwd.is_absolute() && Path::new(&ws.current_dir).is_absolute() => {
+                PathBuf::from(&ws.current_dir)
+            }
+            _ => cwd,
+        };
+        // Opt-in: use the real path so git discovery and repo-relative truncation agree
+        let current_dir = if config.directory.resolve_symlinks {
+            fs::canonicalize(&current_dir).unwrap_or(current_dir)
+        } else {
+            current_dir
+        };
 
         // For now, project_root is the same as workspace.project_dir if available
         let project_root = input
@@ -75,7 +117,19 @@ impl Context {
             project_root,
             #[cfg(feature = "git")]
             repo: OnceLock::new(),
-            dir_contents: OnceLock::new(),
+            dir_contents: Arc::new(OnceLock::new()),
+            repo_root: Arc::new(OnceLock::new()),
+            found_up: Arc::new(Mutex::new(HashMap::new())),
+            #[cfg(feature = "git")]
+            git_snapshot: Arc::new(OnceLock::new()),
+            #[cfg(all(test, feature = "git"))]
+            git_status_reads: Arc::new(AtomicUsize::new(0)),
+            #[cfg(all(test, feature = "git"))]
+            repo_discovers: Arc::new(AtomicUsize::new(0)),
+            #[cfg(test)]
+            dir_scans: Arc::new(AtomicUsize::new(0)),
+            #[cfg(test)]
+            find_up_walks: Arc::new(AtomicUsize::new(0)),
         }
     }
 
@@ -90,13 +144,26 @@ impl Context {
         &self.input.model.display_name
     }
 
+    /// Color depth for this render: `color_depth` from the config, or
+    /// detected from the environment
+    pub fn color_depth(&self) -> crate::style::ColorDepth {
+        self.config
+            .color_depth
+            .unwrap_or_else(crate::style::ColorDepth::detect)
+    }
+
     /// Get memoized git repository for current directory (if available).
     /// Uses OnceLock to avoid repeated `git2::Repository::discover` calls.
+    /// Always an error when `git_backend = "cli"`.
     #[cfg(feature = "git")]
     pub fn repo(&self) -> Result<MutexGuard<'_, git2::Repository>, &git2::Error> {
         let res = self.repo.get_or_init(|| {
+            // `git_backend = "cli"` never opens the repository with libgit2
+            if !self.config.git_backend.uses_libgit2() {
+                return Err(git2::Error::from_str("libgit2 is disabled by git_backend"));
+            }
             #[cfg(test)]
-            REPO_DISCOVER_COUNT.fetch_add(1, Ordering::Relaxed);
+            self.repo_discovers.fetch_add(1, Ordering::Relaxed);
             git2::Repository::discover(&self.current_dir).map(Mutex::new)
         });
         match res {
@@ -105,12 +172,53 @@ impl Context {
         }
     }
 
+    /// Memoized HEAD/upstream snapshot of the current repository
+    ///
+    /// `None` when no repository is found. Shared by clones, so the git
+    /// modules rendered from per-module clones read the repository once.
+    #[cfg(feature = "git")]
+    pub fn git_snapshot(&self) -> Option<&GitSnapshot> {
+        self.git_snapshot
+            .get_or_init(|| self.repo().ok().map(|repo| GitSnapshot::read(&repo)))
+            .as_ref()
+    }
+
+    /// Commits ahead of / behind the upstream (or `git_status.compare_to`)
+    ///
+    /// Computed on first use and memoized in the [`GitSnapshot`].
+    #[cfg(feature = "git")]
+    pub fn git_ahead_behind(&self) -> Option<(usize, usize)> {
+        let snapshot = self.git_snapshot()?;
+        *snapshot.ahead_behind.get_or_init(|| {
+            let repo = self.repo().ok()?;
+            crate::types::git::ahead_behind(&repo, self.config.git_status.compare_to.as_deref())
+        })
+    }
+
+    /// Working tree / index status counts, `None` outside a repository or
+    /// in a bare one
+    ///
+    /// Computed on first use and memoized in the [`GitSnapshot`].
+    #[cfg(feature = "git")]
+    pub fn git_status_counts(&self) -> Option<GitStatusCounts> {
+        let snapshot = self.git_snapshot().filter(|s| !s.bare)?;
+        let counts = snapshot.status.get_or_init(|| {
+            #[cfg(test)]
+            self.git_status_reads.fetch_add(1, Ordering::Relaxed);
+            match self.repo() {
+                Ok(mut repo) => GitStatusCounts::read(&mut repo, &self.config.git_status),
+                Err(_) => GitStatusCounts::default(),
+            }
+        });
+        Some(*counts)
+    }
+
     /// Get memoized directory contents for current directory.
     #[allow(dead_code)]
     pub fn dir_contents(&self) -> Result<&DirContents, &io::Error> {
         let res = self.dir_contents.get_or_init(|| {
             #[cfg(test)]
-            DIR_SCAN_COUNT.fetch_add(1, Ordering::Relaxed);
+            self.dir_scans.fetch_add(1, Ordering::Relaxed);
             DirContents::scan(&self.current_dir)
         });
         match res {
@@ -119,9 +227,73 @@ impl Context {
         }
     }
 
-    #[cfg(test)]
-    pub fn test_repo_discover_count() -> usize {
-        REPO_DISCOVER_COUNT.load(Ordering::Relaxed)
+    /// Whether the current directory contains a file named `name`
+    ///
+    /// Backed by the memoized [`Context::dir_contents`] scan.
+    pub fn has_file(&self, name: &str) -> bool {
+        self.dir_contents().is_ok_and(|dc| dc.contains_file(name))
+    }
+
+    /// Whether the current directory contains a file with extension `ext`
+    ///
+    /// Backed by the memoized [`Context::dir_contents`] scan.
+    pub fn has_extension(&self, ext: &str) -> bool {
+        self.dir_contents().is_ok_and(|dc| dc.has_extension(ext))
+    }
+
+    /// Root (working tree) of the repository containing the current directory
+    ///
+    /// Uses the shared [`Context::git_snapshot`] when the `git` feature is
+    /// enabled; otherwise (or when discovery fails) walks up looking for a
+    /// `.git` directory or file (worktrees). The result is memoized.
+    pub fn repo_root(&self) -> Option<PathBuf> {
+        self.repo_root
+            .get_or_init(|| {
+                #[cfg(feature = "git")]
+                // Bare repositories have no workdir and yield no root
+                if let Some(wd) = self.git_snapshot().and_then(|s| s.workdir.as_deref()) {
+                    if self.current_dir.starts_with(wd) {
+                        return Some(wd.to_path_buf());
+                    }
+                }
+                self.current_dir
+                    .ancestors()
+                    .find(|dir| {
+                        let dot_git = dir.join(".git");
+                        dot_git.is_dir() || dot_git.is_file()
+                    })
+                    .map(Path::to_path_buf)
+            })
+            .clone()
+    }
+
+    /// Find the nearest directory containing `name`, starting at the current
+    /// directory and walking up its ancestors.
+    ///
+    /// The walk stops after the repository root (a directory with `.git`) or
+    /// at the filesystem root. Returns the path to the marker itself.
+    /// Results are memoized per marker name.
+    pub fn find_up(&self, name: &str) -> Option<PathBuf> {
+        let mut cache = self.found_up.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(found) = cache.get(name) {
+            return found.clone();
+        }
+        #[cfg(test)]
+        self.find_up_walks.fetch_add(1, Ordering::Relaxed);
+
+        let mut found = None;
+        for dir in self.current_dir.ancestors() {
+            let candidate = dir.join(name);
+            if candidate.exists() {
+                found = Some(candidate);
+                break;
+            }
+            if dir.join(".git").exists() {
+                break;
+            }
+        }
+        cache.insert(name.to_string(), found.clone());
+        found
     }
 }
 
@@ -157,6 +329,7 @@ mod tests {
             }),
             version: Some("1.0.0".to_string()),
             output_style: None,
+            exit_status: None,
         }
     }
 
@@ -201,6 +374,65 @@ mod tests {
         assert_eq!(context.project_root, None);
     }
 
+    #[rstest]
+    #[case("src", Some("/abs/project/src"), "/abs/project/src")]
+    #[case("./src", Some("/abs/project/src"), "/abs/project/src")]
+    #[case("src", Some("relative/src"), "src")]
+    #[case("src", None, "src")]
+    #[case("/abs/cwd", Some("/abs/project/src"), "/abs/cwd")]
+    fn test_current_dir_prefers_absolute_path(
+        #[case] cwd: &str,
+        #[case] workspace_dir: Option<&str>,
+        #[case] expected: &str,
+    ) {
+        let input =
+            create_claude_input(cwd, "Opus", workspace_dir.map(|dir| (dir, "/abs/project")));
+        let context = Context::new(input, Config::default());
+
+        assert_eq!(context.current_dir, PathBuf::from(expected));
+        // The raw cwd is still exposed unchanged
+        assert_eq!(context.current_dir_str(), cwd);
+    }
+
+    #[cfg(unix)]
+    #[rstest]
+    #[case(true)]
+    #[case(false)]
+    fn test_resolve_symlinks_canonicalizes_current_dir(#[case] resolve: bool) {
+        let tmp = tempdir().unwrap();
+        let real = tmp.path().join("real");
+        fs::create_dir_all(real.join("project")).unwrap();
+        let link = tmp.path().join("link");
+        std::os::unix::fs::symlink(&real, &link).unwrap();
+
+        let cwd = link.join("project");
+        let cwd_str = cwd.to_str().unwrap();
+        let mut config = Config::default();
+        config.directory.resolve_symlinks = resolve;
+        let context = Context::new(create_claude_input(cwd_str, "Opus", None), config);
+
+        let expected = if resolve {
+            fs::canonicalize(real.join("project")).unwrap()
+        } else {
+            cwd.clone()
+        };
+        assert_eq!(context.current_dir, expected);
+        assert_eq!(context.current_dir_str(), cwd_str);
+    }
+
+    #[rstest]
+    fn test_resolve_symlinks_falls_back_to_raw_path() {
+        let mut config = Config::default();
+        config.directory.resolve_symlinks = true;
+        let input = create_claude_input("/definitely/missing/dir", "Opus", None);
+        let context = Context::new(input, config);
+
+        assert_eq!(
+            context.current_dir,
+            PathBuf::from("/definitely/missing/dir")
+        );
+    }
+
     #[cfg(feature = "git")]
     #[rstest]
     fn test_repo_memoization_once() {
@@ -225,12 +457,12 @@ mod tests {
         // The helper above expects (current, project); pass same path for both.
         let context = Context::new(input, Config::default());
 
-        // First call: discover runs (global counter may be affected by other tests)
+        // First call: discover runs
         drop(context.repo().unwrap());
-        let after_first = Context::test_repo_discover_count();
+        assert_eq!(context.test_repo_discovers(), 1);
         // Second call: should be memoized (no increment for the same Context)
         drop(context.repo().unwrap());
-        assert_eq!(Context::test_repo_discover_count(), after_first);
+        assert_eq!(context.test_repo_discovers(), 1);
     }
 
     #[rstest]
@@ -250,19 +482,165 @@ mod tests {
         let input = create_claude_input(root_str, "Opus", Some((root_str, root_str)));
         let context = Context::new(input, Config::default());
 
-        // Baseline counter
-        let baseline = Context::test_dir_scan_count();
         // First call triggers scan
         let dc1 = context.dir_contents().unwrap();
         assert!(dc1.contains_file("a.txt"));
         assert!(dc1.contains_file("b.rs"));
         assert!(dc1.folders.contains("subdir"));
-        assert_eq!(Context::test_dir_scan_count(), baseline + 1);
+        assert_eq!(context.test_dir_scans(), 1);
 
         // Second call uses memoized result (no increment)
         let dc2 = context.dir_contents().unwrap();
         assert!(std::ptr::eq(dc1 as *const _, dc2 as *const _));
-        assert_eq!(Context::test_dir_scan_count(), baseline + 1);
+        assert_eq!(context.test_dir_scans(), 1);
+    }
+
+    #[rstest]
+    fn test_marker_helpers_share_one_scan_across_clones() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path();
+        File::create(root.join("Cargo.toml")).unwrap();
+        File::create(root.join("main.rs")).unwrap();
+        fs::create_dir(root.join("pkg.json")).unwrap();
+
+        let root_str = root.to_str().unwrap();
+        let input = create_claude_input(root_str, "Opus", None);
+        let context = Context::new(input, Config::default());
+
+        // Each module receives its own clone of the context
+        let module_a = context.clone();
+        let module_b = context.clone();
+        assert!(module_a.has_file("Cargo.toml"));
+        assert!(!module_a.has_file("package.json"));
+        assert!(module_b.has_extension("rs"));
+        assert!(!module_b.has_extension("py"));
+        // Directories are not files
+        assert!(!context.has_file("pkg.json"));
+        assert!(!context.has_extension("json"));
+
+        assert_eq!(context.test_dir_scans(), 1);
+    }
+
+    #[rstest]
+    fn test_find_up_walks_to_ancestor_markers() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path();
+        let nested = root.join("project/crates/app/src");
+        fs::create_dir_all(&nested).unwrap();
+        File::create(root.join("project/Cargo.toml")).unwrap();
+        File::create(root.join("project/crates/app/Cargo.toml")).unwrap();
+        File::create(root.join("outside.txt")).unwrap();
+        fs::create_dir(root.join("project/.git")).unwrap();
+
+        let input = create_claude_input(nested.to_str().unwrap(), "Opus", None);
+        let context = Context::new(input, Config::default());
+
+        // Nearest marker wins
+        assert_eq!(
+            context.find_up("Cargo.toml"),
+            Some(root.join("project/crates/app/Cargo.toml"))
+        );
+        // Markers at the repository root are found
+        assert_eq!(context.find_up(".git"), Some(root.join("project/.git")));
+        // The walk stops at the repository root
+        assert_eq!(context.find_up("outside.txt"), None);
+    }
+
+    #[rstest]
+    fn test_find_up_outside_repo_reaches_filesystem_root() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path();
+        let nested = root.join("a/b/c");
+        fs::create_dir_all(&nested).unwrap();
+        File::create(root.join("marker.toml")).unwrap();
+
+        let input = create_claude_input(nested.to_str().unwrap(), "Opus", None);
+        let context = Context::new(input, Config::default());
+
+        assert_eq!(
+            context.find_up("marker.toml"),
+            Some(root.join("marker.toml"))
+        );
+        assert_eq!(context.find_up("no-such-marker-file.lock"), None);
+    }
+
+    #[rstest]
+    fn test_find_up_is_memoized_across_clones() {
+        let tmp = tempdir().unwrap();
+        let nested = tmp.path().join("x/y");
+        fs::create_dir_all(&nested).unwrap();
+        File::create(tmp.path().join("x/go.mod")).unwrap();
+
+        let input = create_claude_input(nested.to_str().unwrap(), "Opus", None);
+        let context = Context::new(input, Config::default());
+
+        let first = context.find_up("go.mod");
+        let second = context.clone().find_up("go.mod");
+        assert_eq!(first, second);
+        assert_eq!(context.test_find_up_walks(), 1);
+
+        // Different markers are walked separately
+        assert_eq!(context.find_up("missing.lock"), None);
+        assert_eq!(context.find_up("missing.lock"), None);
+        assert_eq!(context.test_find_up_walks(), 2);
+    }
+
+    #[cfg(feature = "git")]
+    #[rstest]
+    fn test_repo_root_uses_git_workdir() {
+        let tmp = tempdir().unwrap();
+        let root = fs::canonicalize(tmp.path()).unwrap();
+        GitRepository::init(&root).unwrap();
+        let sub = root.join("src/nested");
+        fs::create_dir_all(&sub).unwrap();
+
+        let input = create_claude_input(sub.to_str().unwrap(), "Opus", None);
+        let context = Context::new(input, Config::default());
+
+        let found = context.repo_root().unwrap();
+        assert_eq!(fs::canonicalize(found).unwrap(), root);
+    }
+
+    #[rstest]
+    #[case::git_dir(true)]
+    #[case::worktree_git_file(false)]
+    fn test_repo_root_falls_back_to_dot_git_marker(#[case] as_dir: bool) {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path().join("checkout");
+        let sub = root.join("a/b");
+        fs::create_dir_all(&sub).unwrap();
+        // Neither marker is a valid repository, so git2 discovery fails
+        if as_dir {
+            fs::create_dir(root.join(".git")).unwrap();
+        } else {
+            fs::write(root.join(".git"), "gitdir: /nonexistent/.git/worktrees/x\n").unwrap();
+        }
+
+        let input = create_claude_input(sub.to_str().unwrap(), "Opus", None);
+        let context = Context::new(input, Config::default());
+
+        assert_eq!(context.repo_root(), Some(root.clone()));
+        // Memoized value is shared with clones
+        assert_eq!(context.clone().repo_root(), Some(root));
+    }
+
+    #[rstest]
+    fn test_repo_root_is_none_outside_repositories() {
+        let tmp = tempdir().unwrap();
+        let input = create_claude_input(tmp.path().to_str().unwrap(), "Opus", None);
+        let context = Context::new(input, Config::default());
+        // Temp dirs are not inside a repository on test machines
+        if tmp.path().ancestors().all(|p| !p.join(".git").exists()) {
+            assert_eq!(context.repo_root(), None);
+        }
+    }
+
+    #[rstest]
+    fn test_marker_helpers_are_false_for_unreadable_dir() {
+        let input = create_claude_input("/definitely/missing/dir", "Opus", None);
+        let context = Context::new(input, Config::default());
+        assert!(!context.has_file("Cargo.toml"));
+        assert!(!context.has_extension("rs"));
     }
 }
 
@@ -270,9 +648,26 @@ mod tests {
 
 impl Clone for Context {
     fn clone(&self) -> Self {
-        // Reconstruct a fresh Context from cloned input and config.
-        // Memoized fields are intentionally not copied (per-execution cache only).
-        Self::new(self.input.clone(), self.config.clone())
+        // Reconstruct a Context from cloned input and config. The directory
+        // scan, repo root, `find_up` results and git snapshot are shared so
+        // every module sees the same single scan; the git repository handle
+        // stays per-clone.
+        Self {
+            dir_contents: Arc::clone(&self.dir_contents),
+            repo_root: Arc::clone(&self.repo_root),
+            found_up: Arc::clone(&self.found_up),
+            #[cfg(feature = "git")]
+            git_snapshot: Arc::clone(&self.git_snapshot),
+            #[cfg(all(test, feature = "git"))]
+            git_status_reads: Arc::clone(&self.git_status_reads),
+            #[cfg(all(test, feature = "git"))]
+            repo_discovers: Arc::clone(&self.repo_discovers),
+            #[cfg(test)]
+            dir_scans: Arc::clone(&self.dir_scans),
+            #[cfg(test)]
+            find_up_walks: Arc::clone(&self.find_up_walks),
+            ..Self::new(self.input.clone(), self.config.clone())
+        }
     }
 }
 
@@ -323,14 +718,38 @@ impl DirContents {
     pub fn contains_file(&self, name: &str) -> bool {
         self.files.contains(name)
     }
-}
 
-#[cfg(test)]
-static DIR_SCAN_COUNT: AtomicUsize = AtomicUsize::new(0);
+    pub fn has_extension(&self, ext: &str) -> bool {
+        self.extensions.contains(ext)
+    }
+}
 
 #[cfg(test)]
 impl Context {
-    pub fn test_dir_scan_count() -> usize {
-        DIR_SCAN_COUNT.load(Ordering::Relaxed)
+    /// Times the current directory was scanned by this context or its clones
+    pub fn test_dir_scans(&self) -> usize {
+        self.dir_scans.load(Ordering::Relaxed)
+    }
+
+    /// Times the git status counts were computed by this context or its clones
+    #[cfg(feature = "git")]
+    pub fn test_git_status_reads(&self) -> usize {
+        self.git_status_reads.load(Ordering::Relaxed)
+    }
+
+    /// Times a repository was discovered by this context or its clones
+    #[cfg(feature = "git")]
+    pub fn test_repo_discovers(&self) -> usize {
+        self.repo_discovers.load(Ordering::Relaxed)
+    }
+
+    /// Times `find_up` walked the ancestors for this context or its clones
+    pub fn test_find_up_walks(&self) -> usize {
+        self.find_up_walks.load(Ordering::Relaxed)
+    }
+
+    /// Whether the repository root has been looked up (by this context or a clone)
+    pub fn test_repo_root_resolved(&self) -> bool {
+        self.repo_root.get().is_some()
     }
 }
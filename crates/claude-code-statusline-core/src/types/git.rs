@@ -0,0 +1,234 @@
+//! Git repository state shared by the git modules
+//!
+//! [`GitSnapshot`] is read once per frame through
+//! [`Context::git_snapshot`](crate::types::context::Context::git_snapshot)
+//! so `git_branch` and `git_status` don't each re-derive HEAD, the upstream
+//! and the working tree status. The costlier parts (ahead/behind and the
+//! status counts) are only computed when a module asks for them.
+
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Pauses before retrying an operation that hit a lock held by a concurrent
+/// git process; the total stays far below the smallest module timeout
+const LOCK_RETRY_DELAYS: [Duration; 2] = [Duration::from_millis(5), Duration::from_millis(15)];
+
+/// Run `op`, retrying while it fails with a lock error (`GIT_ELOCKED`)
+///
+/// A `git` command holding `index.lock` makes libgit2 fail immediately;
+/// such errors usually clear within milliseconds. Other errors, and a lock
+/// that outlasts [`LOCK_RETRY_DELAYS`], are returned as-is.
+pub(crate) fn retry_on_lock<T>(
+    mut op: impl FnMut() -> Result<T, git2::Error>,
+) -> Result<T, git2::Error> {
+    for delay in LOCK_RETRY_DELAYS {
+        match op() {
+            Err(e) if e.code() == git2::ErrorCode::Locked => std::thread::sleep(delay),
+            result => return result,
+        }
+    }
+    op()
+}
+
+/// HEAD and upstream of the repository, plus lazily computed summaries
+#[derive(Debug, Default)]
+pub struct GitSnapshot {
+    /// Repository has no working tree
+    pub bare: bool,
+    /// HEAD points at a branch without commits yet
+    pub unborn: bool,
+    /// Current branch name, `None` when detached (or unborn)
+    pub branch: Option<String>,
+    /// Commit HEAD points at
+    pub head: Option<git2::Oid>,
+    /// Upstream of the current branch (e.g. `origin/main`)
+    pub upstream: Option<String>,
+    /// Working tree root, `None` for a bare repository
+    pub workdir: Option<std::path::PathBuf>,
+    pub(crate) ahead_behind: OnceLock<Option<(usize, usize)>>,
+    pub(crate) status: OnceLock<GitStatusCounts>,
+}
+
+impl GitSnapshot {
+    /// Read HEAD and the upstream of the current branch from `repo`
+    pub(crate) fn read(repo: &git2::Repository) -> Self {
+        let mut snapshot = GitSnapshot {
+            bare: repo.is_bare(),
+            workdir: repo.workdir().map(std::path::Path::to_path_buf),
+            ..Default::default()
+        };
+        match repo.head() {
+            Ok(head) => {
+                snapshot.head = head.target();
+                if head.is_branch() {
+                    let branch = head.shorthand().unwrap_or("").to_string();
+                    snapshot.upstream = repo
+                        .find_branch(&branch, git2::BranchType::Local)
+                        .and_then(|b| b.upstream())
+                        .ok()
+                        .and_then(|up| up.name().ok().flatten().map(str::to_string));
+                    snapshot.branch = Some(branch);
+                }
+            }
+            Err(e) => snapshot.unborn = e.code() == git2::ErrorCode::UnbornBranch,
+        }
+        snapshot
+    }
+}
+
+/// Number of entries per working tree / index state
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GitStatusCounts {
+    pub conflicted: u32,
+    pub stashed: u32,
+    /// Staged deletions
+    pub deleted: u32,
+    /// Staged renames
+    pub renamed: u32,
+    /// Unstaged working tree modifications
+    pub modified: u32,
+    /// Staged type changes
+    pub typechanged: u32,
+    /// Staged changes of any kind (added/modified/renamed/deleted/typechange)
+    pub staged: u32,
+    pub untracked: u32,
+    /// Ignored entries (an ignored directory counts once); only read with
+    /// `show_ignored`
+    pub ignored: u32,
+    /// Files with any index or working tree change, each counted once
+    /// (ignored entries excluded)
+    pub changed: u32,
+}
+
+impl GitStatusCounts {
+    /// Count status entries and stashes of `repo`
+    ///
+    /// Untracked files are listed one by one; staged renames are detected
+    /// (instead of a deletion plus an addition) when `detect_renames` is set,
+    /// and ignored entries are only listed with `show_ignored`.
+    pub(crate) fn read(
+        repo: &mut git2::Repository,
+        cfg: &crate::types::config::GitStatusConfig,
+    ) -> Self {
+        let mut counts = GitStatusCounts::default();
+        let mut opts = git2::StatusOptions::new();
+        opts.include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .renames_head_to_index(cfg.detect_renames)
+            .include_ignored(cfg.show_ignored);
+        if let Ok(stats) = retry_on_lock(|| repo.statuses(Some(&mut opts))) {
+            use git2::Status;
+            for s in stats.iter().map(|e| e.status()) {
+                if !s.intersects(Status::IGNORED) {
+                    counts.changed += 1;
+                }
+                if s.intersects(Status::CONFLICTED) {
+                    counts.conflicted += 1;
+                    continue;
+                }
+                if s.intersects(Status::IGNORED) {
+                    counts.ignored += 1;
+                    continue;
+                }
+                if s.intersects(Status::WT_NEW) {
+                    counts.untracked += 1;
+                }
+                if s.intersects(Status::WT_MODIFIED) {
+                    counts.modified += 1;
+                }
+                if s.intersects(Status::INDEX_NEW | Status::INDEX_MODIFIED) {
+                    counts.staged += 1;
+                }
+                if s.intersects(Status::INDEX_RENAMED) {
+                    counts.renamed += 1;
+                    counts.staged += 1;
+                }
+                if s.intersects(Status::INDEX_DELETED) {
+                    counts.deleted += 1;
+                    counts.staged += 1;
+                }
+                if s.intersects(Status::INDEX_TYPECHANGE) {
+                    counts.typechanged += 1;
+                    counts.staged += 1;
+                }
+            }
+        }
+        let _ = repo.stash_foreach(|_, _, _| {
+            counts.stashed += 1;
+            true
+        });
+        counts
+    }
+}
+
+/// Commits the current branch is ahead of and behind its upstream, or
+/// `compare_to` (any revspec, e.g. `origin/main`) when it has none
+pub(crate) fn ahead_behind(
+    repo: &git2::Repository,
+    compare_to: Option<&str>,
+) -> Option<(usize, usize)> {
+    let head = repo.head().ok()?;
+    if !head.is_branch() {
+        return None;
+    }
+    let local = head.target()?;
+    let upstream = repo
+        .find_branch(head.shorthand()?, git2::BranchType::Local)
+        .and_then(|b| b.upstream())
+        .ok()
+        .and_then(|up| up.get().target());
+    let base = match upstream {
+        Some(oid) => oid,
+        None => repo
+            .revparse_single(compare_to.filter(|r| !r.is_empty())?)
+            .and_then(|obj| obj.peel_to_commit())
+            .ok()?
+            .id(),
+    };
+    repo.graph_ahead_behind(local, base).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn locked() -> git2::Error {
+        git2::Error::new(
+            git2::ErrorCode::Locked,
+            git2::ErrorClass::Index,
+            "index is locked",
+        )
+    }
+
+    #[test]
+    fn lock_errors_are_retried_until_success() {
+        let mut calls = 0;
+        let result = retry_on_lock(|| {
+            calls += 1;
+            if calls < 3 { Err(locked()) } else { Ok(calls) }
+        });
+        assert_eq!(result.unwrap(), 3);
+    }
+
+    #[test]
+    fn persistent_lock_gives_up_after_bounded_attempts() {
+        let mut calls = 0;
+        let result: Result<(), _> = retry_on_lock(|| {
+            calls += 1;
+            Err(locked())
+        });
+        assert_eq!(result.unwrap_err().code(), git2::ErrorCode::Locked);
+        assert_eq!(calls, LOCK_RETRY_DELAYS.len() + 1);
+    }
+
+    #[test]
+    fn other_errors_are_not_retried() {
+        let mut calls = 0;
+        let result: Result<(), _> = retry_on_lock(|| {
+            calls += 1;
+            Err(git2::Error::from_str("boom"))
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+}
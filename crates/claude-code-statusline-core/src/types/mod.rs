@@ -9,6 +9,7 @@
 //! * [`claude`] - Types for Claude Code JSON input
 //! * [`config`] - Configuration structure definitions
 //! * [`context`] - Runtime context that combines input and config
+//! * `git` - Git repository snapshot shared by the git modules (`git` feature)
 
 /// Types for parsing Claude Code JSON input
 pub mod claude;
@@ -18,3 +19,7 @@ pub mod config;
 
 /// Runtime context that combines input and configuration
 pub mod context;
+
+/// Git repository snapshot shared by the git modules
+#[cfg(feature = "git")]
+pub mod git;
@@ -23,3 +23,109 @@ fn engine_renders_basic_status_line() {
         "output should contain model name: {out}"
     );
 }
+
+#[test]
+fn engine_renders_claude_session_token() {
+    let json = r#"{
+        "session_id": "5f2e9a41-7c1d-4e0b",
+        "cwd": "/tmp",
+        "model": { "id": "claude-opus", "display_name": "Opus" }
+    }"#;
+
+    let input = parse_claude_input(json).expect("valid input");
+    let config = Config {
+        format: "$claude_session".to_string(),
+        ..Default::default()
+    };
+    let engine = claude_code_statusline_core::Engine::new(config);
+
+    let out = engine.render(&input).expect("render ok");
+    let plain = String::from_utf8(strip_ansi_escapes::strip(out)).unwrap();
+    assert_eq!(plain, "5f2e9a");
+}
+
+#[test]
+fn engine_render_modules_returns_outputs_in_format_order() {
+    let json = r#"{
+        "session_id": "5f2e9a41-7c1d-4e0b",
+        "cwd": "/tmp",
+        "model": { "id": "claude-opus", "display_name": "Opus" },
+        "version": "1.0.80"
+    }"#;
+
+    let input = parse_claude_input(json).expect("valid input");
+    let config = Config {
+        format: "$cc_version $status $claude_session".to_string(),
+        ..Default::default()
+    };
+    let engine = claude_code_statusline_core::Engine::new(config);
+
+    let outputs = engine.render_modules(&input);
+    let names: Vec<&str> = outputs.iter().map(|(name, _)| name.as_str()).collect();
+    // `status` renders nothing without a nonzero exit status
+    assert_eq!(names, ["cc_version", "claude_session"]);
+    let plain = String::from_utf8(strip_ansi_escapes::strip(&outputs[1].1)).unwrap();
+    assert_eq!(plain, "5f2e9a");
+}
+
+mod custom_registry {
+    use claude_code_statusline_core::modules::{
+        EmptyConfig, Module, ModuleConfig, ModuleFactory, Registry,
+    };
+    use claude_code_statusline_core::{Config, Context, Engine, parse_claude_input};
+
+    struct MyModule;
+
+    impl Module for MyModule {
+        fn name(&self) -> &str {
+            "my_module"
+        }
+        fn should_display(&self, _context: &Context, _config: &dyn ModuleConfig) -> bool {
+            true
+        }
+        fn render(&self, context: &Context, _config: &dyn ModuleConfig) -> String {
+            format!("hello from {}", context.input.session_id)
+        }
+    }
+
+    struct MyFactory;
+
+    impl ModuleFactory for MyFactory {
+        fn name(&self) -> &'static str {
+            "my_module"
+        }
+        fn create(&self, _context: &Context) -> Box<dyn Module> {
+            Box::new(MyModule)
+        }
+        fn config<'a>(&self, _context: &'a Context) -> Option<&'a dyn ModuleConfig> {
+            Some(&EmptyConfig)
+        }
+    }
+
+    #[test]
+    fn engine_renders_module_from_custom_registry() {
+        let json = r#"{
+            "session_id": "abc123",
+            "cwd": "/tmp",
+            "model": { "id": "claude-opus", "display_name": "Opus" }
+        }"#;
+        let input = parse_claude_input(json).expect("valid input");
+        let config = Config {
+            format: "$claude_model | $my_module".to_string(),
+            ..Default::default()
+        };
+
+        let mut registry = Registry::with_defaults();
+        registry.register_factory(MyFactory);
+        let out = Engine::new_with_registry(config.clone(), registry)
+            .render(&input)
+            .expect("render ok");
+        let plain = String::from_utf8(strip_ansi_escapes::strip(out)).unwrap();
+        assert_eq!(plain, "Opus | hello from abc123");
+
+        // The default engine knows nothing about the custom module
+        let out = Engine::new(config).render(&input).expect("render ok");
+        let plain = String::from_utf8(strip_ansi_escapes::strip(out)).unwrap();
+        assert_eq!(plain, "Opus | $my_module");
+    }
+}
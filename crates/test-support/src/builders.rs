@@ -14,6 +14,7 @@ pub struct ClaudeInputBuilder {
     workspace: Option<WorkspaceInfo>,
     version: Option<String>,
     output_style: Option<OutputStyle>,
+    exit_status: Option<i32>,
 }
 
 #[allow(dead_code)]
@@ -34,6 +35,7 @@ impl ClaudeInputBuilder {
             }),
             version: Some("1.0.0".to_string()),
             output_style: None,
+            exit_status: None,
         }
     }
 
@@ -75,6 +77,28 @@ impl ClaudeInputBuilder {
         self
     }
 
+    pub fn with_version(mut self, version: &str) -> Self {
+        self.version = Some(version.to_string());
+        self
+    }
+
+    pub fn with_transcript(mut self, path: &str) -> Self {
+        self.transcript_path = Some(path.to_string());
+        self
+    }
+
+    pub fn with_output_style(mut self, name: &str) -> Self {
+        self.output_style = Some(OutputStyle {
+            name: name.to_string(),
+        });
+        self
+    }
+
+    pub fn with_exit_status(mut self, status: i32) -> Self {
+        self.exit_status = Some(status);
+        self
+    }
+
     pub fn build(self) -> ClaudeInput {
         ClaudeInput {
             hook_event_name: self.hook_event_name,
@@ -85,6 +109,7 @@ impl ClaudeInputBuilder {
             workspace: self.workspace,
             version: self.version,
             output_style: self.output_style,
+            exit_status: self.exit_status,
         }
     }
 }
@@ -151,3 +176,35 @@ impl Default for ContextBuilder {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_fully_populated_input() {
+        let input = ClaudeInputBuilder::new()
+            .with_session_id("s-1")
+            .with_cwd("/work/app")
+            .with_model_id("claude-opus-4-1", "Opus")
+            .with_version("1.0.80")
+            .with_transcript("/tmp/transcript.jsonl")
+            .with_output_style("explanatory")
+            .with_exit_status(2)
+            .build();
+
+        assert_eq!(input.session_id, "s-1");
+        assert_eq!(input.cwd, "/work/app");
+        assert_eq!(input.model.id, "claude-opus-4-1");
+        assert_eq!(input.version.as_deref(), Some("1.0.80"));
+        assert_eq!(
+            input.transcript_path.as_deref(),
+            Some("/tmp/transcript.jsonl")
+        );
+        assert_eq!(
+            input.output_style.map(|style| style.name).as_deref(),
+            Some("explanatory")
+        );
+        assert_eq!(input.exit_status, Some(2));
+    }
+}
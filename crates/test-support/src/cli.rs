@@ -65,7 +65,7 @@ format = "[🌿 $branch]($style)"
 style = "bold green"
 
 [git_status]
-format = "([[$all_status$ahead_behind]]($style) )"
+format = "([[$all_status$ahead_behind$clean]]($style) )"
 style = "bold red"
 
 [claude_model]
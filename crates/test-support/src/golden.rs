@@ -0,0 +1,116 @@
+//! Golden-file tests for the end-to-end rendering path
+//!
+//! A fixture is a directory containing:
+//!
+//! - `input.json`: Claude Code input fed to [`Engine::render`]
+//! - `expected.txt`: expected output with ANSI escapes stripped
+//! - `config.toml` (optional): configuration; defaults when absent
+//!
+//! ```no_run
+//! test_support::golden::assert_golden_dir("tests/golden");
+//! ```
+use claude_code_statusline_core::{Config, Engine, parse_claude_input};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// One discovered fixture directory
+pub struct GoldenCase {
+    pub name: String,
+    pub dir: PathBuf,
+}
+
+impl GoldenCase {
+    /// Render the fixture input and return the ANSI-stripped output
+    pub fn render(&self) -> String {
+        let input_path = self.dir.join("input.json");
+        let json = fs::read_to_string(&input_path)
+            .unwrap_or_else(|e| panic!("{}: {e}", input_path.display()));
+        let input =
+            parse_claude_input(&json).unwrap_or_else(|e| panic!("{}: {e}", input_path.display()));
+
+        let config_path = self.dir.join("config.toml");
+        let config: Config = if config_path.exists() {
+            let contents = fs::read_to_string(&config_path).unwrap();
+            Config::from_toml_str(&contents)
+                .unwrap_or_else(|e| panic!("{}: {e}", config_path.display()))
+        } else {
+            Config::default()
+        };
+
+        let rendered = Engine::new(config)
+            .render(&input)
+            .unwrap_or_else(|e| panic!("{}: render failed: {e}", self.name));
+        String::from_utf8(strip_ansi_escapes::strip(rendered)).unwrap()
+    }
+
+    /// Expected output (a single trailing newline is ignored)
+    pub fn expected(&self) -> String {
+        let path = self.dir.join("expected.txt");
+        let text = fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+        text.strip_suffix('\n').unwrap_or(&text).to_string()
+    }
+}
+
+/// Discover fixture directories under `root`, sorted by name
+///
+/// Every subdirectory containing an `input.json` is a fixture.
+pub fn discover(root: impl AsRef<Path>) -> Vec<GoldenCase> {
+    let root = root.as_ref();
+    let mut cases: Vec<GoldenCase> = fs::read_dir(root)
+        .unwrap_or_else(|e| panic!("{}: {e}", root.display()))
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|dir| dir.join("input.json").is_file())
+        .map(|dir| GoldenCase {
+            name: dir.file_name().unwrap().to_string_lossy().to_string(),
+            dir,
+        })
+        .collect();
+    cases.sort_by(|a, b| a.name.cmp(&b.name));
+    cases
+}
+
+/// Render every fixture under `root` and panic with diffs on mismatch
+pub fn assert_golden_dir(root: impl AsRef<Path>) {
+    let root = root.as_ref();
+    let cases = discover(root);
+    assert!(!cases.is_empty(), "no fixtures under {}", root.display());
+
+    let failures: Vec<String> = cases
+        .iter()
+        .filter_map(|case| {
+            let expected = case.expected();
+            let actual = case.render();
+            (actual != expected)
+                .then(|| format!("--- {} ---\n{}", case.name, line_diff(&expected, &actual)))
+        })
+        .collect();
+
+    assert!(
+        failures.is_empty(),
+        "{} of {} golden fixture(s) differ (- expected, + actual):\n{}",
+        failures.len(),
+        cases.len(),
+        failures.join("\n")
+    );
+}
+
+/// Minimal line diff: unchanged lines are indented, changes are `-`/`+`
+fn line_diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+    let mut out = String::new();
+    for i in 0..expected.len().max(actual.len()) {
+        match (expected.get(i), actual.get(i)) {
+            (Some(e), Some(a)) if e == a => out.push_str(&format!("  {e}\n")),
+            (e, a) => {
+                if let Some(e) = e {
+                    out.push_str(&format!("- {e:?}\n"));
+                }
+                if let Some(a) = a {
+                    out.push_str(&format!("+ {a:?}\n"));
+                }
+            }
+        }
+    }
+    out
+}
@@ -1,3 +1,4 @@
 pub mod builders;
 pub mod cli;
 pub mod fixtures;
+pub mod golden;